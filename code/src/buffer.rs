@@ -1,15 +1,25 @@
-use crate::catalog::Catalog;
+use crate::catalog::{Catalog, Table};
 use crate::disk::{read_page, write_page};
-use crate::page::{PAGE_SIZE, Page, init_page, page_free_space, ITEM_ID_SIZE, PAGE_HEADER_SIZE};
-use crate::table::TABLE_HEADER_SIZE;
+use crate::page::{Page, init_page, page_free_space, place_tuple, ITEM_ID_SIZE};
+use crate::constraint::ConstraintChecker;
+use crate::page::{page_count, page_tuples, truncate_uncommitted_pages};
+use crate::toast::{ToastFile, detoast_tuple, toast_tuple};
+use crate::csv_import::{CsvOptions, CsvReader, CsvRow, LoadReport};
+use crate::tuple::{decode_tuple, encode_tuple};
 
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::io::{Read, Seek, SeekFrom};
+
+use tracing::{debug, info, instrument};
+
+use crate::error::{Result, RookError};
 
 pub const EXTENT_SIZE: usize = 16;   // 16 data pages per extent
 
 pub struct BufferManager {
     pub pages: Vec<Page>, // In-memory pages (header + data)
+    pub loaded_table: Option<(String, String)>, // (database, table) whose pages are in the buffer
+    pub staged_toast: Option<ToastFile>, // TOAST chunks of the buffered rows, published by the next flush
 }
 
 impl BufferManager {
@@ -22,14 +32,54 @@ impl BufferManager {
         init_page(&mut header);
         pages.push(header);
 
-        println!("Buffer Manager initialized with header page only.");
+        debug!("Buffer Manager initialized with header page only.");
+
+        Self {
+            pages,
+            loaded_table: None,
+            staged_toast: None,
+        }
+    }
+
+    /// Discards every buffered page and goes back to a single empty header page.
+    fn reset(&mut self) {
+        let mut header = Page::new();
+        init_page(&mut header);
 
-        Self { pages }
+        self.pages.clear();
+        self.pages.push(header);
+        self.loaded_table = None;
+        if let Some(mut toast) = self.staged_toast.take() {
+            toast.discard();
+        }
+    }
+
+    /// Evicts the buffered pages if they belong to the given table.
+    /// Returns true if anything was evicted.
+    pub fn evict_table(&mut self, db_name: &str, table_name: &str) -> bool {
+        match &self.loaded_table {
+            Some((db, table)) if db == db_name && table == table_name => {
+                self.reset();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Evicts the buffered pages if they belong to any table of the given database.
+    /// Returns true if anything was evicted.
+    pub fn evict_database(&mut self, db_name: &str) -> bool {
+        match &self.loaded_table {
+            Some((db, _)) if db == db_name => {
+                self.reset();
+                true
+            }
+            _ => false,
+        }
     }
 
     /// Add a full extent (16 initialized data pages) AFTER header page.
     pub fn allocate_extent(&mut self) {
-        // println!("Allocating new EXTENT of {} pages...", EXTENT_SIZE);
 
         for _ in 0..EXTENT_SIZE {
             let mut page = Page::new();
@@ -39,19 +89,14 @@ impl BufferManager {
     }
 
     /// Loads table from disk into buffer
-    pub fn load_table_on_create(&mut self, db_name: &str, table_name: &str) -> io::Result<()> {
-        let table_path = format!("database/base/{}/{}.dat", db_name, table_name);
-        let mut file = File::open(&table_path)?;
-
-        // Get file size and total pages
-        let metadata = file.metadata()?;
-        let file_size = metadata.len();
-        let total_pages = (file_size as usize) / PAGE_SIZE;
-
-        println!(
-            "Loading table '{}' ({} bytes, {} pages)...",
-            table_name, file_size, total_pages
-        );
+    #[instrument(skip(self, catalog))]
+    pub fn load_table_on_create(&mut self, catalog: &Catalog, db_name: &str, table_name: &str) -> Result<()> {
+        let mut file = File::open(catalog.table_file(db_name, table_name)?)?;
+
+        // Only the pages counted in the header; anything after them was left by a load that never committed
+        let total_pages = page_count(&mut file)? as usize;
+
+        debug!(total_pages, "Loading table");
 
         self.pages.clear();
 
@@ -66,18 +111,15 @@ impl BufferManager {
             let mut page = Page::new();
             match read_page(&mut file, &mut page, page_num as u32) {
                 Ok(_) => self.pages.push(page),
-                Err(e) => {
-                    if e.kind() == ErrorKind::UnexpectedEof {
-                        break;
-                    } else {
-                        return Err(e);
-                    }
-                }
+                Err(RookError::NotFound { .. }) => break,
+                Err(e) => return Err(e),
             }
         }
 
+        self.loaded_table = Some((db_name.to_string(), table_name.to_string()));
+
         // Ensure buffer can grow when inserting
-        println!(
+        debug!(
             "Loaded {} pages (1 header + {} data).",
             self.pages.len(),
             self.pages.len().saturating_sub(1)
@@ -87,78 +129,122 @@ impl BufferManager {
     }
 
     /// Load CSV into memory using extent-based allocation
+    #[instrument(name = "csv_load", skip(self, catalog, options))]
     pub fn load_csv_into_pages(
         &mut self,
         catalog: &Catalog,
         db_name: &str,
         table_name: &str,
         csv_path: &str,
-    ) -> io::Result<usize> {
+        options: &CsvOptions,
+    ) -> Result<LoadReport> {
         // --- schema ---
-        let db = catalog.databases.get(db_name).ok_or_else(|| {
-            io::Error::new(io::ErrorKind::NotFound, format!("Database '{}' not found", db_name))
-        })?;
-        let table = db.tables.get(table_name).ok_or_else(|| {
-            io::Error::new(io::ErrorKind::NotFound, format!("Table '{}' not found", table_name))
-        })?;
-        let columns = &table.columns;
-
-        if columns.is_empty() {
-            return Err(io::Error::new(io::ErrorKind::InvalidData, "Table has no columns"));
+        let db = catalog
+            .databases
+            .get(db_name)
+            .ok_or_else(|| RookError::not_found("Database", db_name))?;
+        let table = db
+            .tables
+            .get(table_name)
+            .ok_or_else(|| RookError::not_found("Table", table_name))?;
+        if table.columns.is_empty() {
+            return Err(RookError::SchemaMismatch(format!("Table '{}' has no columns", table_name)));
         }
 
-        // --- read CSV ---
-        let csv_file = File::open(csv_path)?;
-        let reader = BufReader::new(csv_file);
-        let mut lines = reader.lines();
-        if let Some(Ok(_)) = lines.next() {} // skip header
+        // Make sure the buffer holds this table's committed pages, not another table's or an outdated copy
+        // (rows of an earlier load that was never flushed are dropped, with their TOAST chunks)
+        if let Some(mut toast) = self.staged_toast.take() {
+            toast.discard();
+        }
+        let committed_pages = page_count(&mut File::open(catalog.table_file(db_name, table_name)?)?)? as usize;
+        if self.loaded_table.as_ref() != Some(&(db_name.to_string(), table_name.to_string()))
+            || self.pages.len() != committed_pages
+        {
+            self.load_table_on_create(catalog, db_name, table_name)?;
+        }
 
-        let mut inserted_rows = 0usize;
-        let mut current_page_index = 1; // DATA pages start at index 1 (page 0 is header)
+        // --- read CSV (header matched to the columns) ---
+        let mut rows = CsvReader::open(csv_path, table, options)?;
 
-        // Ensure first extent exists
-        if self.pages.len() == 1 {
-            self.allocate_extent(); // pages[1..17]
+        let mut toast = ToastFile::staged(catalog.toast_file(db_name, table_name)?);
+
+        // Key constraints see every row already in the buffered pages
+        let mut checker = ConstraintChecker::new(table).map_err(RookError::InvalidDefinition)?;
+        checker.load_references(catalog, db_name, table_name)?;
+        if checker.has_indexes() {
+            for page in self.pages.iter().skip(1) {
+                for (_, tuple_data) in page_tuples(page) {
+                    let tuple_data = detoast_tuple(table, tuple_data, &mut toast)?;
+                    checker.index_row(&decode_tuple(table, &tuple_data)?);
+                }
+            }
         }
 
+        // New rows go into new extents after the committed pages, which are never rewritten
+        self.allocate_extent();
+
+        // Rows of a failed load must not stay in the buffer, where the next flush would write them
+        if let Err(e) = self.insert_rows(table, &mut rows, &mut checker, &mut toast, committed_pages) {
+            toast.discard();
+            self.reset();
+            return Err(e);
+        }
+        self.staged_toast = Some(toast);
+        let report = rows.finish()?;
+
+        // Empty pages at the end of the last extent are not published
+        while self.pages.len() > committed_pages && page_tuples(self.pages.last().unwrap()).is_empty() {
+            self.pages.pop();
+        }
+        let used_pages = self.pages.len();
+
+        // update header's page count (written to disk last, by flush_to_disk)
+        self.pages[0].data[0..4].copy_from_slice(&(used_pages as u32).to_le_bytes());
+
+        let new_pages = used_pages - committed_pages;
+        info!(
+            "Loaded {} rows into {} new data pages ({} extents): {}.",
+            report.rows_inserted,
+            new_pages,
+            new_pages.div_ceil(EXTENT_SIZE),
+            report
+        );
+
+        Ok(report)
+    }
+
+    /// Inserts the rows of a CSV file into the buffered pages, allocating extents as they fill up.
+    fn insert_rows(
+        &mut self,
+        table: &Table,
+        rows: &mut CsvReader,
+        checker: &mut ConstraintChecker,
+        toast: &mut ToastFile,
+        first_page: usize,
+    ) -> Result<()> {
+        let mut current_page_index = first_page; // First page of the new extents
+
         // --- iterate CSV ---
-        for (i, line) in lines.enumerate() {
-            let row = line?;
-            if row.trim().is_empty() {
-                continue;
-            }
+        while let Some(row) = rows.next() {
+            let CsvRow { row, values } = row?;
 
-            let values: Vec<&str> = row.split(',').map(|v| v.trim()).collect();
-            if values.len() != columns.len() {
-                println!(
-                    "Skipping row {}: expected {} columns, got {}",
-                    i + 1,
-                    columns.len(),
-                    values.len()
-                );
-                continue;
-            }
+            // --- check constraints, serialize ---
+            let values = match values.and_then(|values| checker.check_row(&values).map(|_| values)) {
+                Ok(values) => values,
+                Err(e) => {
+                    rows.reject(row, &e)?;
+                    continue;
+                }
+            };
 
-            // --- serialize row ---
-            let mut tuple_bytes: Vec<u8> = Vec::new();
-            for (val, col) in values.iter().zip(columns.iter()) {
-                match col.data_type.as_str() {
-                    "INT" => {
-                        let num: i32 = val.parse().unwrap_or_default();
-                        tuple_bytes.extend_from_slice(&num.to_le_bytes());
-                    }
-                    "TEXT" => {
-                        let mut t = val.as_bytes().to_vec();
-                        if t.len() > 10 {
-                            t.truncate(10);
-                        } else if t.len() < 10 {
-                            t.extend(vec![b' '; 10 - t.len()]);
-                        }
-                        tuple_bytes.extend_from_slice(&t);
-                    }
-                    _ => continue,
+            // --- move large values out of line (TOAST chunks are staged until the flush) ---
+            let tuple_bytes = match toast_tuple(table, encode_tuple(table, &values), toast) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    rows.reject(row, &e)?;
+                    continue;
                 }
-            }
+            };
 
             let tuple_len = tuple_bytes.len() as u32;
             let required = tuple_len + ITEM_ID_SIZE;
@@ -179,7 +265,7 @@ impl BufferManager {
                     current_page_index += 1;
 
                     // If we just crossed an extent boundary, allocate next extent
-                    if (current_page_index - 1) % EXTENT_SIZE == 0 {
+                    if (current_page_index - first_page).is_multiple_of(EXTENT_SIZE) {
                         self.allocate_extent();
                     }
 
@@ -187,59 +273,60 @@ impl BufferManager {
                 }
 
                 // ---- Insert the tuple ----
-                let mut lower = u32::from_le_bytes(page.data[0..4].try_into().unwrap());
-                let mut upper = u32::from_le_bytes(page.data[4..8].try_into().unwrap());
-
-                let start = upper - tuple_len;
-
-                page.data[start as usize..upper as usize].copy_from_slice(&tuple_bytes);
-
-                let item_id_pos = lower as usize;
-                page.data[item_id_pos..item_id_pos + 4].copy_from_slice(&start.to_le_bytes());
-                page.data[item_id_pos + 4..item_id_pos + 8]
-                    .copy_from_slice(&(tuple_len.to_le_bytes()));
+                place_tuple(page, &tuple_bytes);
 
-                lower += ITEM_ID_SIZE;
-                upper = start;
-
-                page.data[0..4].copy_from_slice(&lower.to_le_bytes());
-                page.data[4..8].copy_from_slice(&upper.to_le_bytes());
-
-                inserted_rows += 1;
+                // Only a stored row's keys count for the rows after it
+                checker.index_row(&values);
+                rows.accept();
                 break;
             }
         }
 
-        // number of used pages
-        let used_pages = self.pages.len();
-
-        // update header's page count
-        self.pages[0].data[0..4].copy_from_slice(&(used_pages as u32).to_le_bytes());
-
-        println!(
-            "Loaded {} rows into {} data pages ({} extents).",
-            inserted_rows,
-            used_pages - 1,
-            (used_pages - 1 + EXTENT_SIZE - 1) / EXTENT_SIZE
-        );
-
-        Ok(used_pages)
+        Ok(())
     }
 
-    /// Write buffer to disk
+    /// Write buffer to disk: the staged TOAST chunks, the pages after the committed ones, then the
+    /// header naming them. Until the header is written the new pages are invisible, so a load that
+    /// fails or crashes before that leaves the table as it was.
+    #[instrument(name = "flush", skip(self, catalog))]
     pub fn flush_to_disk(
         &mut self,
+        catalog: &Catalog,
         db_name: &str,
         table_name: &str,
         used_pages: usize,
-    ) -> io::Result<()> {
-        let path = format!("database/base/{}/{}.dat", db_name, table_name);
-        let mut file = File::options().write(true).open(&path)?;
+    ) -> Result<()> {
+        let mut file = File::options().read(true).write(true).open(catalog.table_file(db_name, table_name)?)?;
+        catalog.checkers.invalidate(catalog, db_name, table_name);
+        let toast_published = match self.staged_toast.as_mut() {
+            Some(toast) => toast.publish(),
+            None => Ok(()),
+        };
+        if let Err(e) = toast_published.and_then(|_| self.publish_pages(&mut file, used_pages)) {
+            // The buffer no longer matches the disk, and the half-written pages must go
+            self.reset();
+            let _ = truncate_uncommitted_pages(&mut file);
+            return Err(e);
+        }
+        self.staged_toast = None;
+        Ok(())
+    }
 
-        for (i, page) in self.pages.iter_mut().take(used_pages).enumerate() {
-            write_page(&mut file, page, i as u32)?;
+    fn publish_pages(&mut self, file: &mut File, used_pages: usize) -> Result<()> {
+        let committed_pages = page_count(file)? as usize;
+        truncate_uncommitted_pages(file)?; // Leftovers of an earlier load must not follow the new pages
+
+        // Step 1: Write the new pages after the committed ones and make them durable
+        for page_num in committed_pages..used_pages {
+            write_page(file, &mut self.pages[page_num], page_num as u32)?;
         }
+        file.sync_data()?;
+
+        // Step 2: Publish them with a single header write
+        write_page(file, &mut self.pages[0], 0)?;
+        file.sync_data()?;
 
+        debug!("Flushed {} new pages", used_pages.saturating_sub(committed_pages));
         Ok(())
     }
 
@@ -250,9 +337,16 @@ impl BufferManager {
         db_name: &str,
         table_name: &str,
         csv_path: &str,
-    ) -> io::Result<()> {
-        let used = self.load_csv_into_pages(catalog, db_name, table_name, csv_path)?;
-        self.flush_to_disk(db_name, table_name, used)?;
-        Ok(())
+        options: &CsvOptions,
+    ) -> Result<LoadReport> {
+        let report = self.load_csv_into_pages(catalog, db_name, table_name, csv_path, options)?;
+        self.flush_to_disk(catalog, db_name, table_name, self.pages.len())?;
+        Ok(report)
+    }
+}
+
+impl Default for BufferManager {
+    fn default() -> Self {
+        Self::new()
     }
 }
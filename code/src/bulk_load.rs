@@ -0,0 +1,344 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Instant;
+
+use csv::ByteRecord;
+use tracing::{info, instrument};
+
+use crate::buffer::EXTENT_SIZE;
+use crate::catalog::{Catalog, Table};
+use crate::constraint::ConstraintChecker;
+use crate::csv_import::{CsvOptions, CsvReader, LoadReport, RecordParser};
+use crate::disk::write_page;
+use crate::error::{Result, RookError};
+use crate::page::{
+    Page, init_page, page_count, page_free_space, page_tuples, place_tuple, publish_page_count,
+    truncate_uncommitted_pages, ITEM_ID_SIZE,
+};
+use crate::toast::{ToastFile, toast_tuple_shared};
+use crate::tuple::encode_tuple;
+use crate::value::Value;
+
+//-------------------------------------------
+// Parallel bulk load:
+// - The calling thread reads the CSV file in chunks of `CHUNK_ROWS` records and queues them.
+// - Workers parse and encode the rows of a chunk, move large values to the (shared, staged) TOAST
+//   file, compressing them without holding its lock, and pack the tuples into pages of their own,
+//   allocated an extent at a time. They check no constraint.
+// - The calling thread takes the chunks back in file order and runs the key and CHECK constraints
+//   row by row, so rows are checked and rejected exactly as a serial load would: with duplicate
+//   keys the first row in the file wins, and strict mode or `max_errors` aborts on the same record.
+//   The pages of a chunk are then appended after the committed pages; a chunk with rejected rows
+//   is packed again without them. Each chunk starts on a new page, so its last page may be part full.
+// - TOAST chunks of rows rejected by a constraint stay in the TOAST file; nothing points at them.
+// - Like the buffer loader, the new pages are published by a single header write at the end,
+//   right after the staged TOAST chunks; a load that fails or crashes before it leaves the table as it was.
+//-------------------------------------------
+
+pub const CHUNK_ROWS: usize = 10_000; // Records handed to a worker at a time
+
+/// Records of the CSV file, with their numbers, for one worker.
+struct Chunk {
+    seq: usize, // Position of the chunk in the file
+    records: Vec<(usize, ByteRecord)>,
+}
+
+/// A chunk parsed, TOASTed and packed into pages by a worker.
+struct Batch {
+    seq: usize,
+    rows: Vec<PackedRow>,
+    pages: Vec<Page>, // Tuples of the rows, in file order
+}
+
+/// One record with its values and where its tuple is in the batch pages, or why it cannot be stored.
+struct PackedRow {
+    row: usize,
+    record: ByteRecord, // Kept for the reject file
+    packed: Result<(Vec<Value>, TupleSlot)>,
+}
+
+/// Index of a page of a batch and item index of a tuple in it.
+type TupleSlot = (usize, u32);
+
+/// State every worker shares.
+struct Shared<'a> {
+    table: &'a Table,
+    parser: RecordParser<'a>,
+    toast: Mutex<ToastFile>,
+    cancelled: AtomicBool, // Set once the load fails, so queued chunks are skipped
+}
+
+/// Checks the packed rows in file order and appends the pages of the accepted ones.
+struct Appender<'a> {
+    checker: ConstraintChecker,
+    file: &'a mut File,
+    next_page: u32, // Page number of the next appended page
+}
+
+/// Packs tuples into pages, an extent at a time.
+struct ExtentWriter {
+    pages: Vec<Page>,
+    current: usize, // Page receiving tuples
+}
+
+/// Loads a CSV file into a table with `workers` threads parsing and encoding its rows.
+/// Returns the same report as the serial loaders; `rows_read` also counts the records
+/// already queued when a load aborts.
+#[instrument(name = "csv_load", skip(catalog, options))]
+pub fn load_csv_parallel(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    csv_path: &str,
+    options: &CsvOptions,
+    workers: usize,
+) -> Result<LoadReport> {
+    let start = Instant::now();
+    let table = catalog
+        .databases
+        .get(db_name)
+        .ok_or_else(|| RookError::not_found("Database", db_name))?
+        .tables
+        .get(table_name)
+        .ok_or_else(|| RookError::not_found("Table", table_name))?;
+    if table.columns.is_empty() {
+        return Err(RookError::SchemaMismatch(format!("Table '{}' has no columns", table_name)));
+    }
+
+    // Step 1: New pages go after the committed ones; leftovers of a load that never committed are dropped
+    let mut file = File::options().read(true).write(true).open(catalog.table_file(db_name, table_name)?)?;
+    truncate_uncommitted_pages(&mut file)?;
+    let committed_pages = page_count(&mut file)?;
+    catalog.checkers.invalidate(catalog, db_name, table_name);
+
+    // Step 2: Key constraints see the rows already stored
+    let mut rows = CsvReader::open(csv_path, table, options)?;
+    let checker = ConstraintChecker::for_table(catalog, db_name, table_name, &mut file)?;
+    let shared = Shared {
+        table,
+        parser: rows.parser(),
+        toast: Mutex::new(ToastFile::staged(catalog.toast_file(db_name, table_name)?)),
+        cancelled: AtomicBool::new(false),
+    };
+    let mut appender = Appender { checker, file: &mut file, next_page: committed_pages };
+
+    // Step 3: Parse, TOAST and pack on the workers, check and append here
+    let (chunk_tx, chunk_rx) = mpsc::sync_channel(workers.max(1) * 2);
+    let (batch_tx, batch_rx) = mpsc::channel();
+    let chunk_rx = Arc::new(Mutex::new(chunk_rx)); // Closed once every worker is gone
+    let appended = thread::scope(|scope| {
+        for _ in 0..workers.max(1) {
+            let (shared, chunk_rx, batch_tx) = (&shared, Arc::clone(&chunk_rx), batch_tx.clone());
+            scope.spawn(move || shared.work(&chunk_rx, batch_tx));
+        }
+        drop((chunk_rx, batch_tx));
+
+        let appended = append_batches(&mut rows, &mut appender, chunk_tx, &batch_rx, &shared.cancelled);
+        if appended.is_err() {
+            shared.cancelled.store(true, Ordering::Relaxed);
+        }
+        appended
+    });
+
+    // Step 4: Publish the TOAST chunks, then the new pages with a single header write, or drop both
+    let used_pages = appended.map(|_| appender.next_page);
+    let mut toast = shared.toast.into_inner().unwrap_or_else(PoisonError::into_inner);
+    let published = used_pages.and_then(|used_pages| {
+        toast.publish()?;
+        publish_page_count(&mut file, used_pages)
+    });
+    if let Err(e) = published {
+        toast.discard();
+        let _ = truncate_uncommitted_pages(&mut file);
+        return Err(e);
+    }
+    let report = rows.finish()?;
+
+    let elapsed = start.elapsed();
+    info!(
+        "Loaded {} rows with {} workers in {:.3} seconds ({:.0} rows/s, {:.2} MB/s): {}.",
+        report.rows_inserted,
+        workers,
+        elapsed.as_secs_f64(),
+        report.rows_per_sec(elapsed),
+        report.mb_per_sec(elapsed),
+        report
+    );
+    Ok(report)
+}
+
+/// Queues the records of the file in chunks and appends the batches of the workers in file order.
+fn append_batches(
+    rows: &mut CsvReader,
+    appender: &mut Appender,
+    chunk_tx: SyncSender<Chunk>,
+    batch_rx: &Receiver<Batch>,
+    cancelled: &AtomicBool,
+) -> Result<()> {
+    let mut pending = BTreeMap::new(); // Batches that finished before an earlier one
+    let mut next_seq = 0;
+    let mut append = |batch: Batch, rows: &mut CsvReader| -> Result<()> {
+        pending.insert(batch.seq, batch);
+        while let Some(batch) = pending.remove(&next_seq) {
+            appender.append(batch, rows)?;
+            next_seq += 1;
+        }
+        Ok(())
+    };
+
+    // Step 1: Queue chunks, appending the batches already done between them
+    let mut seq = 0;
+    let mut end_of_file = false;
+    while !end_of_file && !cancelled.load(Ordering::Relaxed) {
+        let mut records = Vec::with_capacity(CHUNK_ROWS);
+        while records.len() < CHUNK_ROWS {
+            match rows.next_record() {
+                Some(record) => records.push(record?),
+                None => {
+                    end_of_file = true;
+                    break;
+                }
+            }
+        }
+        if !records.is_empty() {
+            // Sending fails only once every worker has stopped (a worker panicked)
+            if chunk_tx.send(Chunk { seq, records }).is_err() {
+                cancelled.store(true, Ordering::Relaxed);
+            }
+            seq += 1;
+        }
+        while let Ok(batch) = batch_rx.try_recv() {
+            append(batch, rows)?;
+        }
+    }
+
+    // Step 2: No more chunks; wait for the rest
+    drop(chunk_tx);
+    for batch in batch_rx {
+        append(batch, rows)?;
+    }
+    if cancelled.load(Ordering::Relaxed) {
+        return Err(io::Error::other("Load workers stopped before the end of the file").into());
+    }
+    Ok(())
+}
+
+impl Shared<'_> {
+    /// Worker loop: turns queued chunks into batches until the queue is closed.
+    fn work(&self, chunk_rx: &Mutex<Receiver<Chunk>>, batch_tx: Sender<Batch>) {
+        loop {
+            // A worker that panicked while waiting leaves the queue itself intact
+            let chunk = chunk_rx.lock().unwrap_or_else(PoisonError::into_inner).recv();
+            let Ok(chunk) = chunk else {
+                return;
+            };
+            if self.cancelled.load(Ordering::Relaxed) {
+                continue; // Keep draining, so the reader never waits on a full queue
+            }
+            let mut writer = ExtentWriter::new();
+            let rows = chunk
+                .records
+                .into_iter()
+                .map(|(row, record)| {
+                    let packed = self.parser.parse(&record).and_then(|values| {
+                        let tuple_bytes = encode_tuple(self.table, &values);
+                        let tuple_bytes = toast_tuple_shared(self.table, tuple_bytes, &self.toast)?;
+                        Ok((values, writer.place(&tuple_bytes)?))
+                    });
+                    PackedRow { row, record, packed }
+                })
+                .collect();
+            if batch_tx.send(Batch { seq: chunk.seq, rows, pages: writer.finish() }).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Appender<'_> {
+    /// Checks the rows of a batch, counting them in the report, and appends the batch pages
+    /// without the tuples of the rejected rows.
+    fn append(&mut self, batch: Batch, rows: &mut CsvReader) -> Result<()> {
+        let mut rejected = HashSet::new();
+        for PackedRow { row, record, packed } in batch.rows {
+            let checked = packed.and_then(|(values, slot)| match self.checker.check_row(&values) {
+                Ok(()) => Ok(values),
+                Err(e) => {
+                    rejected.insert(slot);
+                    Err(e)
+                }
+            });
+            match checked {
+                Ok(values) => {
+                    // Only a stored row's keys count for the rows after it
+                    self.checker.index_row(&values);
+                    rows.accept();
+                }
+                Err(e) => rows.reject_record(row, &e, &record)?,
+            }
+        }
+
+        let pages = if rejected.is_empty() { batch.pages } else { repack(&batch.pages, &rejected)? };
+        for mut page in pages {
+            write_page(self.file, &mut page, self.next_page)?;
+            self.next_page += 1;
+        }
+        Ok(())
+    }
+}
+
+impl ExtentWriter {
+    fn new() -> Self {
+        Self { pages: new_extent(), current: 0 }
+    }
+
+    /// Adds a tuple to the first page with room from the current one on, allocating another
+    /// extent when all are full, and returns its slot. The tuple must fit an empty page
+    /// (`toast_tuple` guarantees it).
+    fn place(&mut self, tuple_bytes: &[u8]) -> Result<TupleSlot> {
+        let required = tuple_bytes.len() as u32 + ITEM_ID_SIZE;
+        while page_free_space(&self.pages[self.current])? < required {
+            self.current += 1;
+            if self.current == self.pages.len() {
+                self.pages.extend(new_extent());
+            }
+        }
+        Ok((self.current, place_tuple(&mut self.pages[self.current], tuple_bytes)))
+    }
+
+    /// The pages that hold tuples.
+    fn finish(mut self) -> Vec<Page> {
+        let used = self.pages.iter().take_while(|page| !page_tuples(page).is_empty()).count();
+        self.pages.truncate(used);
+        self.pages
+    }
+}
+
+/// Packs the tuples of a batch again, leaving out the ones at `removed`.
+fn repack(pages: &[Page], removed: &HashSet<TupleSlot>) -> Result<Vec<Page>> {
+    let mut writer = ExtentWriter::new();
+    for (index, page) in pages.iter().enumerate() {
+        for (item, tuple_bytes) in page_tuples(page) {
+            if !removed.contains(&(index, item)) {
+                writer.place(tuple_bytes)?;
+            }
+        }
+    }
+    Ok(writer.finish())
+}
+
+/// A full extent of initialized pages.
+fn new_extent() -> Vec<Page> {
+    (0..EXTENT_SIZE)
+        .map(|_| {
+            let mut page = Page::new();
+            init_page(&mut page);
+            page
+        })
+        .collect()
+}
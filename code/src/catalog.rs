@@ -1,288 +1,1862 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs::{self, OpenOptions};
-use std::path::Path;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-pub const DATA_DIR: &str = "database"; // Root directory for all storage
-pub const CATALOG_DIR: &str = "database/global"; // Catalog metadata directory
-pub const CATALOG_FILE: &str = "database/global/catalog.json"; // Global catalog file
-pub const DATABASE_DIR: &str = "database/base"; // Root directory for all databases
-pub const TABLE_DIR_TEMPLATE: &str = "database/base/{database}"; // Directory for specific database
-pub const TABLE_FILE_TEMPLATE: &str = "database/base/{database}/{table}.dat"; // File path for specific table
+use tracing::{debug, info, warn};
 
-use crate::table::init_table;
+use crate::buffer::BufferManager;
+use crate::config::{DROPPED_SUFFIX, GENERATION_DIR_PREFIX, StorageConfig, tombstone_path};
+use crate::constraint::{CheckerCache, ConstraintChecker};
+use crate::error::{self, RookError};
+use crate::expr::Expr;
+use crate::page::{scan_tuples, truncate_uncommitted_pages};
+use crate::stats::{TableStats, collect_stats};
+use crate::system_catalog::{read_system_tables, write_system_tables};
+use crate::table::{check_file_format, init_table};
+use crate::tuple::MAX_SCHEMA_VERSION;
+use crate::value::{fixed_size, parse_value};
 
-#[derive(Serialize, Deserialize)]
+/// Column data types supported by the storage layer.
+/// Stored in the catalog file by their SQL name (e.g. "INT", "DECIMAL(10,2)").
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub enum DataType {
+    SmallInt, // i16
+    Int,      // i32
+    BigInt,   // i64
+    Float,    // f32
+    Double,   // f64
+    Boolean,
+    Decimal { precision: u8, scale: u8 }, // exact, stored as a scaled i128
+    Date,        // days since 1970-01-01
+    Time,        // microseconds since midnight
+    Timestamp,   // microseconds since the epoch, no zone
+    TimestampTz, // microseconds since the epoch, UTC
+    Interval,    // months + days + microseconds
+    Text,          // variable length, unlimited
+    Varchar(u32),  // variable length, at most n characters
+    Char(u32),     // exactly n characters, blank padded
+    Bytea,         // variable length binary
+    Json,          // validated JSON document, stored in binary form (JSONB)
+}
+
+/// Object identifier of a database or table. Files are named by OID, never by name,
+/// so renames do not touch files and names never become paths.
+pub type Oid = u32;
+pub const FIRST_OID: Oid = 16384; // First OID handed out; lower OIDs are reserved
+pub const MAX_IDENTIFIER_LENGTH: usize = 63; // Longest database, table, column or constraint name (in bytes)
+
+pub const DECIMAL_MAX_PRECISION: u8 = 38; // Largest precision that fits an i128
+pub const MAX_CHAR_LENGTH: u32 = 10_485_760; // Largest n accepted for VARCHAR(n) / CHAR(n)
+
+impl DataType {
+    /// SQL names accepted when creating tables.
+    pub const SUPPORTED: &'static [&'static str] = &[
+        "SMALLINT",
+        "INT",
+        "BIGINT",
+        "FLOAT",
+        "DOUBLE",
+        "BOOLEAN",
+        "DECIMAL(p,s)",
+        "DATE",
+        "TIME",
+        "TIMESTAMP",
+        "TIMESTAMPTZ",
+        "INTERVAL",
+        "TEXT",
+        "VARCHAR(n)",
+        "CHAR(n)",
+        "BYTEA",
+        "JSON",
+    ];
+
+    /// Parses a SQL type name (case-insensitive), e.g. "bigint" or "DECIMAL(10, 2)".
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let unsupported = || {
+            format!(
+                "Unsupported data type '{}'. Supported types: {}",
+                name.trim(),
+                DataType::SUPPORTED.join(", ")
+            )
+        };
+
+        // Normalize case and whitespace: "double   precision" -> "DOUBLE PRECISION"
+        let normalized = name
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_ascii_uppercase();
+
+        // Split "NAME(args)" into the base name and its arguments
+        let (base, args) = match normalized.split_once('(') {
+            Some((base, rest)) => {
+                let args = rest.strip_suffix(')').ok_or_else(unsupported)?;
+                let args = args
+                    .split(',')
+                    .map(|arg| arg.trim().parse::<u32>().map_err(|_| unsupported()))
+                    .collect::<Result<Vec<u32>, String>>()?;
+                (base.trim(), Some(args))
+            }
+            None => (normalized.as_str(), None),
+        };
+
+        let decimal = |precision: u32, scale: u32| {
+            if precision == 0 || precision > DECIMAL_MAX_PRECISION as u32 || scale > precision {
+                return Err(format!(
+                    "Invalid DECIMAL({},{}): precision must be 1..={} and scale at most the precision",
+                    precision, scale, DECIMAL_MAX_PRECISION
+                ));
+            }
+            Ok(DataType::Decimal {
+                precision: precision as u8,
+                scale: scale as u8,
+            })
+        };
+
+        let data_type = match (base, args.as_deref()) {
+            ("SMALLINT" | "INT2", None) => DataType::SmallInt,
+            ("INT" | "INTEGER" | "INT4", None) => DataType::Int,
+            ("BIGINT" | "INT8", None) => DataType::BigInt,
+            ("FLOAT" | "REAL" | "FLOAT4", None) => DataType::Float,
+            ("DOUBLE" | "DOUBLE PRECISION" | "FLOAT8", None) => DataType::Double,
+            ("BOOLEAN" | "BOOL", None) => DataType::Boolean,
+            ("DECIMAL" | "NUMERIC", None) => decimal(DECIMAL_MAX_PRECISION as u32, 0)?,
+            ("DECIMAL" | "NUMERIC", Some([precision])) => decimal(*precision, 0)?,
+            ("DECIMAL" | "NUMERIC", Some([precision, scale])) => decimal(*precision, *scale)?,
+            ("DATE", None) => DataType::Date,
+            ("TIME" | "TIME WITHOUT TIME ZONE", None) => DataType::Time,
+            ("TIMESTAMP" | "TIMESTAMP WITHOUT TIME ZONE" | "DATETIME", None) => DataType::Timestamp,
+            ("TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE", None) => DataType::TimestampTz,
+            ("INTERVAL", None) => DataType::Interval,
+            ("TEXT" | "VARCHAR" | "CHARACTER VARYING", None) => DataType::Text,
+            ("VARCHAR" | "CHARACTER VARYING", Some([length])) => DataType::Varchar(*length),
+            ("CHAR" | "CHARACTER", None) => DataType::Char(1),
+            ("CHAR" | "CHARACTER", Some([length])) => DataType::Char(*length),
+            ("BYTEA" | "BLOB", None) => DataType::Bytea,
+            ("JSON" | "JSONB", None) => DataType::Json,
+            _ => return Err(unsupported()),
+        };
+
+        if let DataType::Varchar(length) | DataType::Char(length) = data_type
+            && (length == 0 || length > MAX_CHAR_LENGTH)
+        {
+            return Err(format!(
+                "Invalid length {} for {}: must be 1..={}",
+                length, base, MAX_CHAR_LENGTH
+            ));
+        }
+
+        Ok(data_type)
+    }
+}
+
+impl fmt::Display for StorageStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageStrategy::Plain => write!(f, "PLAIN"),
+            StorageStrategy::Main => write!(f, "MAIN"),
+            StorageStrategy::External => write!(f, "EXTERNAL"),
+            StorageStrategy::Extended => write!(f, "EXTENDED"),
+        }
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::SmallInt => write!(f, "SMALLINT"),
+            DataType::Int => write!(f, "INT"),
+            DataType::BigInt => write!(f, "BIGINT"),
+            DataType::Float => write!(f, "FLOAT"),
+            DataType::Double => write!(f, "DOUBLE"),
+            DataType::Boolean => write!(f, "BOOLEAN"),
+            DataType::Decimal { precision, scale } => write!(f, "DECIMAL({},{})", precision, scale),
+            DataType::Date => write!(f, "DATE"),
+            DataType::Time => write!(f, "TIME"),
+            DataType::Timestamp => write!(f, "TIMESTAMP"),
+            DataType::TimestampTz => write!(f, "TIMESTAMPTZ"),
+            DataType::Interval => write!(f, "INTERVAL"),
+            DataType::Text => write!(f, "TEXT"),
+            DataType::Varchar(length) => write!(f, "VARCHAR({})", length),
+            DataType::Char(length) => write!(f, "CHAR({})", length),
+            DataType::Bytea => write!(f, "BYTEA"),
+            DataType::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+impl TryFrom<String> for DataType {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        DataType::parse(&name)
+    }
+}
+
+impl From<DataType> for String {
+    fn from(data_type: DataType) -> Self {
+        data_type.to_string()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Column {
     pub name: String,
-    pub data_type: String,
+    pub data_type: DataType,
+    #[serde(default)]
+    pub id: u32, // Stable column id (survives renames), 0 = not assigned yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>, // DEFAULT: used for empty/missing fields and rows written before the column existed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub not_null: bool, // NOT NULL: empty fields without a default are rejected instead of stored as NULL
+    #[serde(default)]
+    pub storage: StorageStrategy, // How large values of a variable-length column may be stored
 }
 
-#[derive(Serialize, Deserialize)]
+/// How large variable-length values of a column may be stored (as in PostgreSQL's SET STORAGE).
+/// Fixed-length columns are always stored plain.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StorageStrategy {
+    Plain,    // Inline and uncompressed only
+    Main,     // Compressed inline; moved out of line only if the row cannot fit a page otherwise
+    External, // Moved out of line uncompressed (fast substring access)
+    #[default]
+    Extended, // Compressed first, then moved out of line if still too large
+}
+
+impl StorageStrategy {
+    /// Parses a strategy name (case-insensitive).
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "PLAIN" => Ok(StorageStrategy::Plain),
+            "MAIN" => Ok(StorageStrategy::Main),
+            "EXTERNAL" => Ok(StorageStrategy::External),
+            "EXTENDED" => Ok(StorageStrategy::Extended),
+            _ => Err(format!(
+                "Unknown storage strategy '{}'. Expected PLAIN, MAIN, EXTERNAL or EXTENDED",
+                name.trim()
+            )),
+        }
+    }
+
+    /// Whether values may be compressed.
+    pub fn allows_compression(self) -> bool {
+        matches!(self, StorageStrategy::Main | StorageStrategy::Extended)
+    }
+}
+
+impl Column {
+    pub fn new(name: &str, data_type: DataType) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type,
+            id: 0,
+            default: None,
+            not_null: false,
+            storage: StorageStrategy::default(),
+        }
+    }
+}
+
+/// Table-level constraint. Columns are referenced by name and kept up to date on renames.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Constraint {
+    PrimaryKey { name: String, columns: Vec<String> }, // Unique and NOT NULL; at most one per table
+    Unique { name: String, columns: Vec<String> },     // Rows with a NULL key column never conflict
+    Check { name: String, expression: String },        // Rows are rejected when the expression is FALSE
+    ForeignKey {
+        name: String,
+        columns: Vec<String>,
+        ref_table: String,        // Parent table in the same database
+        ref_columns: Vec<String>, // PRIMARY KEY or UNIQUE columns of the parent
+        #[serde(default)]
+        on_delete: ReferentialAction,
+    },
+}
+
+/// What happens to child rows when the parent row they reference is deleted.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReferentialAction {
+    #[default]
+    Restrict, // The delete fails
+    Cascade,  // Child rows are deleted too
+    SetNull,  // The child's foreign key columns become NULL
+}
+
+impl ReferentialAction {
+    /// Parses an action name (case-insensitive). NO ACTION behaves like RESTRICT.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let words: Vec<String> = name.split_whitespace().map(|w| w.to_ascii_uppercase()).collect();
+        match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+            ["RESTRICT"] | ["NO", "ACTION"] => Ok(ReferentialAction::Restrict),
+            ["CASCADE"] => Ok(ReferentialAction::Cascade),
+            ["SET", "NULL"] => Ok(ReferentialAction::SetNull),
+            _ => Err(format!(
+                "Unknown referential action '{}'. Expected RESTRICT, CASCADE or SET NULL",
+                name.trim()
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReferentialAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReferentialAction::Restrict => "RESTRICT",
+            ReferentialAction::Cascade => "CASCADE",
+            ReferentialAction::SetNull => "SET NULL",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Constraint {
+    pub fn name(&self) -> &str {
+        match self {
+            Constraint::PrimaryKey { name, .. }
+            | Constraint::Unique { name, .. }
+            | Constraint::Check { name, .. }
+            | Constraint::ForeignKey { name, .. } => name,
+        }
+    }
+
+    /// Columns the constraint depends on.
+    pub fn columns(&self) -> Vec<String> {
+        match self {
+            Constraint::PrimaryKey { columns, .. }
+            | Constraint::Unique { columns, .. }
+            | Constraint::ForeignKey { columns, .. } => columns.clone(),
+            Constraint::Check { expression, .. } => Expr::parse(expression)
+                .map(|expr| expr.columns().into_iter().map(str::to_string).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::PrimaryKey { name, columns } => {
+                write!(f, "{} PRIMARY KEY ({})", name, columns.join(", "))
+            }
+            Constraint::Unique { name, columns } => {
+                write!(f, "{} UNIQUE ({})", name, columns.join(", "))
+            }
+            Constraint::Check { name, expression } => write!(f, "{} CHECK ({})", name, expression),
+            Constraint::ForeignKey { name, columns, ref_table, ref_columns, on_delete } => write!(
+                f,
+                "{} FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE {}",
+                name,
+                columns.join(", "),
+                ref_table,
+                ref_columns.join(", "),
+                on_delete
+            ),
+        }
+    }
+}
+
+/// Column layout of a table at one schema version.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SchemaVersion {
+    pub version: u32,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Table {
+    #[serde(default)]
+    pub oid: Oid, // Names the table file, 0 = not assigned yet
     pub columns: Vec<Column>,
+    #[serde(default)]
+    pub schema_version: u32, // Version stamped into every tuple written with `columns`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schema_history: Vec<SchemaVersion>, // Layouts of all previous versions
+    #[serde(default)]
+    pub next_column_id: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<Constraint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<TableStats>, // Collected by the last ANALYZE, None if never analyzed
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        let mut table = Self {
+            oid: 0,
+            columns,
+            schema_version: 0,
+            schema_history: Vec::new(),
+            next_column_id: 0,
+            constraints: Vec::new(),
+            stats: None,
+        };
+        table.assign_column_ids();
+        table
+    }
+
+    /// Gives every column a stable id. Catalogs written before ALTER TABLE existed
+    /// have no ids, so they are assigned by position (their tuples only have one layout).
+    pub fn assign_column_ids(&mut self) {
+        if self.next_column_id == 0 {
+            self.next_column_id = 1;
+        }
+        for column in self.columns.iter_mut() {
+            if column.id == 0 {
+                column.id = self.next_column_id;
+                self.next_column_id += 1;
+            }
+        }
+    }
+
+    /// Returns the columns of the given schema version, if the version is known.
+    pub fn columns_for_version(&self, version: u32) -> Option<&[Column]> {
+        if version == self.schema_version {
+            return Some(&self.columns);
+        }
+        self.schema_history
+            .iter()
+            .find(|schema| schema.version == version)
+            .map(|schema| schema.columns.as_slice())
+    }
+
+    /// Archives the current layout and starts a new schema version.
+    /// Tuples store the version in 16 bits, so a table cannot go past `MAX_SCHEMA_VERSION`.
+    fn bump_schema_version(&mut self) -> error::Result<()> {
+        if self.schema_version >= MAX_SCHEMA_VERSION {
+            return Err(RookError::InvalidDefinition(format!(
+                "table has reached the maximum of {} schema versions",
+                MAX_SCHEMA_VERSION
+            )));
+        }
+        self.schema_history.push(SchemaVersion {
+            version: self.schema_version,
+            columns: self.columns.clone(),
+        });
+        self.schema_version += 1;
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Database {
+    #[serde(default)]
+    pub oid: Oid, // Names the database directory, 0 = not assigned yet
     pub tables: HashMap<String, Table>,
 }
 
+impl Database {
+    /// Foreign keys (with the name of their child table) that reference the given table.
+    pub fn foreign_keys_to(&self, table_name: &str) -> Vec<(&str, &Constraint)> {
+        let mut references = Vec::new();
+        for (child_name, child) in &self.tables {
+            for constraint in &child.constraints {
+                if let Constraint::ForeignKey { ref_table, .. } = constraint
+                    && ref_table == table_name
+                {
+                    references.push((child_name.as_str(), constraint));
+                }
+            }
+        }
+        references
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Catalog {
-    pub databases: HashMap<String, Database>,
+    pub databases: HashMap<String, Database>, // Name -> database (and through it, name -> OID)
+    #[serde(default)]
+    pub next_oid: Oid, // Next OID to hand out, persisted in the control file
+    #[serde(skip)]
+    pub config: StorageConfig, // Data directory the catalog was loaded from and is saved to
+    #[serde(skip)]
+    pub checkers: CheckerCache, // Constraint checkers of recently written tables
 }
 
-pub fn init_catalog() {
-    let catalog_path = Path::new(CATALOG_FILE);
+impl Catalog {
+    /// A catalog without databases, stored under the given data directory.
+    pub fn new(config: &StorageConfig) -> Self {
+        Self {
+            databases: HashMap::new(),
+            next_oid: FIRST_OID,
+            config: config.clone(),
+            checkers: CheckerCache::default(),
+        }
+    }
+
+    /// Hands out a new OID. OIDs are never reused.
+    pub fn allocate_oid(&mut self) -> Oid {
+        self.next_oid = self.next_oid.max(FIRST_OID);
+        let oid = self.next_oid;
+        self.next_oid += 1;
+        oid
+    }
 
-    // Step 1: Create necessary directories if not exist
-    if let Some(parent) = catalog_path.parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent).expect("Failed to create catalog directory");
+    /// Gives every database and table without an OID (catalogs written before OIDs existed) a new one.
+    fn assign_missing_oids(&mut self) {
+        let mut names: Vec<String> = self.databases.keys().cloned().collect();
+        names.sort();
+        for db_name in names {
+            if self.databases[&db_name].oid == 0 {
+                let oid = self.allocate_oid();
+                self.databases.get_mut(&db_name).unwrap().oid = oid;
+            }
+            let mut table_names: Vec<String> = self.databases[&db_name].tables.keys().cloned().collect();
+            table_names.sort();
+            for table_name in table_names {
+                if self.databases[&db_name].tables[&table_name].oid == 0 {
+                    let oid = self.allocate_oid();
+                    self.databases.get_mut(&db_name).unwrap().tables.get_mut(&table_name).unwrap().oid = oid;
+                }
+            }
         }
     }
 
-    let base_dir = Path::new(DATABASE_DIR);
-    if !base_dir.exists() {
-        fs::create_dir_all(base_dir).expect("Failed to create base data directory");
+    /// Looks up the OIDs of a database and one of its tables by name.
+    pub fn table_oids(&self, db_name: &str, table_name: &str) -> error::Result<(Oid, Oid)> {
+        let database = self
+            .databases
+            .get(db_name)
+            .ok_or_else(|| RookError::not_found("Database", db_name))?;
+        let table = database
+            .tables
+            .get(table_name)
+            .ok_or_else(|| RookError::not_found("Table", table_name))?;
+        Ok((database.oid, table.oid))
     }
 
-    // Step 2: Create catalog.json if not exist
-    if !catalog_path.exists() {
-        let empty_catalog = Catalog {
-            databases: HashMap::new(),
-        };
-        let json = serde_json::to_string_pretty(&empty_catalog)
-            .expect("Failed to serialize empty catalog");
-        fs::write(catalog_path, json).expect("Failed to write catalog file");
-        println!(
-            "Catalog file not found. Created new catalog file at {}",
-            catalog_path.display()
+    /// Directory of a database.
+    pub fn database_dir(&self, db_name: &str) -> error::Result<PathBuf> {
+        let database = self
+            .databases
+            .get(db_name)
+            .ok_or_else(|| RookError::not_found("Database", db_name))?;
+        Ok(self.config.table_dir(database.oid))
+    }
+
+    /// Data file of a table.
+    pub fn table_file(&self, db_name: &str, table_name: &str) -> error::Result<PathBuf> {
+        let (db_oid, table_oid) = self.table_oids(db_name, table_name)?;
+        Ok(self.config.table_file(db_oid, table_oid))
+    }
+
+    /// TOAST file of a table.
+    pub fn toast_file(&self, db_name: &str, table_name: &str) -> error::Result<PathBuf> {
+        let (db_oid, table_oid) = self.table_oids(db_name, table_name)?;
+        Ok(self.config.toast_file(db_oid, table_oid))
+    }
+}
+
+/// Checks a database, table, column or constraint name: 1 to `MAX_IDENTIFIER_LENGTH` ASCII letters,
+/// digits and underscores, not starting with a digit. `kind` names the object in the error.
+pub fn validate_identifier(kind: &str, name: &str) -> error::Result<()> {
+    if name.is_empty() {
+        return Err(RookError::InvalidName(format!("{} name cannot be empty", kind)));
+    }
+    if name.len() > MAX_IDENTIFIER_LENGTH {
+        return Err(RookError::InvalidName(format!(
+            "{} name '{}' is longer than {} characters",
+            kind, name, MAX_IDENTIFIER_LENGTH
+        )));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(RookError::InvalidName(format!(
+            "{} name '{}' may only contain letters, digits and underscores",
+            kind, name
+        )));
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(RookError::InvalidName(format!(
+            "{} name '{}' cannot start with a digit",
+            kind, name
+        )));
+    }
+    Ok(())
+}
+
+/// Creates the data directories and the catalog system tables if they do not exist yet.
+/// A `catalog.json` from before system tables existed is migrated once and renamed to
+/// `CATALOG_MIGRATED_FILE`; otherwise an empty catalog is written.
+pub fn bootstrap_catalog(config: &StorageConfig) -> error::Result<()> {
+    // Step 1: Create necessary directories if not exist
+    fs::create_dir_all(config.catalog_dir())?;
+    fs::create_dir_all(config.database_dir())?;
+
+    // Step 2: A leftover control temp file is an unfinished save
+    let _ = fs::remove_file(config.control_temp_file());
+
+    // Step 3: Create the system tables, from catalog.json if there is one
+    let catalog_file = config.catalog_file();
+    if config.control_file().exists() {
+        let generation = current_generation(config)?;
+        remove_generations(config, |g| g > generation)?; // Written by saves that never switched over
+        warn!("Catalog already exists at {}", config.generation_dir(generation).display());
+    } else if !catalog_generations(config)?.is_empty() {
+        return Err(RookError::Corruption {
+            file: config.control_file(),
+            page: None,
+            message: format!(
+                "missing, but catalog generations exist in {}; restore it instead of starting with an empty catalog",
+                config.catalog_dir().display()
+            ),
+        });
+    } else if catalog_file.exists() {
+        let data = fs::read_to_string(&catalog_file)?;
+        let mut catalog = serde_json::from_str::<Catalog>(&data).map_err(|e| RookError::Corruption {
+            file: catalog_file.clone(),
+            page: None,
+            message: format!("cannot migrate: {}", e),
+        })?;
+        catalog.config = config.clone();
+        catalog.next_oid = FIRST_OID;
+        catalog.assign_missing_oids(); // Deterministic, so a migration interrupted here is simply redone
+        move_to_oid_paths(&catalog)?;
+        save_catalog(&catalog)?;
+        fs::rename(&catalog_file, config.catalog_migrated_file())?;
+        sync_directory(&config.catalog_dir())?;
+        info!(
+            "Migrated {} into system tables (the old file is kept at {})",
+            catalog_file.display(),
+            config.catalog_migrated_file().display()
         );
     } else {
-        println!("Catalog file already exists at {}", catalog_path.display());
+        save_catalog(&Catalog::new(config))?;
+        info!(
+            "Catalog not found. Created new system tables in {}",
+            config.catalog_dir().display()
+        );
+    }
+
+    // Step 4: Finish or roll back drops and bulk loads interrupted by a crash.
+    // Without a readable catalog nothing is known to be dropped, so tombstones are left alone.
+    match load_catalog(config) {
+        Ok(catalog) => {
+            recover_dropped_objects(&catalog);
+            recover_uncommitted_pages(&catalog);
+        }
+        Err(e) => warn!("Skipping recovery of dropped objects: {}", e),
     }
+    Ok(())
 }
 
-pub fn load_catalog() -> Catalog {
-    let catalog_path = Path::new(CATALOG_FILE);
+/// Moves the name-based files of a migrated `catalog.json` (`base/{database}/{table}.dat`
+/// and `.toast`) to their OID paths. Files that are already moved are skipped.
+fn move_to_oid_paths(catalog: &Catalog) -> io::Result<()> {
+    let config = &catalog.config;
+    for (db_name, database) in &catalog.databases {
+        let old_dir = config.database_dir().join(db_name);
+        let new_dir = config.table_dir(database.oid);
+        if old_dir.is_dir() && !new_dir.exists() {
+            fs::rename(&old_dir, &new_dir)?;
+        }
+        fs::create_dir_all(&new_dir)?;
 
-    // Step 1: Check if catalog file exists
-    if !catalog_path.exists() {
-        eprintln!("Catalog file does not exist at {}.", catalog_path.display());
-        return Catalog {
-            databases: HashMap::new(),
-        };
+        for (table_name, table) in &database.tables {
+            let moves = [
+                (format!("{}.dat", table_name), config.table_file(database.oid, table.oid)),
+                (format!("{}.toast", table_name), config.toast_file(database.oid, table.oid)),
+            ];
+            for (old_name, new_path) in moves {
+                let old_path = new_dir.join(old_name);
+                if old_path.exists() && !new_path.exists() {
+                    fs::rename(&old_path, &new_path)?;
+                }
+            }
+        }
+        sync_directory(&new_dir)?;
     }
+    sync_directory(&config.database_dir())
+}
+
+/// Resolves leftover `.dropped` tombstones from interrupted DROP TABLE / DROP DATABASE.
+/// The catalog is the source of truth: if it still lists the object the drop never
+/// committed and the tombstone is renamed back, otherwise the tombstone is deleted.
+pub fn recover_dropped_objects(catalog: &Catalog) {
+    let database_dir = catalog.config.database_dir();
+    let Ok(entries) = fs::read_dir(&database_dir) else {
+        return;
+    };
+    let database_by_oid = |oid: &str| {
+        let oid = oid.parse::<Oid>().ok()?;
+        catalog.databases.values().find(|db| db.oid == oid)
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
 
-    // Step 2: Read the catalog file
-    let data = fs::read_to_string(catalog_path);
-    let data = match data {
-        Ok(content) => content,
-        Err(err) => {
-            eprintln!("Failed to read catalog file: {}", err);
-            return Catalog {
-                databases: HashMap::new(),
+        // Database tombstone: base/{db_oid}.dropped
+        if let Some(db_oid) = name.strip_suffix(DROPPED_SUFFIX) {
+            let original = database_dir.join(db_oid);
+            let result = if database_by_oid(db_oid).is_some() && !original.exists() {
+                fs::rename(&path, &original)
+            } else {
+                fs::remove_dir_all(&path)
             };
+            if let Err(e) = result {
+                warn!("Failed to recover dropped database {}: {}", db_oid, e);
+            }
+            continue;
         }
-    };
 
-    // Step 3: Deserialize JSON into Catalog struct
-    match serde_json::from_str::<Catalog>(&data) {
-        Ok(catalog) => {
-            // println!(
-            //     "Catalog loaded successfully from {}",
-            //     catalog_path.display()
-            // );
-            catalog
-        }
-        Err(err) => {
-            eprintln!("Failed to parse catalog JSON: {}", err);
-            Catalog {
-                databases: HashMap::new(),
+        if !path.is_dir() {
+            continue;
+        }
+
+        // Table tombstones: base/{db_oid}/{table_oid}.dropped (and {table_oid}.toast.dropped)
+        let Ok(files) = fs::read_dir(&path) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let file_name = file.file_name().to_string_lossy().to_string();
+            let Some(table_file) = file_name.strip_suffix(DROPPED_SUFFIX) else {
+                continue;
+            };
+            let table_oid = table_file.strip_suffix(".toast").unwrap_or(table_file);
+
+            let still_listed = table_oid.parse::<Oid>().is_ok_and(|oid| {
+                database_by_oid(&name).is_some_and(|db| db.tables.values().any(|t| t.oid == oid))
+            });
+            let original = path.join(table_file);
+
+            let result = if still_listed && !original.exists() {
+                fs::rename(file.path(), &original)
+            } else {
+                fs::remove_file(file.path())
+            };
+            if let Err(e) = result {
+                warn!("Failed to recover dropped table {}/{}: {}", name, table_oid, e);
             }
         }
     }
 }
 
-pub fn save_catalog(catalog: &Catalog) {
-    let catalog_path = Path::new(CATALOG_FILE);
+/// Cuts the pages a crashed bulk load wrote after the committed ones off every table and TOAST file.
+/// They were never counted in the file header, so no reader has seen them.
+pub fn recover_uncommitted_pages(catalog: &Catalog) {
+    for database in catalog.databases.values() {
+        for table in database.tables.values() {
+            let table_file = catalog.config.table_file(database.oid, table.oid);
+            let toast_file = catalog.config.toast_file(database.oid, table.oid);
+            for path in [table_file, toast_file] {
+                let Ok(mut file) = OpenOptions::new().read(true).write(true).open(&path) else {
+                    continue;
+                };
+                match truncate_uncommitted_pages(&mut file) {
+                    Ok(true) => info!("Removed uncommitted pages from {}", path.display()),
+                    Ok(false) => {}
+                    Err(e) => warn!("Failed to remove uncommitted pages from {}: {}", path.display(), e),
+                }
+            }
+        }
+    }
+}
 
-    // Serialize the Catalog struct to pretty JSON
-    let json = serde_json::to_string_pretty(catalog).expect("Failed to serialize catalog to JSON");
+/// Builds the catalog by scanning the system tables of the current generation.
+/// A missing or corrupt control file or system table is an error: an empty catalog would look
+/// like every database was lost. The generation before the last save is kept as a backup.
+pub fn load_catalog(config: &StorageConfig) -> error::Result<Catalog> {
+    // Step 1: Find the current generation
+    let generation = current_generation(config)?;
 
-    // Write JSON to the catalog file
-    fs::write(catalog_path, json).expect("Failed to write catalog file to disk");
+    // Step 2: Scan its system tables
+    let dir = config.generation_dir(generation);
+    let mut catalog = read_system_tables(&dir).map_err(|e| {
+        let previous = config.generation_dir(generation.saturating_sub(1));
+        let backup_hint = if generation > 1 && previous.exists() {
+            format!(" (the previous version is kept at {})", previous.display())
+        } else {
+            String::new()
+        };
+        match e {
+            RookError::Corruption { file, page, message } => {
+                RookError::Corruption { file, page, message: format!("{}{}", message, backup_hint) }
+            }
+            RookError::Io(e) if e.kind() == io::ErrorKind::InvalidData => {
+                RookError::Corruption { file: dir.clone(), page: None, message: format!("{}{}", e, backup_hint) }
+            }
+            RookError::Io(e) => io::Error::new(e.kind(), format!("{}{}", e, backup_hint)).into(),
+            other => other,
+        }
+    })?;
+    catalog.config = config.clone();
 
-    println!(
-        "Catalog File updated with In Memory Data {}",
-        catalog_path.display()
-    );
+    // Step 3: Continue handing out OIDs where the last save stopped
+    let highest_oid = catalog
+        .databases
+        .values()
+        .flat_map(|db| std::iter::once(db.oid).chain(db.tables.values().map(|t| t.oid)))
+        .max()
+        .unwrap_or(0);
+    let (_, next_oid) = read_control_file(config)?;
+    catalog.next_oid = next_oid.unwrap_or(FIRST_OID).max(highest_oid.saturating_add(1));
+    catalog.assign_missing_oids();
+
+    // Step 4: Refuse data files written in another format (TOAST files are only created when needed)
+    for database in catalog.databases.values() {
+        for table in database.tables.values() {
+            for path in [
+                config.table_file(database.oid, table.oid),
+                config.toast_file(database.oid, table.oid),
+            ] {
+                if let Ok(mut file) = File::open(&path) {
+                    check_file_format(&mut file, &path)?;
+                }
+            }
+        }
+    }
+    Ok(catalog)
 }
 
-/// Displays all databases available in the catalog.
-/// If no databases exist, it prints an appropriate message.
-pub fn show_databases(catalog: &Catalog) {
-    println!("--------------------------");
-    println!("Databases in Catalog");
-    println!("--------------------------");
+/// Reads the current catalog generation from the control file.
+pub fn current_generation(config: &StorageConfig) -> error::Result<u64> {
+    read_control_file(config).map(|(generation, _)| generation)
+}
 
-    if catalog.databases.is_empty() {
-        println!("No databases found.\n");
-        return;
+/// Reads the control file: the current generation and, if recorded, the next OID to hand out.
+fn read_control_file(config: &StorageConfig) -> error::Result<(u64, Option<Oid>)> {
+    let control_file = config.control_file();
+    let data = fs::read_to_string(&control_file).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Failed to read catalog control file {}: {}", control_file.display(), e),
+        )
+    })?;
+    let corrupt = || RookError::Corruption {
+        file: control_file.clone(),
+        page: None,
+        message: "expected 'generation N' and 'next_oid N' lines".to_string(),
+    };
+
+    let mut lines = data.lines();
+    let generation = lines
+        .next()
+        .and_then(|line| line.trim().strip_prefix("generation "))
+        .and_then(|n| n.parse::<u64>().ok())
+        .filter(|n| *n > 0)
+        .ok_or_else(corrupt)?;
+    let next_oid = match lines.next().map(str::trim).filter(|line| !line.is_empty()) {
+        Some(line) => Some(
+            line.strip_prefix("next_oid ")
+                .and_then(|n| n.parse::<Oid>().ok())
+                .ok_or_else(corrupt)?,
+        ),
+        None => None, // Written before OIDs existed
+    };
+    Ok((generation, next_oid))
+}
+
+/// Points the control file at a generation and records the next OID: the new content goes to a temp file that is
+/// fsynced and then renamed over the control file, and the directory is fsynced.
+fn write_control_file(config: &StorageConfig, generation: u64, next_oid: Oid) -> io::Result<()> {
+    let mut temp = File::create(config.control_temp_file())?;
+    temp.write_all(format!("generation {}\nnext_oid {}\n", generation, next_oid).as_bytes())?;
+    temp.sync_all()?;
+    drop(temp);
+
+    fs::rename(config.control_temp_file(), config.control_file())?;
+    sync_directory(&config.catalog_dir())
+}
+
+/// Generation numbers of every catalog generation directory, in ascending order.
+fn catalog_generations(config: &StorageConfig) -> io::Result<Vec<u64>> {
+    let mut generations = Vec::new();
+    for entry in fs::read_dir(config.catalog_dir())? {
+        let entry = entry?;
+        let generation = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_prefix(GENERATION_DIR_PREFIX))
+            .and_then(|n| n.parse::<u64>().ok());
+        if let Some(generation) = generation
+            && entry.path() == config.generation_dir(generation)
+        {
+            generations.push(generation);
+        }
     }
+    generations.sort();
+    Ok(generations)
+}
 
-    for db_name in catalog.databases.keys() {
-        println!("- {}", db_name);
+/// Deletes the generation directories selected by `remove`.
+fn remove_generations(config: &StorageConfig, remove: impl Fn(u64) -> bool) -> io::Result<()> {
+    for generation in catalog_generations(config)? {
+        if remove(generation) {
+            fs::remove_dir_all(config.generation_dir(generation))?;
+        }
     }
+    Ok(())
+}
 
-    println!();
+/// Fsyncs a directory so renames and new entries in it are durable.
+fn sync_directory(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir; // Directories cannot be opened for syncing on this platform
+    Ok(())
 }
 
-pub fn create_database(catalog: &mut Catalog, db_name: &str) -> bool {
-    // Step 1: Validate input
-    if db_name.is_empty() {
-        println!("Database name cannot be empty");
-        return false;
+/// Persists the catalog as a new generation of system tables.
+/// The generation is written and fsynced completely before the control file is switched to it,
+/// so a crash leaves either the old or the new catalog. The previous generation is kept.
+pub fn save_catalog(catalog: &Catalog) -> error::Result<()> {
+    // Step 1: Pick the next generation number
+    let config = &catalog.config;
+    let current = match current_generation(config) {
+        Ok(generation) => generation,
+        Err(RookError::Io(e)) if e.kind() == io::ErrorKind::NotFound => 0,
+        Err(e) => return Err(e),
+    };
+    let next = current + 1;
+    let dir = config.generation_dir(next);
+
+    // Step 2: Write the system tables of the new generation
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?; // Left behind by an interrupted save
     }
+    fs::create_dir_all(&dir)?;
+    write_system_tables(catalog, &dir, Some(&config.generation_dir(current)))?;
+    sync_directory(&dir)?;
+    sync_directory(&config.catalog_dir())?;
+
+    // Step 3: Switch to it, then drop everything older than the previous generation
+    write_control_file(config, next, catalog.next_oid.max(FIRST_OID))?;
+    remove_generations(config, |g| g < current)?;
+
+    debug!("Catalog system tables updated in {}", dir.display());
+    Ok(())
+}
+
+/// Names of the databases in the catalog, sorted.
+pub fn list_databases(catalog: &Catalog) -> Vec<&str> {
+    let mut names: Vec<&str> = catalog.databases.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    names
+}
+
+pub fn create_database(catalog: &mut Catalog, db_name: &str) -> error::Result<()> {
+    // Step 1: Validate input
+    validate_identifier("Database", db_name)?;
 
     if catalog.databases.contains_key(db_name) {
-        println!("Database '{}' already exists", db_name);
-        return false;
+        return Err(RookError::already_exists("Database", db_name));
     }
 
     // Step 2: Insert new database into in-memory catalog
+    let oid = catalog.allocate_oid();
     catalog.databases.insert(
         db_name.to_string(),
         Database {
+            oid,
             tables: HashMap::new(),
         },
     );
 
     // Step 3: Persist updated catalog to disk
-    let json = match serde_json::to_string_pretty(&catalog) {
-        Ok(j) => j,
-        Err(e) => {
-            println!("Failed to serialize catalog: {}", e);
-            return false;
-        }
-    };
-
-    if let Err(e) = fs::write(CATALOG_FILE, json) {
-        println!("Failed to write catalog file: {}", e);
-        return false;
+    if let Err(e) = save_catalog(catalog) {
+        catalog.databases.remove(db_name);
+        return Err(e);
     }
 
     // Step 4: Create the physical database directory
-    let db_path_str = TABLE_DIR_TEMPLATE.replace("{database}", db_name);
-    let db_path = Path::new(&db_path_str);
+    let db_path = catalog.config.table_dir(oid);
 
     if !db_path.exists() {
-        if let Err(e) = fs::create_dir_all(db_path) {
-            println!("Failed to create database directory: {}", e);
-            return false;
-        }
-        // println!("Created new database directory at {}", db_path.display());
+        fs::create_dir_all(&db_path)?;
     } else {
-        println!("Database directory already exists at {}", db_path.display());
+        warn!("Database directory already exists at {}", db_path.display());
+    }
+
+    info!("Database '{}' created successfully", db_name);
+    Ok(())
+}
+
+/// Checks that a table definition has at least one column and unique, valid column names.
+pub fn validate_columns(columns: &[Column]) -> error::Result<()> {
+    if columns.is_empty() {
+        return Err(RookError::InvalidDefinition("a table needs at least one column".to_string()));
+    }
+
+    for (i, column) in columns.iter().enumerate() {
+        validate_identifier("column", &column.name)?;
+        if columns[..i].iter().any(|c| c.name == column.name) {
+            return Err(RookError::InvalidDefinition(format!(
+                "column '{}' is defined more than once",
+                column.name
+            )));
+        }
+        if let Some(default) = &column.default
+            && let Err(e) = parse_value(column.data_type, default)
+        {
+            return Err(RookError::InvalidDefinition(format!(
+                "invalid default for column '{}': {}",
+                column.name, e
+            )));
+        }
     }
 
-    println!("Database '{}' created successfully", db_name);
-    true
+    Ok(())
 }
 
 /// Creates a new table under the specified database and updates the catalog on disk.
 /// If the table already exists, it will not be overwritten.
-pub fn create_table(catalog: &mut Catalog, db_name: &str, table_name: &str, columns: Vec<Column>) {
+pub fn create_table(
+    catalog: &mut Catalog,
+    db_name: &str,
+    table_name: &str,
+    columns: Vec<Column>,
+) -> error::Result<()> {
     // Step 1: Validate database existence
-    if !catalog.databases.contains_key(db_name) {
-        println!(
-            "Database '{}' does not exist. Cannot create table '{}'.",
-            db_name, table_name
-        );
-        return;
-    }
+    let database = catalog
+        .databases
+        .get(db_name)
+        .ok_or_else(|| RookError::not_found("Database", db_name))?;
 
-    // Step 2: Get mutable reference to the database
-    let database = catalog.databases.get_mut(db_name).unwrap();
+    // Step 2: Validate the table name and column definitions
+    validate_identifier("table", table_name)?;
+    validate_columns(&columns)?;
 
     // Step 3: Check if the table already exists
     if database.tables.contains_key(table_name) {
-        println!(
-            "Table '{}' already exists in database '{}'. Skipping creation.",
-            table_name, db_name
-        );
-        return;
+        return Err(RookError::already_exists("Table", table_name));
     }
 
-    // Step 4: Create and insert the new table into catalog
-    let new_table = Table { columns };
-    database.tables.insert(table_name.to_string(), new_table);
+    // Step 4: Create the new table, only now taking an OID, and insert it into catalog
+    let mut new_table = Table::new(columns);
+    let oid = catalog.allocate_oid();
+    new_table.oid = oid;
+    if let Some(database) = catalog.databases.get_mut(db_name) {
+        database.tables.insert(table_name.to_string(), new_table);
+    }
 
     // Step 5: Save updated catalog using your existing save_catalog() helper
-    save_catalog(catalog);
+    if let Err(e) = save_catalog(catalog) {
+        if let Some(database) = catalog.databases.get_mut(db_name) {
+            database.tables.remove(table_name);
+        }
+        return Err(e);
+    }
 
     // Step 6: Define the table file path (multi-database layout)
-    let table_file_path = TABLE_FILE_TEMPLATE
-        .replace("{database}", db_name)
-        .replace("{table}", table_name);
+    let table_path = catalog.config.table_file(catalog.databases[db_name].oid, oid);
 
     // Step 7: Create and initialize the table file
-    let table_path = Path::new(&table_file_path);
     if !table_path.exists() {
-        match OpenOptions::new()
+        let mut file = OpenOptions::new()
             .create(true)
             .write(true)
             .read(true) // 👈 THIS is the key fix
             .truncate(true)
-            .open(&table_file_path)
+            .open(&table_path)?;
+        debug!("Table data file created at '{}'.", table_path.display());
+
+        init_table(&mut file)?;
+        debug!("Table '{}' initialized successfully.", table_name);
+    } else {
+        warn!("Table data file '{}' already exists.", table_path.display());
+    }
+
+    info!(
+        "Table '{}' created successfully in database '{}' and saved to catalog.",
+        table_name, db_name
+    );
+    Ok(())
+}
+
+/// Names of the tables of a database, sorted.
+pub fn list_tables<'a>(catalog: &'a Catalog, db_name: &str) -> error::Result<Vec<&'a str>> {
+    let database = catalog
+        .databases
+        .get(db_name)
+        .ok_or_else(|| RookError::not_found("Database", db_name))?;
+    let mut names: Vec<&str> = database.tables.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    Ok(names)
+}
+
+/// Drops a table: removes it from the catalog, deletes its data and TOAST files and evicts
+/// its pages from the buffer manager.
+///
+/// The files are first renamed to `.dropped` tombstones, then the catalog is saved,
+/// then the tombstones are deleted. A crash at any point is resolved by
+/// `recover_dropped_objects` on the next start.
+pub fn drop_table(
+    catalog: &mut Catalog,
+    buffer_manager: &mut BufferManager,
+    db_name: &str,
+    table_name: &str,
+) -> error::Result<()> {
+    // Step 1: Validate database and table existence
+    let Some(database) = catalog.databases.get_mut(db_name) else {
+        return Err(RookError::not_found("Database", db_name));
+    };
+
+    if !database.tables.contains_key(table_name) {
+        return Err(RookError::not_found("Table", table_name));
+    }
+
+    if let Some((child_name, fk)) = database
+        .foreign_keys_to(table_name)
+        .into_iter()
+        .find(|(child_name, _)| *child_name != table_name)
+    {
+        return Err(RookError::ConstraintViolation(format!(
+            "Cannot drop table '{}': foreign key '{}' of table '{}' references it.",
+            table_name,
+            fk.name(),
+            child_name
+        )));
+    }
+
+    // Step 2: Move the data and TOAST files out of the way
+    let table_oid = database.tables[table_name].oid;
+    let file_paths = [
+        catalog.config.table_file(database.oid, table_oid),
+        catalog.config.toast_file(database.oid, table_oid),
+    ];
+    let tombstone_paths = file_paths.each_ref().map(|path| tombstone_path(path));
+
+    for (path, tombstone_path) in file_paths.iter().zip(&tombstone_paths) {
+        if path.exists() {
+            fs::rename(path, tombstone_path)?;
+        }
+    }
+
+    // Step 3: Remove the table from the catalog and persist (commit point)
+    let table = database.tables.remove(table_name).unwrap();
+    if let Err(e) = save_catalog(catalog) {
+        catalog
+            .databases
+            .get_mut(db_name)
+            .unwrap()
+            .tables
+            .insert(table_name.to_string(), table);
+        for (path, tombstone_path) in file_paths.iter().zip(&tombstone_paths) {
+            if tombstone_path.exists() {
+                let _ = fs::rename(tombstone_path, path);
+            }
+        }
+        return Err(e);
+    }
+
+    // Step 4: Evict buffered pages of the table
+    buffer_manager.evict_table(db_name, table_name);
+
+    // Step 5: Delete the tombstones
+    for tombstone_path in &tombstone_paths {
+        if tombstone_path.exists()
+            && let Err(e) = fs::remove_file(tombstone_path)
         {
-            Ok(mut file) => {
-                println!("Table data file created at '{}'.", table_file_path);
+            warn!(
+                "Failed to delete dropped table file '{}': {}",
+                tombstone_path.display(),
+                e
+            );
+        }
+    }
+
+    info!(
+        "Table '{}' dropped successfully from database '{}'.",
+        table_name, db_name
+    );
+    Ok(())
+}
+
+/// Drops a database with all of its tables: removes it from the catalog, deletes its
+/// directory and evicts its pages from the buffer manager.
+///
+/// Uses the same tombstone protocol as `drop_table`, applied to the database directory.
+pub fn drop_database(
+    catalog: &mut Catalog,
+    buffer_manager: &mut BufferManager,
+    db_name: &str,
+) -> error::Result<()> {
+    // Step 1: Validate database existence
+    if !catalog.databases.contains_key(db_name) {
+        return Err(RookError::not_found("Database", db_name));
+    }
+
+    // Step 2: Move the database directory out of the way
+    let db_path = catalog.config.table_dir(catalog.databases[db_name].oid);
+    let tombstone_path = tombstone_path(&db_path);
+
+    if tombstone_path.exists() {
+        // Leftover from an interrupted drop that recovery could not delete
+        fs::remove_dir_all(&tombstone_path)?;
+    }
+
+    if db_path.exists() {
+        fs::rename(&db_path, &tombstone_path)?;
+    }
 
-                if let Err(e) = init_table(&mut file) {
-                    eprintln!("Failed to initialize table '{}': {}", table_name, e);
-                } else {
-                    println!("Table '{}' initialized successfully.", table_name);
+    // Step 3: Remove the database from the catalog and persist (commit point)
+    let database = catalog.databases.remove(db_name).unwrap();
+    if let Err(e) = save_catalog(catalog) {
+        catalog.databases.insert(db_name.to_string(), database);
+        if tombstone_path.exists() {
+            let _ = fs::rename(&tombstone_path, &db_path);
+        }
+        return Err(e);
+    }
+
+    // Step 4: Evict buffered pages of any table in the database
+    buffer_manager.evict_database(db_name);
+
+    // Step 5: Delete the tombstone directory
+    if tombstone_path.exists()
+        && let Err(e) = fs::remove_dir_all(&tombstone_path)
+    {
+        warn!(
+            "Failed to delete dropped database directory '{}': {}",
+            tombstone_path.display(),
+            e
+        );
+    }
+
+    info!("Database '{}' dropped successfully.", db_name);
+    Ok(())
+}
+
+/// Looks up a table for an ALTER TABLE operation and returns a copy to change.
+/// The copy only replaces the table once the catalog is saved (`commit_table`).
+fn table_for_alter(catalog: &Catalog, db_name: &str, table_name: &str) -> error::Result<Table> {
+    let database = catalog
+        .databases
+        .get(db_name)
+        .ok_or_else(|| RookError::not_found("Database", db_name))?;
+    let mut table = database
+        .tables
+        .get(table_name)
+        .ok_or_else(|| RookError::not_found("Table", table_name))?
+        .clone();
+
+    // Tables created before ALTER TABLE support have no column ids yet
+    table.assign_column_ids();
+    Ok(table)
+}
+
+/// Swaps the altered copy of a table into the catalog and saves it.
+/// If the save fails, the previous table is put back.
+fn commit_table(catalog: &mut Catalog, db_name: &str, table_name: &str, table: Table) -> error::Result<()> {
+    let tables = &mut catalog.databases.get_mut(db_name).unwrap().tables;
+    let previous = tables.insert(table_name.to_string(), table).unwrap();
+    if let Err(e) = save_catalog(catalog) {
+        catalog.databases.get_mut(db_name).unwrap().tables.insert(table_name.to_string(), previous);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Like `commit_table`, for ALTERs that also change other tables of the database
+/// (renames are carried into the foreign keys referencing the table).
+fn commit_tables(catalog: &mut Catalog, db_name: &str, tables: HashMap<String, Table>) -> error::Result<()> {
+    let database = catalog.databases.get_mut(db_name).unwrap();
+    let previous = std::mem::replace(&mut database.tables, tables);
+    if let Err(e) = save_catalog(catalog) {
+        catalog.databases.get_mut(db_name).unwrap().tables = previous;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// ALTER TABLE ... ADD COLUMN.
+/// Existing rows report `column.default` (or NULL) for the new column.
+pub fn alter_table_add_column(
+    catalog: &mut Catalog,
+    db_name: &str,
+    table_name: &str,
+    mut column: Column,
+) -> error::Result<()> {
+    // Step 1: Validate table and column
+    let mut table = table_for_alter(catalog, db_name, table_name)?;
+
+    validate_identifier("Column", &column.name)?;
+
+    if table.columns.iter().any(|c| c.name == column.name) {
+        return Err(RookError::already_exists("Column", &column.name));
+    }
+
+    if let Some(default) = &column.default
+        && let Err(e) = parse_value(column.data_type, default)
+    {
+        return Err(RookError::InvalidDefinition(format!(
+            "Invalid default for column '{}': {}",
+            column.name, e
+        )));
+    }
+
+    if column.not_null && column.default.is_none() {
+        return Err(RookError::InvalidDefinition(format!(
+            "Column '{}' is NOT NULL and needs a default for existing rows.",
+            column.name
+        )));
+    }
+
+    // Step 2: Start a new schema version with the extra column
+    table.bump_schema_version()?;
+    column.id = table.next_column_id;
+    table.next_column_id += 1;
+    table.columns.push(column);
+
+    // Step 3: Persist
+    commit_table(catalog, db_name, table_name, table)?;
+    info!("Column added to table '{}.{}'.", db_name, table_name);
+    Ok(())
+}
+
+/// ALTER TABLE ... DROP COLUMN.
+/// Existing tuples keep their bytes; the column is simply no longer decoded.
+pub fn alter_table_drop_column(
+    catalog: &mut Catalog,
+    db_name: &str,
+    table_name: &str,
+    column_name: &str,
+) -> error::Result<()> {
+    // Step 1: Validate table and column
+    let mut table = table_for_alter(catalog, db_name, table_name)?;
+
+    let Some(index) = table.columns.iter().position(|c| c.name == column_name) else {
+        return Err(RookError::not_found("Column", column_name));
+    };
+
+    if table.columns.len() == 1 {
+        return Err(RookError::InvalidDefinition(format!(
+            "Cannot drop '{}': table '{}' must keep at least one column.",
+            column_name, table_name
+        )));
+    }
+
+    if let Some(constraint) = table
+        .constraints
+        .iter()
+        .find(|c| c.columns().iter().any(|name| name == column_name))
+    {
+        return Err(RookError::ConstraintViolation(format!(
+            "Cannot drop '{}': constraint '{}' depends on it. Drop the constraint first.",
+            column_name,
+            constraint.name()
+        )));
+    }
+
+    // Step 2: Start a new schema version without the column
+    table.bump_schema_version()?;
+    table.columns.remove(index);
+
+    // Step 3: Persist
+    commit_table(catalog, db_name, table_name, table)?;
+    info!(
+        "Column '{}' dropped from table '{}.{}'.",
+        column_name, db_name, table_name
+    );
+    Ok(())
+}
+
+/// ALTER TABLE ... RENAME COLUMN.
+/// Only the name changes; tuples are matched to columns by id, so no new schema version is needed.
+pub fn alter_table_rename_column(
+    catalog: &mut Catalog,
+    db_name: &str,
+    table_name: &str,
+    old_name: &str,
+    new_name: &str,
+) -> error::Result<()> {
+    // Step 1: Validate table and columns
+    let mut table = table_for_alter(catalog, db_name, table_name)?;
+
+    validate_identifier("Column", new_name)?;
+
+    if table.columns.iter().any(|c| c.name == new_name) {
+        return Err(RookError::already_exists("Column", new_name));
+    }
+
+    let Some(column) = table.columns.iter_mut().find(|c| c.name == old_name) else {
+        return Err(RookError::not_found("Column", old_name));
+    };
+
+    // Step 2: Rename, including every constraint that refers to the column, and persist
+    column.name = new_name.to_string();
+    for constraint in table.constraints.iter_mut() {
+        match constraint {
+            Constraint::PrimaryKey { columns, .. }
+            | Constraint::Unique { columns, .. }
+            | Constraint::ForeignKey { columns, .. } => {
+                for name in columns.iter_mut().filter(|name| *name == old_name) {
+                    *name = new_name.to_string();
                 }
             }
-            Err(e) => {
-                eprintln!(
-                    "Failed to create table data file '{}': {}",
-                    table_file_path, e
-                );
-                return;
+            Constraint::Check { expression, .. } => {
+                if let Ok(mut expr) = Expr::parse(expression)
+                    && expr.columns().contains(&old_name)
+                {
+                    expr.rename_column(old_name, new_name);
+                    *expression = expr.to_string();
+                }
             }
         }
-    } else {
-        println!("Table data file '{}' already exists.", table_file_path);
     }
 
-    println!(
-        "Table '{}' created successfully in database '{}' and saved to catalog.",
-        table_name, db_name
+    // Foreign keys of other tables (or this one) that reference the column
+    let mut tables = catalog.databases[db_name].tables.clone();
+    tables.insert(table_name.to_string(), table);
+    for child in tables.values_mut() {
+        for constraint in child.constraints.iter_mut() {
+            if let Constraint::ForeignKey { ref_table, ref_columns, .. } = constraint
+                && ref_table == table_name
+            {
+                for name in ref_columns.iter_mut().filter(|name| *name == old_name) {
+                    *name = new_name.to_string();
+                }
+            }
+        }
+    }
+    commit_tables(catalog, db_name, tables)?;
+
+    info!(
+        "Column '{}' renamed to '{}' in table '{}.{}'.",
+        old_name, new_name, db_name, table_name
     );
+    Ok(())
+}
+
+/// Parses a table constraint: `[CONSTRAINT name] PRIMARY KEY (a, b)`, `UNIQUE (a)` or `CHECK (expr)`.
+/// Unnamed constraints are named like PostgreSQL does (`users_pkey`, `users_email_key`, `users_age_check`).
+pub fn parse_constraint(table_name: &str, text: &str) -> Result<Constraint, String> {
+    let mut rest = text.trim();
+    let mut name = None;
+    if let Some((keyword, after)) = rest.split_once(char::is_whitespace)
+        && keyword.eq_ignore_ascii_case("CONSTRAINT")
+    {
+        let (constraint_name, after) = after
+            .trim_start()
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("Expected a constraint after the name in '{}'", text))?;
+        validate_identifier("Constraint", constraint_name).map_err(|e| e.to_string())?;
+        name = Some(constraint_name.to_string());
+        rest = after.trim_start();
+    }
+
+    let upper = rest.to_ascii_uppercase();
+    if let Some(body) = upper.strip_prefix("FOREIGN KEY") {
+        return parse_foreign_key(table_name, name, &rest[rest.len() - body.len()..], text);
+    }
+    let (kind, body) = if let Some(body) = upper.strip_prefix("PRIMARY KEY") {
+        ("PRIMARY KEY", &rest[rest.len() - body.len()..])
+    } else if let Some(body) = upper.strip_prefix("UNIQUE") {
+        ("UNIQUE", &rest[rest.len() - body.len()..])
+    } else if let Some(body) = upper.strip_prefix("CHECK") {
+        ("CHECK", &rest[rest.len() - body.len()..])
+    } else {
+        return Err(format!(
+            "Unknown constraint '{}'. Expected PRIMARY KEY (...), UNIQUE (...), CHECK (...) or FOREIGN KEY (...) REFERENCES ...",
+            text.trim()
+        ));
+    };
+
+    let inner = body
+        .trim()
+        .strip_prefix('(')
+        .and_then(|b| b.strip_suffix(')'))
+        .ok_or_else(|| format!("Expected {} (...) in '{}'", kind, text.trim()))?
+        .trim();
+
+    if kind == "CHECK" {
+        let expr = Expr::parse(inner)?;
+        let name = name.unwrap_or_else(|| match expr.columns().first() {
+            Some(column) => format!("{}_{}_check", table_name, column),
+            None => format!("{}_check", table_name),
+        });
+        return Ok(Constraint::Check { name, expression: inner.to_string() });
+    }
+
+    let columns: Vec<String> = inner.split(',').map(|c| c.trim().to_string()).collect();
+    Ok(if kind == "PRIMARY KEY" {
+        let name = name.unwrap_or_else(|| format!("{}_pkey", table_name));
+        Constraint::PrimaryKey { name, columns }
+    } else {
+        let name = name.unwrap_or_else(|| format!("{}_{}_key", table_name, columns.join("_")));
+        Constraint::Unique { name, columns }
+    })
+}
+
+/// Parses the part after `FOREIGN KEY`: `(a, b) REFERENCES parent [(x, y)] [ON DELETE action]`.
+/// Without parent columns the key references the parent's primary key.
+fn parse_foreign_key(
+    table_name: &str,
+    name: Option<String>,
+    body: &str,
+    text: &str,
+) -> Result<Constraint, String> {
+    let invalid = || format!("Expected FOREIGN KEY (...) REFERENCES table [(...)] in '{}'", text.trim());
+    let split_list = |list: &str| -> Vec<String> { list.split(',').map(|c| c.trim().to_string()).collect() };
+
+    let (columns, rest) = body
+        .trim()
+        .strip_prefix('(')
+        .and_then(|b| b.split_once(')'))
+        .ok_or_else(invalid)?;
+    let rest = rest.trim();
+    if !rest.to_ascii_uppercase().starts_with("REFERENCES") {
+        return Err(invalid());
+    }
+    let rest = rest["REFERENCES".len()..].trim();
+
+    // Parent table, then optional column list, then optional ON DELETE action
+    let table_end = rest.find(|c: char| c == '(' || c.is_whitespace()).unwrap_or(rest.len());
+    let ref_table = rest[..table_end].to_string();
+    let mut rest = rest[table_end..].trim();
+    if ref_table.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut ref_columns = Vec::new();
+    if let Some(list) = rest.strip_prefix('(') {
+        let (list, after) = list.split_once(')').ok_or_else(invalid)?;
+        ref_columns = split_list(list);
+        rest = after.trim();
+    }
+
+    let mut on_delete = ReferentialAction::default();
+    if !rest.is_empty() {
+        let words: Vec<&str> = rest.split_whitespace().collect();
+        if words.len() < 3 || !words[0].eq_ignore_ascii_case("ON") || !words[1].eq_ignore_ascii_case("DELETE") {
+            return Err(format!("Expected ON DELETE action, found '{}'", rest));
+        }
+        on_delete = ReferentialAction::parse(&words[2..].join(" "))?;
+    }
+
+    let columns = split_list(columns);
+    let name = name.unwrap_or_else(|| format!("{}_{}_fkey", table_name, columns.join("_")));
+    Ok(Constraint::ForeignKey { name, columns, ref_table, ref_columns, on_delete })
 }
 
-pub fn show_tables(catalog: &Catalog, db_name: &str) {
-    println!("--------------------------");
-    println!("Tables in Database: {}", db_name);
-    println!("--------------------------");
+/// Checks that a new constraint fits the table definition (existing rows are checked separately).
+fn validate_constraint(database: &Database, table_name: &str, constraint: &Constraint) -> Result<(), String> {
+    let table = &database.tables[table_name];
+    if constraint.name().is_empty() {
+        return Err("constraint name cannot be empty".to_string());
+    }
+    if table.constraints.iter().any(|c| c.name() == constraint.name()) {
+        return Err(format!("constraint '{}' already exists", constraint.name()));
+    }
 
-    // Check if database exists
-    if let Some(database) = catalog.databases.get(db_name) {
-        if database.tables.is_empty() {
-            println!("No tables found in '{}'.\n", db_name);
-            return;
+    match constraint {
+        Constraint::PrimaryKey { columns, .. }
+        | Constraint::Unique { columns, .. }
+        | Constraint::ForeignKey { columns, .. } => {
+            if columns.is_empty() || columns.iter().any(|c| c.is_empty()) {
+                return Err("a key needs at least one column".to_string());
+            }
+            for (i, name) in columns.iter().enumerate() {
+                if columns[..i].contains(name) {
+                    return Err(format!("column '{}' appears more than once in the key", name));
+                }
+            }
+            if matches!(constraint, Constraint::PrimaryKey { .. })
+                && table.constraints.iter().any(|c| matches!(c, Constraint::PrimaryKey { .. }))
+            {
+                return Err("the table already has a primary key".to_string());
+            }
+        }
+        Constraint::Check { expression, .. } => {
+            Expr::parse(expression)?;
         }
+    }
 
-        for table_name in database.tables.keys() {
-            println!("- {}", table_name);
+    for name in constraint.columns() {
+        if !table.columns.iter().any(|c| c.name == name) {
+            return Err(format!("column '{}' does not exist", name));
         }
+    }
 
-        println!();
-    } else {
-        println!("Database '{}' not found.\n", db_name);
+    if let Constraint::ForeignKey { columns, ref_table, ref_columns, on_delete, .. } = constraint {
+        validate_foreign_key(database, table, columns, ref_table, ref_columns, *on_delete)?;
     }
+    Ok(())
+}
+
+/// Checks that a foreign key matches a PRIMARY KEY or UNIQUE constraint of its parent, column types included.
+fn validate_foreign_key(
+    database: &Database,
+    table: &Table,
+    columns: &[String],
+    ref_table: &str,
+    ref_columns: &[String],
+    on_delete: ReferentialAction,
+) -> Result<(), String> {
+    let Some(parent) = database.tables.get(ref_table) else {
+        return Err(format!("referenced table '{}' does not exist", ref_table));
+    };
+    if ref_columns.len() != columns.len() {
+        return Err(format!(
+            "the key has {} columns but references {} columns of '{}'",
+            columns.len(),
+            ref_columns.len(),
+            ref_table
+        ));
+    }
+
+    let mut sorted_ref = ref_columns.to_vec();
+    sorted_ref.sort();
+    let is_parent_key = parent.constraints.iter().any(|c| match c {
+        Constraint::PrimaryKey { columns: key, .. } | Constraint::Unique { columns: key, .. } => {
+            let mut key = key.clone();
+            key.sort();
+            key == sorted_ref
+        }
+        _ => false,
+    });
+    if !is_parent_key {
+        return Err(format!(
+            "columns ({}) of '{}' are not its PRIMARY KEY or a UNIQUE constraint",
+            ref_columns.join(", "),
+            ref_table
+        ));
+    }
+
+    for (name, ref_name) in columns.iter().zip(ref_columns) {
+        let column = table.columns.iter().find(|c| c.name == *name).unwrap();
+        let ref_column = parent.columns.iter().find(|c| c.name == *ref_name).unwrap();
+        if column.data_type != ref_column.data_type {
+            return Err(format!(
+                "column '{}' is {} but references '{}.{}' of type {}",
+                name, column.data_type, ref_table, ref_name, ref_column.data_type
+            ));
+        }
+        if on_delete == ReferentialAction::SetNull
+            && (column.not_null || is_primary_key_column(table, name))
+        {
+            return Err(format!("ON DELETE SET NULL needs column '{}' to be nullable", name));
+        }
+    }
+    Ok(())
+}
+
+fn is_primary_key_column(table: &Table, column_name: &str) -> bool {
+    table.constraints.iter().any(|c| {
+        matches!(c, Constraint::PrimaryKey { columns, .. } if columns.iter().any(|name| name == column_name))
+    })
+}
+
+/// Fills in the parent's primary key for a foreign key declared without parent columns.
+fn resolve_foreign_key(database: &Database, constraint: &mut Constraint) -> Result<(), String> {
+    if let Constraint::ForeignKey { ref_table, ref_columns, .. } = constraint
+        && ref_columns.is_empty()
+    {
+        let primary_key = database.tables.get(ref_table.as_str()).and_then(|parent| {
+            parent.constraints.iter().find_map(|c| match c {
+                Constraint::PrimaryKey { columns, .. } => Some(columns.clone()),
+                _ => None,
+            })
+        });
+        *ref_columns = primary_key.ok_or_else(|| {
+            format!("referenced table '{}' does not exist or has no primary key", ref_table)
+        })?;
+    }
+    Ok(())
+}
+
+/// ALTER TABLE ... ADD CONSTRAINT.
+/// Fails if any existing row violates the constraint.
+pub fn alter_table_add_constraint(
+    catalog: &mut Catalog,
+    db_name: &str,
+    table_name: &str,
+    mut constraint: Constraint,
+) -> error::Result<()> {
+    // Step 1: Validate table and constraint definition
+    let mut table = table_for_alter(catalog, db_name, table_name)?;
+
+    let database = &catalog.databases[db_name];
+    resolve_foreign_key(database, &mut constraint)
+        .and_then(|_| validate_constraint(database, table_name, &constraint))
+        .map_err(|e| {
+            RookError::InvalidDefinition(format!("Cannot add constraint to '{}': {}", table_name, e))
+        })?;
+
+    // Step 2: Check the rows already stored in the table
+    let mut file = File::open(catalog.table_file(db_name, table_name)?)?;
+    let rows = scan_tuples(catalog, db_name, table_name, &mut file)?;
+
+    let mut checker = ConstraintChecker::with_constraints(&table.columns, std::slice::from_ref(&constraint))
+        .map_err(RookError::InvalidDefinition)?;
+    checker.load_references(catalog, db_name, table_name)?;
+    for (i, row) in rows.iter().enumerate() {
+        checker.check_row(row).map_err(|e| {
+            RookError::ConstraintViolation(format!(
+                "Cannot add constraint '{}': row {}: {}",
+                constraint.name(),
+                i + 1,
+                e
+            ))
+        })?;
+        checker.index_row(row);
+    }
+
+    // Step 3: Add and persist
+    let added = constraint.to_string();
+    table.constraints.push(constraint);
+    commit_table(catalog, db_name, table_name, table)?;
+    info!("Constraint {} added to table '{}.{}'.", added, db_name, table_name);
+    Ok(())
+}
+
+/// ALTER TABLE ... DROP CONSTRAINT.
+pub fn alter_table_drop_constraint(
+    catalog: &mut Catalog,
+    db_name: &str,
+    table_name: &str,
+    constraint_name: &str,
+) -> error::Result<()> {
+    // Step 1: Validate table and constraint
+    let mut table = table_for_alter(catalog, db_name, table_name)?;
+
+    let Some(index) = table.constraints.iter().position(|c| c.name() == constraint_name) else {
+        return Err(RookError::not_found("Constraint", constraint_name));
+    };
+
+    // Step 2: A key cannot be dropped while a foreign key references it
+    let database = &catalog.databases[db_name];
+    if let Constraint::PrimaryKey { columns: key, .. } | Constraint::Unique { columns: key, .. } =
+        &table.constraints[index]
+    {
+        let mut key = key.clone();
+        key.sort();
+        let dependent = database.foreign_keys_to(table_name).into_iter().find(|(_, fk)| {
+            let Constraint::ForeignKey { ref_columns, .. } = fk else {
+                return false;
+            };
+            let mut ref_columns = ref_columns.clone();
+            ref_columns.sort();
+            ref_columns == key
+        });
+        if let Some((child_name, fk)) = dependent {
+            return Err(RookError::ConstraintViolation(format!(
+                "Cannot drop '{}': foreign key '{}' of table '{}' depends on it. Drop it first.",
+                constraint_name,
+                fk.name(),
+                child_name
+            )));
+        }
+    }
+
+    // Step 3: Remove and persist
+    table.constraints.remove(index);
+    commit_table(catalog, db_name, table_name, table)?;
+
+    info!(
+        "Constraint '{}' dropped from table '{}.{}'.",
+        constraint_name, db_name, table_name
+    );
+    Ok(())
+}
+
+/// ANALYZE: scans a table and stores its statistics in the catalog, replacing the previous ones.
+pub fn analyze_table(catalog: &mut Catalog, db_name: &str, table_name: &str) -> error::Result<()> {
+    // Step 1: Collect the statistics
+    let mut file = File::open(catalog.table_file(db_name, table_name)?)?;
+    let stats = collect_stats(catalog, db_name, table_name, &mut file)?;
+    let row_count = stats.row_count;
+
+    // Step 2: Store them and persist
+    let table = catalog.databases.get_mut(db_name).unwrap().tables.get_mut(table_name).unwrap();
+    let previous = table.stats.replace(stats);
+    if let Err(e) = save_catalog(catalog) {
+        catalog.databases.get_mut(db_name).unwrap().tables.get_mut(table_name).unwrap().stats = previous;
+        return Err(e);
+    }
+
+    info!("Analyzed table '{}.{}': {} rows.", db_name, table_name, row_count);
+    Ok(())
+}
+
+/// ALTER TABLE ... ALTER COLUMN ... SET STORAGE.
+/// Only values written from now on follow the new strategy; existing tuples are read as they are.
+pub fn alter_table_set_storage(
+    catalog: &mut Catalog,
+    db_name: &str,
+    table_name: &str,
+    column_name: &str,
+    storage: StorageStrategy,
+) -> error::Result<()> {
+    // Step 1: Validate table and column
+    let mut table = table_for_alter(catalog, db_name, table_name)?;
+
+    let Some(column) = table.columns.iter_mut().find(|c| c.name == column_name) else {
+        return Err(RookError::not_found("Column", column_name));
+    };
+
+    if fixed_size(column.data_type).is_some() && storage != StorageStrategy::Plain {
+        return Err(RookError::InvalidDefinition(format!(
+            "Column '{}' has fixed-length type {} and can only use PLAIN storage.",
+            column_name, column.data_type
+        )));
+    }
+
+    // Step 2: Update and persist
+    column.storage = storage;
+    commit_table(catalog, db_name, table_name, table)?;
+
+    info!(
+        "Storage of column '{}' in table '{}.{}' set to {}.",
+        column_name, db_name, table_name, storage
+    );
+    Ok(())
+}
+
+/// ALTER TABLE ... RENAME TO.
+/// Renames the catalog entry; the files are named by OID and stay where they are.
+/// Buffered pages of the table are evicted since the buffer tracks tables by name.
+pub fn alter_table_rename(
+    catalog: &mut Catalog,
+    buffer_manager: &mut BufferManager,
+    db_name: &str,
+    old_name: &str,
+    new_name: &str,
+) -> error::Result<()> {
+    // Step 1: Validate database and tables
+    let Some(database) = catalog.databases.get(db_name) else {
+        return Err(RookError::not_found("Database", db_name));
+    };
+
+    validate_identifier("Table", new_name)?;
+
+    if !database.tables.contains_key(old_name) {
+        return Err(RookError::not_found("Table", old_name));
+    }
+
+    if database.tables.contains_key(new_name) {
+        return Err(RookError::already_exists("Table", new_name));
+    }
+
+    // Step 2: Evict buffered pages so they are never flushed under the old name
+    buffer_manager.evict_table(db_name, old_name);
+
+    // Step 3: Rename the catalog entry and persist
+    let mut tables = database.tables.clone();
+    let table = tables.remove(old_name).unwrap();
+    tables.insert(new_name.to_string(), table);
+    for child in tables.values_mut() {
+        for constraint in child.constraints.iter_mut() {
+            if let Constraint::ForeignKey { ref_table, .. } = constraint
+                && ref_table == old_name
+            {
+                *ref_table = new_name.to_string();
+            }
+        }
+    }
+    commit_tables(catalog, db_name, tables)?;
+
+    info!(
+        "Table '{}' renamed to '{}' in database '{}'.",
+        old_name, new_name, db_name
+    );
+    Ok(())
 }
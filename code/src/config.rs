@@ -0,0 +1,148 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use tracing::level_filters::LevelFilter;
+
+use crate::catalog::Oid;
+
+pub const DEFAULT_DATA_DIR: &str = "database"; // Data directory when none is configured
+pub const DATA_DIR_ENV: &str = "ROOKDB_DATA_DIR"; // Environment variable naming the data directory
+pub const DATA_DIR_FLAG: &str = "--data-dir"; // Command line flag naming the data directory
+pub const LOG_LEVEL_FLAG: &str = "--log-level"; // Command line flag setting the most verbose log level shown
+pub const DROPPED_SUFFIX: &str = ".dropped"; // Suffix of files/directories whose drop has not finished yet
+pub const GENERATION_DIR_PREFIX: &str = "catalog_"; // Catalog generation directories are named catalog_{N}
+
+//-------------------------------------------
+// Data directory layout (relative to the root):
+// rook.lock                        = data directory lock (PID of the process using it)
+// global/                          = catalog (control file + system table generations)
+// global/catalog.json              = catalog before system tables, migrated on bootstrap
+// base/{db_oid}/{table_oid}        = table file
+// base/{db_oid}/{table_oid}.toast  = out-of-line values of a table
+//-------------------------------------------
+
+/// Where an instance keeps its files. Every path of the storage manager is resolved
+/// against `root`, so several instances with different roots can run in one process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageConfig {
+    pub root: PathBuf,
+}
+
+impl StorageConfig {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Picks the data directory from `--data-dir <path>` (or `--data-dir=<path>`) in `args`,
+    /// then from `ROOKDB_DATA_DIR`, then falls back to `DEFAULT_DATA_DIR`.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        // Step 1: Command line flag
+        if let Some(path) = flag_value(args, DATA_DIR_FLAG) {
+            return path.map(Self::new);
+        }
+
+        // Step 2: Environment variable, then the default
+        match env::var(DATA_DIR_ENV) {
+            Ok(path) if !path.is_empty() => Ok(Self::new(path)),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Held by the process using the data directory.
+    pub fn lock_file(&self) -> PathBuf {
+        self.root.join("rook.lock")
+    }
+
+    /// Catalog metadata directory.
+    pub fn catalog_dir(&self) -> PathBuf {
+        self.root.join("global")
+    }
+
+    /// Catalog file from before system tables existed.
+    pub fn catalog_file(&self) -> PathBuf {
+        self.catalog_dir().join("catalog.json")
+    }
+
+    /// `catalog_file` after it has been migrated.
+    pub fn catalog_migrated_file(&self) -> PathBuf {
+        self.catalog_dir().join("catalog.json.migrated")
+    }
+
+    /// Names the current catalog generation.
+    pub fn control_file(&self) -> PathBuf {
+        self.catalog_dir().join("rook_control")
+    }
+
+    /// New control file being written.
+    pub fn control_temp_file(&self) -> PathBuf {
+        self.catalog_dir().join("rook_control.tmp")
+    }
+
+    /// System tables of one catalog version.
+    pub fn generation_dir(&self, generation: u64) -> PathBuf {
+        self.catalog_dir().join(format!("{}{}", GENERATION_DIR_PREFIX, generation))
+    }
+
+    /// Root directory for all databases.
+    pub fn database_dir(&self) -> PathBuf {
+        self.root.join("base")
+    }
+
+    /// Directory of one database.
+    pub fn table_dir(&self, db_oid: Oid) -> PathBuf {
+        self.database_dir().join(db_oid.to_string())
+    }
+
+    /// File of one table.
+    pub fn table_file(&self, db_oid: Oid, table_oid: Oid) -> PathBuf {
+        self.table_dir(db_oid).join(table_oid.to_string())
+    }
+
+    /// Out-of-line values of one table.
+    pub fn toast_file(&self, db_oid: Oid, table_oid: Oid) -> PathBuf {
+        self.table_dir(db_oid).join(format!("{}.toast", table_oid))
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_DATA_DIR)
+    }
+}
+
+/// Appends `DROPPED_SUFFIX` to a path.
+pub fn tombstone_path(path: &Path) -> PathBuf {
+    let mut tombstone = path.as_os_str().to_owned();
+    tombstone.push(DROPPED_SUFFIX);
+    PathBuf::from(tombstone)
+}
+
+/// Picks the log level from `--log-level <level>` (or `--log-level=<level>`) in `args`:
+/// one of off, error, warn, info, debug or trace. Defaults to info.
+pub fn log_level_from_args(args: &[String]) -> Result<LevelFilter, String> {
+    match flag_value(args, LOG_LEVEL_FLAG) {
+        Some(level) => level?
+            .parse()
+            .map_err(|_| format!("{} expects one of off, error, warn, info, debug, trace", LOG_LEVEL_FLAG)),
+        None => Ok(LevelFilter::INFO),
+    }
+}
+
+/// Value of `flag <value>` or `flag=<value>` in `args`, or None if the flag is absent.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<Result<&'a str, String>> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = if arg == flag {
+            args.next().map(String::as_str)
+        } else if let Some(value) = arg.strip_prefix(flag).and_then(|rest| rest.strip_prefix('=')) {
+            Some(value)
+        } else {
+            continue;
+        };
+        return Some(match value {
+            Some(value) if !value.is_empty() => Ok(value),
+            _ => Err(format!("{} expects a value", flag)),
+        });
+    }
+    None
+}
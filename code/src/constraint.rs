@@ -0,0 +1,544 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::sync::Mutex;
+
+use crate::catalog::{Catalog, Column, Constraint, ReferentialAction, Table};
+use crate::error::{self, RookError};
+use crate::expr::Expr;
+use crate::page::{TupleId, scan_rows, scan_tuples};
+use crate::value::{Value, encode_value};
+
+//-------------------------------------------
+// Constraint enforcement:
+// NOT NULL and DEFAULT are applied per column when a row is parsed (`tuple::parse_row`).
+// CHECK, PRIMARY KEY and UNIQUE are table constraints checked here, once the row has values.
+// Keys are enforced with an in-memory hash index of the encoded key values,
+// built from the rows already in the table when a load starts. `check_row` only checks;
+// the keys of a row are added with `index_row` once the row is stored.
+// FOREIGN KEY checks look the key up in the same kind of index built over the parent table.
+// Deleting a parent row applies the ON DELETE action of every foreign key that references it.
+// Single-row writes reuse the checker of a table from the catalog's `CheckerCache` and keep its
+// indexes up to date, instead of rebuilding them from a scan of the table for every row.
+//-------------------------------------------
+
+/// Encoded key of the columns at `positions`, or None when one of them is NULL.
+fn encode_key(values: &[Value], positions: &[usize]) -> Option<Vec<u8>> {
+    let mut key = Vec::new();
+    for &i in positions {
+        if values[i] == Value::Null {
+            return None;
+        }
+        encode_value(&values[i], &mut key);
+    }
+    Some(key)
+}
+
+/// Positions of the named columns.
+fn positions(columns: &[Column], names: &[String]) -> Result<Vec<usize>, String> {
+    names
+        .iter()
+        .map(|name| {
+            columns
+                .iter()
+                .position(|c| c.name == *name)
+                .ok_or_else(|| format!("Constraint refers to unknown column '{}'", name))
+        })
+        .collect()
+}
+
+fn describe_key(columns: &[Column], positions: &[usize], values: &[Value]) -> String {
+    let names: Vec<&str> = positions.iter().map(|&i| columns[i].name.as_str()).collect();
+    let key_values: Vec<String> = positions.iter().map(|&i| values[i].to_string()).collect();
+    format!("({})=({})", names.join(", "), key_values.join(", "))
+}
+
+/// Hash index of the keys of one PRIMARY KEY or UNIQUE constraint.
+struct UniqueIndex {
+    name: String,
+    primary: bool,
+    columns: Vec<usize>, // Positions of the key columns in the row
+    keys: HashSet<Vec<u8>>,
+}
+
+impl UniqueIndex {
+    /// Encoded key of a row, or None when a key column is NULL (NULLs never conflict).
+    fn key(&self, values: &[Value]) -> Option<Vec<u8>> {
+        encode_key(values, &self.columns)
+    }
+
+    fn kind(&self) -> &'static str {
+        if self.primary { "PRIMARY KEY" } else { "UNIQUE" }
+    }
+}
+
+/// A FOREIGN KEY of the checked table, with the keys present in its parent table.
+struct ForeignKeyIndex {
+    name: String,
+    columns: Vec<usize>,
+    ref_table: String,
+    ref_columns: Vec<String>,
+    self_columns: Option<Vec<usize>>, // Positions of the referenced columns when the table references itself
+    parent_keys: Option<HashSet<Vec<u8>>>, // None until `load_references`
+}
+
+/// Checks rows against the CHECK, PRIMARY KEY, UNIQUE and FOREIGN KEY constraints of a table.
+pub struct ConstraintChecker {
+    columns: Vec<Column>,
+    checks: Vec<(String, Expr)>,
+    indexes: Vec<UniqueIndex>,
+    foreign_keys: Vec<ForeignKeyIndex>,
+}
+
+impl ConstraintChecker {
+    /// Checker for all constraints of a table, with empty key indexes.
+    pub fn new(table: &Table) -> Result<Self, String> {
+        Self::with_constraints(&table.columns, &table.constraints)
+    }
+
+    /// Checker for the given constraints over rows laid out as `columns`.
+    /// Foreign keys need `load_references` before rows are checked.
+    pub fn with_constraints(columns: &[Column], constraints: &[Constraint]) -> Result<Self, String> {
+        let mut checks = Vec::new();
+        let mut indexes = Vec::new();
+        let mut foreign_keys = Vec::new();
+        for constraint in constraints {
+            match constraint {
+                Constraint::Check { name, expression } => {
+                    checks.push((name.clone(), Expr::parse(expression)?));
+                }
+                Constraint::PrimaryKey { name, columns: key } | Constraint::Unique { name, columns: key } => {
+                    indexes.push(UniqueIndex {
+                        name: name.clone(),
+                        primary: matches!(constraint, Constraint::PrimaryKey { .. }),
+                        columns: positions(columns, key)?,
+                        keys: HashSet::new(),
+                    });
+                }
+                Constraint::ForeignKey { name, columns: key, ref_table, ref_columns, .. } => {
+                    foreign_keys.push(ForeignKeyIndex {
+                        name: name.clone(),
+                        columns: positions(columns, key)?,
+                        ref_table: ref_table.clone(),
+                        ref_columns: ref_columns.clone(),
+                        self_columns: None,
+                        parent_keys: None,
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            columns: columns.to_vec(),
+            checks,
+            indexes,
+            foreign_keys,
+        })
+    }
+
+    /// Builds the parent key index of every foreign key by scanning the parent tables.
+    pub fn load_references(&mut self, catalog: &Catalog, db_name: &str, table_name: &str) -> error::Result<()> {
+        for fk in self.foreign_keys.iter_mut() {
+            let parent = catalog
+                .databases
+                .get(db_name)
+                .and_then(|db| db.tables.get(&fk.ref_table))
+                .ok_or_else(|| RookError::not_found("Table", &fk.ref_table))?;
+            let ref_positions = positions(&parent.columns, &fk.ref_columns).map_err(RookError::InvalidDefinition)?;
+
+            let mut file = File::open(catalog.table_file(db_name, &fk.ref_table)?)?;
+            let keys = scan_tuples(catalog, db_name, &fk.ref_table, &mut file)?
+                .iter()
+                .filter_map(|row| encode_key(row, &ref_positions))
+                .collect();
+            fk.parent_keys = Some(keys);
+            if fk.ref_table == table_name {
+                fk.self_columns = Some(positions(&self.columns, &fk.ref_columns).map_err(RookError::InvalidDefinition)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checker for a table whose key indexes already hold every row stored in `file`.
+    pub fn for_table(catalog: &Catalog, db_name: &str, table_name: &str, file: &mut File) -> error::Result<Self> {
+        let table = catalog
+            .databases
+            .get(db_name)
+            .and_then(|db| db.tables.get(table_name))
+            .ok_or_else(|| RookError::not_found("Table", table_name))?;
+
+        let mut checker = Self::new(table).map_err(RookError::InvalidDefinition)?;
+        checker.load_references(catalog, db_name, table_name)?;
+        if checker.has_indexes() {
+            for row in scan_tuples(catalog, db_name, table_name, file)? {
+                checker.index_row(&row);
+            }
+        }
+        Ok(checker)
+    }
+
+    /// Whether any PRIMARY KEY or UNIQUE constraint needs the existing rows indexed.
+    pub fn has_indexes(&self) -> bool {
+        !self.indexes.is_empty()
+    }
+
+    /// Adds the keys of a stored row, without checking it. Call it once a checked row is actually
+    /// stored, so a row that fails later (TOAST, page insert) leaves no key behind.
+    pub fn index_row(&mut self, values: &[Value]) {
+        for index in self.indexes.iter_mut() {
+            if let Some(key) = index.key(values) {
+                index.keys.insert(key);
+            }
+        }
+
+        // Later rows of a self-referencing table may reference this one
+        for fk in self.foreign_keys.iter_mut() {
+            if let (Some(self_columns), Some(parent_keys)) = (&fk.self_columns, &mut fk.parent_keys)
+                && let Some(key) = encode_key(values, self_columns)
+            {
+                parent_keys.insert(key);
+            }
+        }
+    }
+
+    /// Forgets the keys of a stored row that is being replaced.
+    pub fn unindex_row(&mut self, values: &[Value]) {
+        for index in self.indexes.iter_mut() {
+            if let Some(key) = index.key(values) {
+                index.keys.remove(&key);
+            }
+        }
+        for fk in self.foreign_keys.iter_mut() {
+            if let (Some(self_columns), Some(parent_keys)) = (&fk.self_columns, &mut fk.parent_keys)
+                && let Some(key) = encode_key(values, self_columns)
+            {
+                parent_keys.remove(&key);
+            }
+        }
+    }
+
+    /// Checks a row about to be inserted, without recording its keys (see `index_row`).
+    /// The error names the violated constraint.
+    pub fn check_row(&self, values: &[Value]) -> error::Result<()> {
+        // Step 1: PRIMARY KEY columns cannot be NULL
+        for index in self.indexes.iter().filter(|index| index.primary) {
+            if let Some(&i) = index.columns.iter().find(|&&i| values[i] == Value::Null) {
+                return Err(RookError::ConstraintViolation(format!(
+                    "column '{}' is part of PRIMARY KEY '{}' and cannot be NULL",
+                    self.columns[i].name, index.name
+                )));
+            }
+        }
+
+        // Step 2: CHECK expressions must not be FALSE (NULL passes, as in SQL)
+        for (name, expr) in &self.checks {
+            match expr.eval(&self.columns, values) {
+                Ok(Value::Boolean(false)) => {
+                    return Err(RookError::ConstraintViolation(format!(
+                        "violates CHECK constraint '{}' ({})",
+                        name, expr
+                    )));
+                }
+                Ok(Value::Boolean(true) | Value::Null) => {}
+                Ok(other) => {
+                    return Err(RookError::ConstraintViolation(format!(
+                        "CHECK constraint '{}' returned {}, not a boolean",
+                        name, other
+                    )));
+                }
+                Err(e) => {
+                    return Err(RookError::ConstraintViolation(format!(
+                        "CHECK constraint '{}': {}",
+                        name, e
+                    )));
+                }
+            }
+        }
+
+        // Step 3: Foreign keys must exist in the parent (a key with a NULL column is not checked)
+        for fk in &self.foreign_keys {
+            let Some(key) = encode_key(values, &fk.columns) else {
+                continue;
+            };
+            let Some(parent_keys) = &fk.parent_keys else {
+                return Err(RookError::ConstraintViolation(format!(
+                    "FOREIGN KEY '{}' has no parent keys loaded",
+                    fk.name
+                )));
+            };
+            let references_itself = fk
+                .self_columns
+                .as_ref()
+                .is_some_and(|self_columns| encode_key(values, self_columns).as_ref() == Some(&key));
+            if !parent_keys.contains(&key) && !references_itself {
+                return Err(RookError::ConstraintViolation(format!(
+                    "violates FOREIGN KEY constraint '{}': key {} is not present in table '{}'",
+                    fk.name,
+                    describe_key(&self.columns, &fk.columns, values),
+                    fk.ref_table
+                )));
+            }
+        }
+
+        // Step 4: Keys must be new
+        for index in &self.indexes {
+            if let Some(key) = index.key(values)
+                && index.keys.contains(&key)
+            {
+                return Err(RookError::ConstraintViolation(format!(
+                    "duplicate key {} violates {} constraint '{}'",
+                    describe_key(&self.columns, &index.columns, values),
+                    index.kind(),
+                    index.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checkers of the tables written through the checked single-row paths (`insert_row`, `update_row`),
+/// kept with their key indexes so each write costs a hash lookup rather than a table scan.
+/// A checker is rebuilt when its table is recreated or its schema or constraints change.
+/// Paths that change rows in bulk (loads, `delete_row`) call `invalidate` before writing; so must
+/// callers that write table files directly (`insert_tuple`, `delete_tuple`, `write_page`).
+#[derive(Default)]
+pub struct CheckerCache {
+    checkers: Mutex<HashMap<(String, String), CachedChecker>>,
+}
+
+/// A checker with what it was built from.
+struct CachedChecker {
+    oid: u32,
+    schema_version: u32,
+    constraints: Vec<Constraint>,
+    checker: ConstraintChecker,
+}
+
+impl CheckerCache {
+    /// Runs `f` with the checker of a table and its file, building the checker from the rows in
+    /// `file` when it is not cached or is outdated. `f` must leave the indexes matching the rows
+    /// stored, even on error.
+    pub fn with_checker<T>(
+        &self,
+        catalog: &Catalog,
+        db_name: &str,
+        table_name: &str,
+        file: &mut File,
+        f: impl FnOnce(&mut ConstraintChecker, &mut File) -> error::Result<T>,
+    ) -> error::Result<T> {
+        let table = catalog
+            .databases
+            .get(db_name)
+            .and_then(|db| db.tables.get(table_name))
+            .ok_or_else(|| RookError::not_found("Table", table_name))?;
+
+        let mut checkers = self.checkers.lock().unwrap();
+        let key = (db_name.to_string(), table_name.to_string());
+        let current = checkers.get(&key).is_some_and(|cached| {
+            cached.oid == table.oid
+                && cached.schema_version == table.schema_version
+                && cached.constraints == table.constraints
+        });
+        if !current {
+            let checker = ConstraintChecker::for_table(catalog, db_name, table_name, file)?;
+            let cached = CachedChecker {
+                oid: table.oid,
+                schema_version: table.schema_version,
+                constraints: table.constraints.clone(),
+                checker,
+            };
+            checkers.insert(key.clone(), cached);
+        }
+        f(&mut checkers.get_mut(&key).unwrap().checker, file)
+    }
+
+    /// Forgets the checker of a table whose rows changed, and those of the tables whose foreign
+    /// keys reference it (their parent keys changed).
+    pub fn invalidate(&self, catalog: &Catalog, db_name: &str, table_name: &str) {
+        self.invalidate_references(catalog, db_name, table_name);
+        self.checkers.lock().unwrap().remove(&(db_name.to_string(), table_name.to_string()));
+    }
+
+    /// Forgets the checkers of the tables whose foreign keys reference a table.
+    pub fn invalidate_references(&self, catalog: &Catalog, db_name: &str, table_name: &str) {
+        let Some(database) = catalog.databases.get(db_name) else {
+            return;
+        };
+        let mut checkers = self.checkers.lock().unwrap();
+        for (child_name, _) in database.foreign_keys_to(table_name) {
+            checkers.remove(&(db_name.to_string(), child_name.to_string()));
+        }
+    }
+}
+
+/// What deleting a parent row does to a row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowChange {
+    Delete,
+    SetNull(Vec<usize>), // Positions of the columns to set to NULL
+}
+
+/// One row changed by a delete: the row itself or a child reached through ON DELETE actions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedChange {
+    pub table: String,
+    pub tuple: TupleId,
+    pub change: RowChange,
+}
+
+/// Rows of a table with their location, scanned at most once per plan.
+struct RowCache<'a> {
+    catalog: &'a Catalog,
+    db_name: &'a str,
+    rows: HashMap<String, Vec<(TupleId, Vec<Value>)>>,
+}
+
+impl RowCache<'_> {
+    fn rows(&mut self, table_name: &str) -> error::Result<&[(TupleId, Vec<Value>)]> {
+        if !self.rows.contains_key(table_name) {
+            let mut file = File::open(self.catalog.table_file(self.db_name, table_name)?)?;
+            let rows = scan_rows(self.catalog, self.db_name, table_name, &mut file)?;
+            self.rows.insert(table_name.to_string(), rows);
+        }
+        Ok(&self.rows[table_name])
+    }
+}
+
+/// Child rows whose foreign key `fk` (of table `child_name`) references the parent row `values`.
+fn referencing_rows(
+    cache: &mut RowCache,
+    parent_name: &str,
+    child_name: &str,
+    fk: &Constraint,
+    values: &[Value],
+) -> error::Result<Vec<(TupleId, Vec<usize>)>> {
+    let Constraint::ForeignKey { columns, ref_columns, .. } = fk else {
+        return Ok(Vec::new());
+    };
+    let database = &cache.catalog.databases[cache.db_name];
+    let ref_positions = positions(&database.tables[parent_name].columns, ref_columns).map_err(RookError::InvalidDefinition)?;
+    let child_positions = positions(&database.tables[child_name].columns, columns).map_err(RookError::InvalidDefinition)?;
+
+    let Some(parent_key) = encode_key(values, &ref_positions) else {
+        return Ok(Vec::new());
+    };
+    Ok(cache
+        .rows(child_name)?
+        .iter()
+        .filter(|(_, row)| encode_key(row, &child_positions).as_ref() == Some(&parent_key))
+        .map(|(tuple, _)| (*tuple, child_positions.clone()))
+        .collect())
+}
+
+/// Works out every row a delete changes: the row itself first, then the child rows reached through
+/// ON DELETE CASCADE (recursively) and SET NULL. Fails without changing anything on RESTRICT.
+pub fn plan_delete(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    tuple: TupleId,
+    values: &[Value],
+) -> error::Result<Vec<PlannedChange>> {
+    let mut cache = RowCache { catalog, db_name, rows: HashMap::new() };
+    let mut plan = vec![PlannedChange { table: table_name.to_string(), tuple, change: RowChange::Delete }];
+    let mut deleted = vec![(table_name.to_string(), values.to_vec())];
+
+    while let Some((parent_name, parent_values)) = deleted.pop() {
+        for (child_name, fk) in catalog.databases[db_name].foreign_keys_to(&parent_name) {
+            let Constraint::ForeignKey { on_delete, ref_columns, .. } = fk else {
+                continue;
+            };
+
+            for (child_tuple, fk_positions) in referencing_rows(&mut cache, &parent_name, child_name, fk, &parent_values)? {
+                let planned = plan
+                    .iter()
+                    .position(|c| c.table == child_name && c.tuple == child_tuple);
+                if let Some(i) = planned
+                    && plan[i].change == RowChange::Delete
+                {
+                    continue; // Already going away
+                }
+
+                match on_delete {
+                    ReferentialAction::Restrict => {
+                        let parent_columns = &catalog.databases[db_name].tables[parent_name.as_str()].columns;
+                        let ref_positions = positions(parent_columns, ref_columns).unwrap_or_default();
+                        return Err(RookError::ConstraintViolation(format!(
+                            "delete on '{}' violates FOREIGN KEY constraint '{}': key {} is still referenced from table '{}'",
+                            parent_name,
+                            fk.name(),
+                            describe_key(parent_columns, &ref_positions, &parent_values),
+                            child_name
+                        )));
+                    }
+                    ReferentialAction::Cascade => {
+                        let child_values = cache
+                            .rows(child_name)?
+                            .iter()
+                            .find(|(t, _)| *t == child_tuple)
+                            .map(|(_, row)| row.clone())
+                            .unwrap();
+                        let change = PlannedChange { table: child_name.to_string(), tuple: child_tuple, change: RowChange::Delete };
+                        match planned {
+                            Some(i) => plan[i] = change,
+                            None => plan.push(change),
+                        }
+                        deleted.push((child_name.to_string(), child_values));
+                    }
+                    ReferentialAction::SetNull => match planned {
+                        Some(i) => {
+                            if let RowChange::SetNull(columns) = &mut plan[i].change {
+                                for position in fk_positions {
+                                    if !columns.contains(&position) {
+                                        columns.push(position);
+                                    }
+                                }
+                            }
+                        }
+                        None => plan.push(PlannedChange {
+                            table: child_name.to_string(),
+                            tuple: child_tuple,
+                            change: RowChange::SetNull(fk_positions),
+                        }),
+                    },
+                }
+            }
+        }
+    }
+    Ok(plan)
+}
+
+/// Fails if an update changes the key of a row that other rows still reference (updates RESTRICT).
+pub fn check_key_update(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    tuple: TupleId,
+    old_values: &[Value],
+    new_values: &[Value],
+) -> error::Result<()> {
+    let mut cache = RowCache { catalog, db_name, rows: HashMap::new() };
+    let columns = &catalog.databases[db_name].tables[table_name].columns;
+
+    for (child_name, fk) in catalog.databases[db_name].foreign_keys_to(table_name) {
+        let Constraint::ForeignKey { ref_columns, .. } = fk else {
+            continue;
+        };
+        let ref_positions = positions(columns, ref_columns).map_err(RookError::InvalidDefinition)?;
+        if encode_key(old_values, &ref_positions) == encode_key(new_values, &ref_positions) {
+            continue;
+        }
+
+        let referencing = referencing_rows(&mut cache, table_name, child_name, fk, old_values)?;
+        if referencing.iter().any(|(child_tuple, _)| child_name != table_name || *child_tuple != tuple) {
+            return Err(RookError::ConstraintViolation(format!(
+                "update on '{}' violates FOREIGN KEY constraint '{}': key {} is still referenced from table '{}'",
+                table_name,
+                fk.name(),
+                describe_key(columns, &ref_positions, old_values),
+                child_name
+            )));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,355 @@
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use std::str;
+use std::time::Duration;
+
+use csv::{ByteRecord, Trim};
+use encoding_rs::{Encoding, UTF_8};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use tracing::warn;
+
+use crate::catalog::Table;
+use crate::error::{self, RookError};
+use crate::tuple::parse_fields;
+use crate::value::Value;
+
+//-------------------------------------------
+// CSV import (RFC 4180):
+// - Fields are separated by `delimiter`; a field enclosed in `quote` may contain delimiters,
+//   line breaks and doubled quotes ("" stands for one ").
+// - With `header`, the first record names the columns: fields are matched to table columns by
+//   name, in any order. Fields of unknown names are ignored; columns missing from the file get
+//   their DEFAULT (or NULL). Without it, fields are matched to columns by position.
+// - A field equal to one of `null_strings` is NULL; any other field is a value, even when empty.
+// - The file is decoded from `encoding` (a BOM overrides it) before parsing.
+// - A record that cannot be loaded is rejected: it is listed in the `LoadReport` and copied to
+//   `reject_file` (after the header, so the file can be loaded again once fixed). A strict load
+//   aborts on the first rejected record, a permissive one once more than `max_errors` are rejected.
+//-------------------------------------------
+
+/// What a load does with records it cannot insert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadMode {
+    Strict, // The first rejected record aborts the load
+    #[default]
+    Permissive, // Rejected records are skipped, up to `max_errors`
+}
+
+/// How the CSV loaders read a file and handle the records they cannot insert.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: u8,                // Field separator
+    pub quote: u8,                    // Encloses fields holding delimiters, quotes or line breaks
+    pub header: bool,                 // First record names the columns
+    pub null_strings: Vec<String>,    // Fields loaded as NULL (or the column DEFAULT)
+    pub encoding: &'static Encoding,  // Encoding of the file
+    pub trim: bool,                   // Strip whitespace around fields and header names
+    pub mode: LoadMode,
+    pub max_errors: Option<usize>,    // Permissive loads abort once more records are rejected (None = no limit)
+    pub reject_file: Option<PathBuf>, // Receives the header and the rejected records
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            header: true,
+            null_strings: vec![String::new()],
+            encoding: UTF_8,
+            trim: true,
+            mode: LoadMode::Permissive,
+            max_errors: None,
+            reject_file: None,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Looks up an encoding by label ("utf-8", "latin1", "windows-1252", "utf-16le", ...).
+    pub fn encoding_for_label(label: &str) -> Result<&'static Encoding, String> {
+        Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| format!("Unknown encoding '{}'", label))
+    }
+}
+
+/// Outcome of a CSV load.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    pub rows_read: usize,          // Records in the file, not counting the header and blank lines
+    pub rows_inserted: usize,
+    pub rejected: Vec<RejectedRow>,
+    pub bytes_read: u64,           // Bytes of the file read, after decoding to UTF-8
+}
+
+impl LoadReport {
+    /// Records read per second over `elapsed`.
+    pub fn rows_per_sec(&self, elapsed: Duration) -> f64 {
+        self.rows_read as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Megabytes (2^20 bytes) read per second over `elapsed`.
+    pub fn mb_per_sec(&self, elapsed: Duration) -> f64 {
+        self.bytes_read as f64 / (1024.0 * 1024.0) / elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// A record that was not inserted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedRow {
+    pub row: usize, // Number of the record, starting at 1 after the header
+    pub reason: String,
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rows read, {} inserted, {} rejected",
+            self.rows_read,
+            self.rows_inserted,
+            self.rejected.len()
+        )
+    }
+}
+
+/// One record of a CSV file.
+pub struct CsvRow {
+    pub row: usize,                        // Number of the record, starting at 1 after the header
+    pub values: error::Result<Vec<Value>>, // Values of the table columns, or why the record does not fit
+}
+
+/// Converts CSV records to the values of the table columns. Cloned into each worker of a
+/// parallel load, so records can be parsed away from the reader.
+#[derive(Clone)]
+pub struct RecordParser<'a> {
+    table: &'a Table,
+    options: &'a CsvOptions,
+    columns: Vec<Option<usize>>, // Table column of each field (None = ignored); empty without a header
+}
+
+impl RecordParser<'_> {
+    /// Converts one record to the values of the table columns.
+    pub fn parse(&self, record: &ByteRecord) -> error::Result<Vec<Value>> {
+        let expected = if self.options.header { self.columns.len() } else { self.table.columns.len() };
+        if record.len() > expected {
+            return Err(RookError::SchemaMismatch(format!(
+                "expected {} columns, found {}",
+                expected,
+                record.len()
+            )));
+        }
+
+        let mut fields = vec![None; self.table.columns.len()];
+        for (i, field) in record.iter().enumerate() {
+            let column = if self.options.header { self.columns[i] } else { Some(i) };
+            let Some(column) = column else {
+                continue;
+            };
+            let field = str::from_utf8(field)
+                .map_err(|_| RookError::SchemaMismatch(format!("field {} is not valid UTF-8", i + 1)))?;
+            if !self.options.null_strings.iter().any(|null| null == field) {
+                fields[column] = Some(field);
+            }
+        }
+        parse_fields(self.table, &fields)
+    }
+}
+
+/// Reads the records of a CSV file as rows of a table and keeps the report of the load.
+/// Yields an error only when the file cannot be read any further.
+pub struct CsvReader<'a> {
+    options: &'a CsvOptions,
+    parser: RecordParser<'a>,
+    reader: csv::Reader<Box<dyn Read>>,
+    record: ByteRecord,
+    rows: usize,
+    rejects: Option<csv::Writer<File>>,
+    report: LoadReport,
+}
+
+impl<'a> CsvReader<'a> {
+    /// Opens a CSV file and, with `options.header`, matches its header to the columns of `table`.
+    /// The reject file, if any, is created now, so a stale one never outlives a new load.
+    pub fn open(path: &str, table: &'a Table, options: &'a CsvOptions) -> error::Result<Self> {
+        // Step 1: Decode to UTF-8 while reading (UTF-8 passes through, so invalid bytes reject only their record)
+        let file = File::open(path)?;
+        let encoding = Some(options.encoding).filter(|&encoding| encoding != UTF_8);
+        let decoded = DecodeReaderBytesBuilder::new()
+            .encoding(encoding)
+            .bom_override(true)
+            .utf8_passthru(true)
+            .strip_bom(true)
+            .build(file);
+
+        // Step 2: Records may have any number of fields; their count is checked against the table
+        let reader = csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .quote(options.quote)
+            .has_headers(false)
+            .flexible(true)
+            .trim(if options.trim { Trim::All } else { Trim::None })
+            .from_reader(Box::new(decoded) as Box<dyn Read>);
+
+        let mut csv_reader = Self {
+            options,
+            parser: RecordParser { table, options, columns: Vec::new() },
+            reader,
+            record: ByteRecord::new(),
+            rows: 0,
+            rejects: None,
+            report: LoadReport::default(),
+        };
+
+        // Step 3: Match header names to columns
+        let header = options.header && csv_reader.read_record()?;
+        if header {
+            csv_reader.parser.columns = header_columns(table, &csv_reader.record)?;
+        }
+
+        // Step 4: Start the reject file with the same header
+        if let Some(reject_file) = &options.reject_file {
+            let mut rejects = csv::WriterBuilder::new()
+                .delimiter(options.delimiter)
+                .quote(options.quote)
+                .flexible(true)
+                .from_writer(File::create(reject_file)?);
+            if header {
+                rejects.write_byte_record(&csv_reader.record).map_err(csv_error)?;
+            }
+            csv_reader.rejects = Some(rejects);
+        }
+        Ok(csv_reader)
+    }
+
+    /// The parser matching records to the columns as the header does.
+    pub fn parser(&self) -> RecordParser<'a> {
+        self.parser.clone()
+    }
+
+    /// Reads the next record without parsing it, with its number. Counts it as read.
+    pub fn next_record(&mut self) -> Option<error::Result<(usize, ByteRecord)>> {
+        match self.advance() {
+            Ok(true) => Some(Ok((self.rows, self.record.clone()))),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Counts the last record read as inserted.
+    pub fn accept(&mut self) {
+        self.accept_rows(1);
+    }
+
+    /// Counts `count` records as inserted.
+    pub fn accept_rows(&mut self, count: usize) {
+        self.report.rows_inserted += count;
+    }
+
+    /// Counts the last record read (number `row`) as rejected and copies it to the reject file.
+    /// Fails with `LoadAborted` once the options allow no more rejected records.
+    pub fn reject(&mut self, row: usize, reason: impl fmt::Display) -> error::Result<()> {
+        let record = std::mem::take(&mut self.record);
+        let rejected = self.reject_record(row, reason, &record);
+        self.record = record;
+        rejected
+    }
+
+    /// Counts `record` (number `row`) as rejected and copies it to the reject file.
+    /// Fails with `LoadAborted` once the options allow no more rejected records.
+    pub fn reject_record(&mut self, row: usize, reason: impl fmt::Display, record: &ByteRecord) -> error::Result<()> {
+        warn!(row, error = %reason, "Skipping row");
+        self.report.rejected.push(RejectedRow { row, reason: reason.to_string() });
+        if let Some(rejects) = &mut self.rejects {
+            rejects.write_byte_record(record).map_err(csv_error)?;
+        }
+
+        let max_errors = match self.options.mode {
+            LoadMode::Strict => Some(0),
+            LoadMode::Permissive => self.options.max_errors,
+        };
+        if max_errors.is_some_and(|max_errors| self.report.rejected.len() > max_errors) {
+            self.flush_rejects()?;
+            return Err(RookError::LoadAborted(self.report.clone()));
+        }
+        Ok(())
+    }
+
+    /// Ends the load and returns its report.
+    pub fn finish(mut self) -> error::Result<LoadReport> {
+        self.flush_rejects()?;
+        Ok(self.report)
+    }
+
+    fn flush_rejects(&mut self) -> error::Result<()> {
+        if let Some(rejects) = &mut self.rejects {
+            rejects.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Reads the next record into `self.record`. Returns false at the end of the file.
+    fn read_record(&mut self) -> error::Result<bool> {
+        self.reader.read_byte_record(&mut self.record).map_err(csv_error)
+    }
+
+    /// Reads the next record into `self.record`, skipping blank lines, and counts it as read.
+    /// Returns false at the end of the file.
+    fn advance(&mut self) -> error::Result<bool> {
+        loop {
+            if !self.read_record()? {
+                return Ok(false);
+            }
+            self.rows += 1;
+            self.report.bytes_read = self.reader.position().byte();
+
+            // A line holding only whitespace is not a record
+            if self.record.len() == 1 && self.record[0].iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            self.report.rows_read += 1;
+            return Ok(true);
+        }
+    }
+}
+
+impl Iterator for CsvReader<'_> {
+    type Item = error::Result<CsvRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.advance() {
+            Ok(true) => Some(Ok(CsvRow { row: self.rows, values: self.parser.parse(&self.record) })),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// I/O errors of the CSV reader and writer stay I/O errors; anything else is malformed input.
+pub fn csv_error(e: csv::Error) -> RookError {
+    if e.is_io_error() {
+        let csv::ErrorKind::Io(e) = e.into_kind() else { unreachable!() };
+        RookError::Io(e)
+    } else {
+        RookError::SchemaMismatch(e.to_string())
+    }
+}
+
+/// Table column of each header field, matched by name. Unknown names are ignored.
+fn header_columns(table: &Table, header: &ByteRecord) -> error::Result<Vec<Option<usize>>> {
+    let mut columns = Vec::with_capacity(header.len());
+    for name in header {
+        let name = String::from_utf8_lossy(name);
+        let column = table.columns.iter().position(|c| c.name == name);
+        if column.is_some() && columns.contains(&column) {
+            return Err(RookError::SchemaMismatch(format!("CSV header names column '{}' twice", name)));
+        }
+        columns.push(column);
+    }
+
+    if columns.iter().all(Option::is_none) {
+        return Err(RookError::SchemaMismatch("CSV header names none of the table columns".to_string()));
+    }
+    Ok(columns)
+}
@@ -0,0 +1,399 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+//-------------------------------------------
+// Date/Time Representation:
+// DATE        = i32 days since 1970-01-01
+// TIME        = i64 microseconds since midnight
+// TIMESTAMP   = i64 microseconds since 1970-01-01 00:00:00 (UTC, no zone attached)
+// TIMESTAMPTZ = i64 microseconds since 1970-01-01 00:00:00 UTC (input offsets applied)
+// INTERVAL    = months (i32) + days (i32) + microseconds (i64)
+//-------------------------------------------
+pub const MICROS_PER_SECOND: i64 = 1_000_000;
+pub const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+pub const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+pub const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+/// Days since 1970-01-01 of a proleptic Gregorian date (Howard Hinnant's algorithm).
+pub fn days_from_civil(year: i32, month: u32, day: u32) -> i32 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400) as u32;
+    let shifted_month = (month + 9) % 12; // March = 0
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era as i32 - 719_468
+}
+
+/// (year, month, day) for a number of days since 1970-01-01.
+pub fn civil_from_days(days: i32) -> (i32, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097) as u32;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era as i32 + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Parses a fixed-width unsigned number made only of ASCII digits.
+fn parse_digits(text: &str, width: usize) -> Option<u32> {
+    if text.len() != width || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Parses an ISO-8601 calendar date `YYYY-MM-DD` into days since 1970-01-01.
+pub fn parse_date(text: &str) -> Result<i32, String> {
+    let invalid = || format!("'{}' is not a valid DATE (expected YYYY-MM-DD)", text);
+
+    let mut parts = text.split('-');
+    let (Some(y), Some(m), Some(d), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+
+    let year = parse_digits(y, 4).ok_or_else(invalid)? as i32;
+    let month = parse_digits(m, 2).ok_or_else(invalid)?;
+    let day = parse_digits(d, 2).ok_or_else(invalid)?;
+
+    if year == 0 || !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return Err(invalid());
+    }
+
+    Ok(days_from_civil(year, month, day))
+}
+
+/// Parses an ISO-8601 time of day `HH:MM[:SS[.ffffff]]` into microseconds since midnight.
+pub fn parse_time(text: &str) -> Result<i64, String> {
+    let invalid = || format!("'{}' is not a valid TIME (expected HH:MM[:SS[.ffffff]])", text);
+
+    let (clock, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let mut parts = clock.split(':');
+    let (Some(h), Some(m), s, None) = (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+
+    let hour = parse_digits(h, 2).ok_or_else(invalid)? as i64;
+    let minute = parse_digits(m, 2).ok_or_else(invalid)? as i64;
+    let second = match s {
+        Some(s) => parse_digits(s, 2).ok_or_else(invalid)? as i64,
+        None if fraction.is_empty() => 0,
+        None => return Err(invalid()),
+    };
+
+    if hour > 23 || minute > 59 || second > 59 {
+        return Err(invalid());
+    }
+
+    // Up to microsecond precision
+    let micros = if fraction.is_empty() {
+        0
+    } else if fraction.len() <= 6 && fraction.bytes().all(|b| b.is_ascii_digit()) {
+        format!("{:0<6}", fraction).parse::<i64>().map_err(|_| invalid())?
+    } else {
+        return Err(invalid());
+    };
+
+    Ok(hour * MICROS_PER_HOUR + minute * MICROS_PER_MINUTE + second * MICROS_PER_SECOND + micros)
+}
+
+/// Splits a trailing UTC offset (`Z`, `±HH`, `±HH:MM`, `±HHMM`) off a time string.
+/// Returns the remaining text and the offset in microseconds east of UTC.
+fn split_offset(text: &str) -> Result<(&str, Option<i64>), String> {
+    if let Some(rest) = text.strip_suffix('Z').or_else(|| text.strip_suffix('z')) {
+        return Ok((rest, Some(0)));
+    }
+
+    let Some(pos) = text.rfind(['+', '-']) else {
+        return Ok((text, None));
+    };
+
+    let (rest, offset) = text.split_at(pos);
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let digits = offset[1..].replace(':', "");
+    let invalid = || format!("'{}' is not a valid UTC offset", offset);
+    // Checked before slicing by byte, which would split a multi-byte character
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let (hours, minutes) = match digits.len() {
+        2 => (parse_digits(&digits, 2).ok_or_else(invalid)?, 0),
+        4 => (
+            parse_digits(&digits[..2], 2).ok_or_else(invalid)?,
+            parse_digits(&digits[2..], 2).ok_or_else(invalid)?,
+        ),
+        _ => return Err(invalid()),
+    };
+
+    if hours > 15 || minutes > 59 {
+        return Err(invalid());
+    }
+
+    let offset = sign * (hours as i64 * MICROS_PER_HOUR + minutes as i64 * MICROS_PER_MINUTE);
+    Ok((rest, Some(offset)))
+}
+
+/// Parses an ISO-8601 timestamp (`YYYY-MM-DD[(T| )HH:MM[:SS[.ffffff]]][offset]`) into
+/// microseconds since the epoch, normalized to UTC.
+///
+/// With `with_time_zone` an offset is optional (UTC assumed); without it an offset is rejected,
+/// because a TIMESTAMP has no zone to keep it in.
+pub fn parse_timestamp(text: &str, with_time_zone: bool) -> Result<i64, String> {
+    let (date, time) = match text.split_once(['T', 't', ' ']) {
+        Some((date, time)) => (date, Some(time.trim())),
+        None => (text, None),
+    };
+
+    let days = parse_date(date)? as i64;
+
+    let (time, offset) = match time {
+        Some(time) => split_offset(time)?,
+        None => ("", None),
+    };
+
+    if offset.is_some() && !with_time_zone {
+        return Err(format!(
+            "'{}' has a UTC offset; use TIMESTAMPTZ for zoned timestamps",
+            text
+        ));
+    }
+
+    let time_of_day = if time.is_empty() { 0 } else { parse_time(time)? };
+    Ok(days * MICROS_PER_DAY + time_of_day - offset.unwrap_or(0))
+}
+
+/// Writes `HH:MM:SS[.ffffff]`, dropping trailing zeros of the fraction.
+fn write_time_of_day(f: &mut fmt::Formatter<'_>, micros: i64) -> fmt::Result {
+    let hour = micros / MICROS_PER_HOUR;
+    let minute = micros % MICROS_PER_HOUR / MICROS_PER_MINUTE;
+    let second = micros % MICROS_PER_MINUTE / MICROS_PER_SECOND;
+    let fraction = micros % MICROS_PER_SECOND;
+
+    write!(f, "{:02}:{:02}:{:02}", hour, minute, second)?;
+    if fraction != 0 {
+        let digits = format!("{:06}", fraction);
+        write!(f, ".{}", digits.trim_end_matches('0'))?;
+    }
+    Ok(())
+}
+
+/// Display helper for DATE values (`YYYY-MM-DD`).
+pub struct DateDisplay(pub i32);
+
+impl fmt::Display for DateDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.0);
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// Display helper for TIME values (`HH:MM:SS[.ffffff]`).
+pub struct TimeDisplay(pub i64);
+
+impl fmt::Display for TimeDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_time_of_day(f, self.0)
+    }
+}
+
+/// Display helper for TIMESTAMP / TIMESTAMPTZ values
+/// (`YYYY-MM-DD HH:MM:SS[.ffffff]`, followed by `+00:00` when zoned).
+pub struct TimestampDisplay {
+    pub micros: i64,
+    pub with_time_zone: bool,
+}
+
+impl fmt::Display for TimestampDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = self.micros.div_euclid(MICROS_PER_DAY) as i32;
+        let time_of_day = self.micros.rem_euclid(MICROS_PER_DAY);
+
+        write!(f, "{} ", DateDisplay(days))?;
+        write_time_of_day(f, time_of_day)?;
+        if self.with_time_zone {
+            write!(f, "+00:00")?;
+        }
+        Ok(())
+    }
+}
+
+/// A calendar-aware duration. Months and days are kept separately from the clock part
+/// because their length varies (28-31 days, 23-25 hours across DST).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub micros: i64,
+}
+
+impl Interval {
+    /// Length used for ordering, assuming 30-day months and 24-hour days (as PostgreSQL does).
+    pub fn approximate_micros(&self) -> i128 {
+        (self.months as i128 * 30 + self.days as i128) * MICROS_PER_DAY as i128
+            + self.micros as i128
+    }
+
+    /// Parses an ISO-8601 duration such as `P1Y2M10DT2H30M15.5S`, `P3W` or `-PT90S`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!("'{}' is not a valid INTERVAL (expected ISO-8601, e.g. P1DT2H)", text)
+        };
+
+        let (sign, rest) = match text.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let rest = rest
+            .strip_prefix('P')
+            .or_else(|| rest.strip_prefix('p'))
+            .ok_or_else(invalid)?;
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut interval = Interval { months: 0, days: 0, micros: 0 };
+        let mut in_time = false;
+        let mut number = String::new();
+
+        for c in rest.chars() {
+            match c.to_ascii_uppercase() {
+                'T' if !in_time && number.is_empty() => in_time = true,
+                '0'..='9' | '.' => number.push(c),
+                '-' if number.is_empty() => number.push(c), // signed component, as in P-1M
+                unit => {
+                    if number.is_empty() {
+                        return Err(invalid());
+                    }
+                    let overflow = || format!("'{}' is out of range for INTERVAL", text);
+
+                    if in_time {
+                        // H/M/S may have a fraction; everything is converted to microseconds
+                        let scale = match unit {
+                            'H' => MICROS_PER_HOUR,
+                            'M' => MICROS_PER_MINUTE,
+                            'S' => MICROS_PER_SECOND,
+                            _ => return Err(invalid()),
+                        };
+                        let amount: f64 = number.parse().map_err(|_| invalid())?;
+                        let micros = (amount * scale as f64).round();
+                        if !micros.is_finite() || micros.abs() >= i64::MAX as f64 {
+                            return Err(overflow());
+                        }
+                        interval.micros = interval
+                            .micros
+                            .checked_add(micros as i64)
+                            .ok_or_else(overflow)?;
+                    } else {
+                        let amount: i32 = number.parse().map_err(|_| invalid())?;
+                        let (field, factor) = match unit {
+                            'Y' => (&mut interval.months, 12),
+                            'M' => (&mut interval.months, 1),
+                            'W' => (&mut interval.days, 7),
+                            'D' => (&mut interval.days, 1),
+                            _ => return Err(invalid()),
+                        };
+                        *field = amount
+                            .checked_mul(factor)
+                            .and_then(|v| field.checked_add(v))
+                            .ok_or_else(overflow)?;
+                    }
+                    number.clear();
+                }
+            }
+        }
+
+        if !number.is_empty() || (in_time && rest.ends_with(['T', 't'])) {
+            return Err(invalid());
+        }
+
+        Ok(Interval {
+            months: sign * interval.months,
+            days: sign * interval.days,
+            micros: sign as i64 * interval.micros,
+        })
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.approximate_micros()
+            .cmp(&other.approximate_micros())
+            .then_with(|| {
+                (self.months, self.days, self.micros).cmp(&(other.months, other.days, other.micros))
+            })
+    }
+}
+
+impl fmt::Display for Interval {
+    /// Canonical ISO-8601 form, e.g. `P1Y2M3DT4H5M6.5S`; components carry their own sign.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.months == 0 && self.days == 0 && self.micros == 0 {
+            return write!(f, "PT0S");
+        }
+
+        write!(f, "P")?;
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years != 0 {
+            write!(f, "{}Y", years)?;
+        }
+        if months != 0 {
+            write!(f, "{}M", months)?;
+        }
+        if self.days != 0 {
+            write!(f, "{}D", self.days)?;
+        }
+
+        if self.micros != 0 {
+            write!(f, "T")?;
+            let sign = if self.micros < 0 { "-" } else { "" };
+            let micros = self.micros.unsigned_abs();
+            let hours = micros / MICROS_PER_HOUR as u64;
+            let minutes = micros % MICROS_PER_HOUR as u64 / MICROS_PER_MINUTE as u64;
+            let seconds = micros % MICROS_PER_MINUTE as u64 / MICROS_PER_SECOND as u64;
+            let fraction = micros % MICROS_PER_SECOND as u64;
+
+            if hours != 0 {
+                write!(f, "{}{}H", sign, hours)?;
+            }
+            if minutes != 0 {
+                write!(f, "{}{}M", sign, minutes)?;
+            }
+            if seconds != 0 || fraction != 0 {
+                write!(f, "{}{}", sign, seconds)?;
+                if fraction != 0 {
+                    let digits = format!("{:06}", fraction);
+                    write!(f, ".{}", digits.trim_end_matches('0'))?;
+                }
+                write!(f, "S")?;
+            }
+        }
+        Ok(())
+    }
+}
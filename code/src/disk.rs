@@ -1,25 +1,22 @@
 use std::fs::File;
-use std::io::{self, Seek, SeekFrom, Read, ErrorKind, Error, Write};
+use std::io::{Seek, SeekFrom, Read, Write};
 
+use tracing::instrument;
+
+use crate::error::{Result, RookError};
 use crate::page::{Page, PAGE_SIZE, init_page, page_count};
 // use crate::table::{TABLE_HEADER_SIZE};
 
 // Create Page 
-pub fn create_page(file: &mut File) -> io::Result<u32> {
+pub fn create_page(file: &mut File) -> Result<u32> {
     // Create an empty page (all zeros) - In Memory
     let mut page = Page::new();
-    // println!("Initializing a In Memory Page with Page Headers...");
     // Initialise Page Header
     init_page(&mut page);
 
-    // Print the first 8 bytes (page header: lower + upper offsets)
-    // println!("Created Page. Page Data: {:?}", &page.data[0..8]);
-
     // --- Step 1: Read existing page_count from File Header (first 4 bytes)
     let mut page_count =  page_count(file)?; // total pages currently in file
 
-    // println!("Page count: {}", page_count);
-    
     // --- Step 2: The new page number = current page_count
     let page_num = page_count;
 
@@ -32,16 +29,12 @@ pub fn create_page(file: &mut File) -> io::Result<u32> {
     file.seek(SeekFrom::Start(0))?;
     file.write_all(&page_count.to_le_bytes())?;
 
-    // println!(
-    //     "Created new Page with Id: {} (total pages = {})",
-    //     page_num, page_count
-    // );
-
     Ok(page_num)
 }
 
 // Read page from disk
-pub fn read_page(file: &mut File, page: &mut Page, page_num: u32) -> io::Result<()> {   // Page Number or Page Id - as offset. (For Contiguous - PageNum * offset is ok but pageId requires more)
+#[instrument(level = "trace", skip(file, page))]
+pub fn read_page(file: &mut File, page: &mut Page, page_num: u32) -> Result<()> {   // Page Number or Page Id - as offset. (For Contiguous - PageNum * offset is ok but pageId requires more)
     
     // calculating the offset
     let offset = (page_num) * PAGE_SIZE as u32;
@@ -49,15 +42,10 @@ pub fn read_page(file: &mut File, page: &mut Page, page_num: u32) -> io::Result<
 
     // get file size
     let file_size = file.metadata()?.len();
-    // println!("File Size: {}", file_size);
-    // println!("OFFSET: {}", offset);
 
     if offset > file_size as u32 {
         // Return an error if the page doesn't exist
-        return Err(Error::new(
-            ErrorKind::UnexpectedEof,
-            format!("Page {} does not exist in the file", page_num),
-        ));
+        return Err(RookError::not_found("Page", page_num.to_string()));
     }
 
     // move the file cursor
@@ -65,25 +53,21 @@ pub fn read_page(file: &mut File, page: &mut Page, page_num: u32) -> io::Result<
 
     // read the page data
     file.read_exact(&mut page.data)?;
-    // println!("READING PAGE OK");
     Ok(())
 }
 
 // Write Page into Disk
-pub fn write_page(file: &mut File, page: &mut Page, page_num: u32) -> io::Result<()> {   // Page Number or Page Id - as offset. (For Contiguous - PageNum * offset is ok but pageId requires more)
+#[instrument(level = "trace", skip(file, page))]
+pub fn write_page(file: &mut File, page: &mut Page, page_num: u32) -> Result<()> {   // Page Number or Page Id - as offset. (For Contiguous - PageNum * offset is ok but pageId requires more)
     // calculating the offset
     let offset = (page_num) as u64 * PAGE_SIZE as u64;  // as is required because not compiling - pageNum is 4 byte but offset requries 8 bytes.
 
     // get file size
     let file_size = file.metadata()?.len();
-    // println!("File Size: {}", file_size);
 
     if offset > file_size {
         // Return an error if the page doesn't exist
-        return Err(Error::new(
-            ErrorKind::UnexpectedEof,
-            format!("Page {} does not exist in the file", page_num),
-        ));
+        return Err(RookError::not_found("Page", page_num.to_string()));
     }
 
     // move the file cursor
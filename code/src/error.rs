@@ -0,0 +1,100 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::csv_import::LoadReport;
+
+/// Everything that can go wrong in the storage manager, so callers can match on the kind of
+/// failure instead of parsing messages.
+#[derive(Debug)]
+pub enum RookError {
+    /// A database, table, column, constraint or tuple that does not exist.
+    NotFound { kind: &'static str, name: String },
+    /// A database, table, column or constraint whose name is already taken.
+    AlreadyExists { kind: &'static str, name: String },
+    /// A name that is not a valid identifier (see `validate_identifier`).
+    InvalidName(String),
+    /// A table, column or constraint definition that cannot be created as given.
+    InvalidDefinition(String),
+    /// Data that does not fit the table schema (wrong column count, unparsable value).
+    SchemaMismatch(String),
+    /// A row too large for a page, even after compression and out-of-line storage.
+    TupleTooLarge(String),
+    /// Stored tuple bytes that cannot be decoded. Readers that know the file and page report it
+    /// as `Corruption` (see `corrupt_page`).
+    MalformedTuple(String),
+    /// A row or change rejected by a NOT NULL, key, CHECK or FOREIGN KEY constraint.
+    ConstraintViolation(String),
+    /// A file whose content cannot be decoded. `page` is None for files without pages.
+    Corruption { file: PathBuf, page: Option<u32>, message: String },
+    /// A CSV load stopped by its error limit (strict mode or `max_errors`), with the report so far.
+    LoadAborted(LoadReport),
+    /// An I/O error from the operating system.
+    Io(io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, RookError>;
+
+impl RookError {
+    pub fn not_found(kind: &'static str, name: impl Into<String>) -> Self {
+        RookError::NotFound { kind, name: name.into() }
+    }
+
+    pub fn already_exists(kind: &'static str, name: impl Into<String>) -> Self {
+        RookError::AlreadyExists { kind, name: name.into() }
+    }
+
+    /// Turns decoding errors (`MalformedTuple`, I/O `InvalidData` and `UnexpectedEof`) into
+    /// `Corruption` of a file page; other errors are kept as they are.
+    pub fn corrupt_page(e: impl Into<RookError>, file: impl Into<PathBuf>, page: u32) -> Self {
+        match e.into() {
+            RookError::MalformedTuple(message) => RookError::Corruption { file: file.into(), page: Some(page), message },
+            RookError::Io(e) if matches!(e.kind(), io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof) => {
+                RookError::Corruption { file: file.into(), page: Some(page), message: e.to_string() }
+            }
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for RookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RookError::NotFound { kind, name } => write!(f, "{} '{}' does not exist", kind, name),
+            RookError::AlreadyExists { kind, name } => write!(f, "{} '{}' already exists", kind, name),
+            RookError::InvalidName(message)
+            | RookError::InvalidDefinition(message)
+            | RookError::SchemaMismatch(message)
+            | RookError::TupleTooLarge(message)
+            | RookError::MalformedTuple(message)
+            | RookError::ConstraintViolation(message) => write!(f, "{}", message),
+            RookError::Corruption { file, page: Some(page), message } => {
+                write!(f, "{} is corrupt at page {}: {}", file.display(), page, message)
+            }
+            RookError::Corruption { file, page: None, message } => {
+                write!(f, "{} is corrupt: {}", file.display(), message)
+            }
+            RookError::LoadAborted(report) => match report.rejected.last() {
+                Some(last) => write!(f, "Load aborted at row {} ({}): {}", last.row, last.reason, report),
+                None => write!(f, "Load aborted: {}", report),
+            },
+            RookError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for RookError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RookError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RookError {
+    fn from(e: io::Error) -> Self {
+        RookError::Io(e)
+    }
+}
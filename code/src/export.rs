@@ -0,0 +1,124 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use serde_json::{Map, Value as JsonValue};
+
+use crate::catalog::Catalog;
+use crate::csv_import::{CsvOptions, csv_error};
+use crate::error::{self, RookError};
+use crate::page::for_each_row;
+use crate::value::Value;
+
+//-------------------------------------------
+// Table export (CSV, JSON Lines):
+// - The first record names the columns (with `header`), then one record per row, fields in
+//   column order, separated by `delimiter` and quoted with `quote` when needed.
+// - Every value is written in the form the CSV loaders accept: BYTEA as hex (`\x...`),
+//   JSON as its text, dates and times in ISO 8601, DECIMAL with its scale.
+// - NULL is written as the first of `null_strings` (an empty field by default), so loading
+//   the file with the same options gives back the same rows. A value equal to a null string
+//   cannot be told apart from NULL.
+// - JSON Lines: one object per row, keyed by column name. NULL is null, integers, finite
+//   floats and booleans are numbers and booleans, JSON values are embedded as they are, and
+//   every other value (DECIMAL, BYTEA, dates, NaN) is a string in its `field_text` form.
+//-------------------------------------------
+
+/// A non-NULL value as a field: its text in the form `parse_value` accepts (text without quotes).
+pub fn field_text(value: &Value) -> String {
+    match value {
+        Value::Text(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Writes every live row of a table to a CSV file. Returns the number of rows written.
+pub fn export_csv(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+    csv_path: &str,
+    options: &CsvOptions,
+) -> error::Result<usize> {
+    let table = catalog
+        .databases
+        .get(db_name)
+        .ok_or_else(|| RookError::not_found("Database", db_name))?
+        .tables
+        .get(table_name)
+        .ok_or_else(|| RookError::not_found("Table", table_name))?;
+    let null = options.null_strings.first().map_or("", String::as_str);
+
+    // Step 1: Header with the column names
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .from_writer(File::create(csv_path)?);
+    if options.header {
+        writer.write_record(table.columns.iter().map(|c| c.name.as_str())).map_err(csv_error)?;
+    }
+
+    // Step 2: One record per row
+    let mut rows = 0;
+    for_each_row(catalog, db_name, table_name, file, |_, values| {
+        let fields = values.iter().map(|value| match value {
+            Value::Null => null.to_string(),
+            value => field_text(value),
+        });
+        writer.write_record(fields).map_err(csv_error)?;
+        rows += 1;
+        Ok(())
+    })?;
+
+    writer.flush()?;
+    Ok(rows)
+}
+
+/// A value as a JSON Lines field.
+pub fn json_field(value: &Value) -> JsonValue {
+    match value {
+        Value::Null => JsonValue::Null,
+        Value::SmallInt(v) => JsonValue::from(*v),
+        Value::Int(v) => JsonValue::from(*v),
+        Value::BigInt(v) => JsonValue::from(*v),
+        Value::Boolean(b) => JsonValue::Bool(*b),
+        Value::Float(v) if v.is_finite() => JsonValue::from(*v),
+        Value::Double(v) if v.is_finite() => JsonValue::from(*v),
+        Value::Json(json) => json.clone(),
+        other => JsonValue::String(field_text(other)),
+    }
+}
+
+/// Writes every live row of a table to a JSON Lines file. Returns the number of rows written.
+pub fn export_json(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+    json_path: &str,
+) -> error::Result<usize> {
+    let table = catalog
+        .databases
+        .get(db_name)
+        .ok_or_else(|| RookError::not_found("Database", db_name))?
+        .tables
+        .get(table_name)
+        .ok_or_else(|| RookError::not_found("Table", table_name))?;
+
+    let mut writer = BufWriter::new(File::create(json_path)?);
+    let mut rows = 0;
+    for_each_row(catalog, db_name, table_name, file, |_, values| {
+        let object: Map<String, JsonValue> = table
+            .columns
+            .iter()
+            .zip(&values)
+            .map(|(column, value)| (column.name.clone(), json_field(value)))
+            .collect();
+        writeln!(writer, "{}", JsonValue::Object(object))?;
+        rows += 1;
+        Ok(())
+    })?;
+
+    writer.flush()?;
+    Ok(rows)
+}
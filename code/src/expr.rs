@@ -0,0 +1,784 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::catalog::{Column, DECIMAL_MAX_PRECISION, DataType};
+use crate::json::{json_extract_path, json_extract_path_text, json_typeof};
+use crate::value::{Decimal, Value, parse_value};
+
+//-------------------------------------------
+// Expressions (CHECK constraints):
+// or         := and (OR and)*
+// and        := not (AND not)*
+// not        := NOT not | comparison
+// comparison := additive [(= | <> | != | < | <= | > | >=) additive
+//                         | IS [NOT] NULL | [NOT] IN (expr, ...)]
+// additive   := term ((+ | -) term)*
+// term       := unary ((* | / | %) unary)*
+// unary      := - unary | primary
+// primary    := number | 'text' | TRUE | FALSE | NULL | column | function(expr, ...) | (expr)
+// Evaluation follows SQL three-valued logic: comparisons with NULL yield NULL.
+// Numbers are exact unless a FLOAT/DOUBLE is involved: integers and DECIMALs (including literals
+// such as 12.50) are compared and computed as scaled i128s, anything with a float as f64.
+//-------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+impl BinaryOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Or => "OR",
+            BinaryOp::And => "AND",
+            BinaryOp::Eq => "=",
+            BinaryOp::NotEq => "<>",
+            BinaryOp::Lt => "<",
+            BinaryOp::LtEq => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::GtEq => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Mod => "%",
+        }
+    }
+}
+
+/// A parsed expression over the columns of one row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    Column(String),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool,
+    },
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    Function {
+        name: String,
+        args: Vec<Expr>,
+    },
+}
+
+/// Functions callable from expressions.
+pub const FUNCTIONS: &[&str] = &[
+    "LENGTH",
+    "LOWER",
+    "UPPER",
+    "ABS",
+    "JSON_EXTRACT_PATH",
+    "JSON_EXTRACT_PATH_TEXT",
+    "JSON_TYPEOF",
+];
+
+impl Expr {
+    /// Parses an expression such as `price > 0 AND status IN ('new', 'paid')`.
+    pub fn parse(text: &str) -> Result<Expr, String> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(format!("Unexpected '{}' in expression '{}'", token, text)),
+        }
+    }
+
+    /// Names of the columns the expression refers to.
+    pub fn columns(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.visit(&mut |expr| {
+            if let Expr::Column(name) = expr {
+                names.push(name.as_str());
+            }
+        });
+        names
+    }
+
+    /// Renames every reference to a column.
+    pub fn rename_column(&mut self, old_name: &str, new_name: &str) {
+        match self {
+            Expr::Column(name) if name == old_name => *name = new_name.to_string(),
+            Expr::Literal(_) | Expr::Column(_) => {}
+            Expr::Negate(expr) | Expr::Not(expr) | Expr::IsNull { expr, .. } => {
+                expr.rename_column(old_name, new_name)
+            }
+            Expr::Binary { left, right, .. } => {
+                left.rename_column(old_name, new_name);
+                right.rename_column(old_name, new_name);
+            }
+            Expr::InList { expr, list, .. } => {
+                expr.rename_column(old_name, new_name);
+                list.iter_mut().for_each(|e| e.rename_column(old_name, new_name));
+            }
+            Expr::Function { args, .. } => {
+                args.iter_mut().for_each(|e| e.rename_column(old_name, new_name))
+            }
+        }
+    }
+
+    fn visit<'a>(&'a self, f: &mut dyn FnMut(&'a Expr)) {
+        f(self);
+        match self {
+            Expr::Literal(_) | Expr::Column(_) => {}
+            Expr::Negate(expr) | Expr::Not(expr) | Expr::IsNull { expr, .. } => expr.visit(f),
+            Expr::Binary { left, right, .. } => {
+                left.visit(f);
+                right.visit(f);
+            }
+            Expr::InList { expr, list, .. } => {
+                expr.visit(f);
+                list.iter().for_each(|e| e.visit(f));
+            }
+            Expr::Function { args, .. } => args.iter().for_each(|e| e.visit(f)),
+        }
+    }
+
+    /// Evaluates the expression against one row (`values` in the order of `columns`).
+    pub fn eval(&self, columns: &[Column], values: &[Value]) -> Result<Value, String> {
+        match self {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Column(name) => columns
+                .iter()
+                .position(|c| c.name == *name)
+                .and_then(|i| values.get(i))
+                .cloned()
+                .ok_or_else(|| format!("Unknown column '{}'", name)),
+            Expr::Negate(expr) => match expr.eval(columns, values)? {
+                Value::Null => Ok(Value::Null),
+                value => arithmetic(BinaryOp::Sub, &Value::BigInt(0), &value),
+            },
+            Expr::Not(expr) => Ok(match as_bool(&expr.eval(columns, values)?)? {
+                Some(b) => Value::Boolean(!b),
+                None => Value::Null,
+            }),
+            Expr::Binary { op: BinaryOp::And, left, right } => {
+                let left = as_bool(&left.eval(columns, values)?)?;
+                if left == Some(false) {
+                    return Ok(Value::Boolean(false));
+                }
+                let right = as_bool(&right.eval(columns, values)?)?;
+                Ok(match (left, right) {
+                    (_, Some(false)) => Value::Boolean(false),
+                    (Some(true), Some(true)) => Value::Boolean(true),
+                    _ => Value::Null,
+                })
+            }
+            Expr::Binary { op: BinaryOp::Or, left, right } => {
+                let left = as_bool(&left.eval(columns, values)?)?;
+                if left == Some(true) {
+                    return Ok(Value::Boolean(true));
+                }
+                let right = as_bool(&right.eval(columns, values)?)?;
+                Ok(match (left, right) {
+                    (_, Some(true)) => Value::Boolean(true),
+                    (Some(false), Some(false)) => Value::Boolean(false),
+                    _ => Value::Null,
+                })
+            }
+            Expr::Binary { op, left, right } => {
+                let left = left.eval(columns, values)?;
+                let right = right.eval(columns, values)?;
+                if left == Value::Null || right == Value::Null {
+                    return Ok(Value::Null);
+                }
+                match op {
+                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
+                        arithmetic(*op, &left, &right)
+                    }
+                    _ => {
+                        let ordering = compare(&left, &right)?;
+                        Ok(Value::Boolean(match op {
+                            BinaryOp::Eq => ordering == Ordering::Equal,
+                            BinaryOp::NotEq => ordering != Ordering::Equal,
+                            BinaryOp::Lt => ordering == Ordering::Less,
+                            BinaryOp::LtEq => ordering != Ordering::Greater,
+                            BinaryOp::Gt => ordering == Ordering::Greater,
+                            _ => ordering != Ordering::Less,
+                        }))
+                    }
+                }
+            }
+            Expr::IsNull { expr, negated } => {
+                let is_null = expr.eval(columns, values)? == Value::Null;
+                Ok(Value::Boolean(is_null != *negated))
+            }
+            Expr::InList { expr, list, negated } => {
+                let value = expr.eval(columns, values)?;
+                if value == Value::Null {
+                    return Ok(Value::Null);
+                }
+                let mut saw_null = false;
+                for item in list {
+                    let item = item.eval(columns, values)?;
+                    if item == Value::Null {
+                        saw_null = true;
+                    } else if compare(&value, &item)? == Ordering::Equal {
+                        return Ok(Value::Boolean(!negated));
+                    }
+                }
+                Ok(if saw_null { Value::Null } else { Value::Boolean(*negated) })
+            }
+            Expr::Function { name, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(columns, values))
+                    .collect::<Result<Vec<_>, _>>()?;
+                call_function(name, &args)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Literal(Value::Text(text)) => write!(f, "'{}'", text.replace('\'', "''")),
+            Expr::Literal(Value::Null) => write!(f, "NULL"),
+            Expr::Literal(Value::Boolean(b)) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+            Expr::Literal(value) => write!(f, "{}", value),
+            Expr::Column(name) => write!(f, "{}", name),
+            Expr::Negate(expr) => write!(f, "-{}", expr),
+            Expr::Not(expr) => write!(f, "NOT {}", expr),
+            Expr::Binary { op, left, right } => write!(f, "({} {} {})", left, op.symbol(), right),
+            Expr::IsNull { expr, negated } => {
+                write!(f, "{} IS {}NULL", expr, if *negated { "NOT " } else { "" })
+            }
+            Expr::InList { expr, list, negated } => {
+                let items: Vec<String> = list.iter().map(|e| e.to_string()).collect();
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{} {}IN ({})", expr, not, items.join(", "))
+            }
+            Expr::Function { name, args } => {
+                let args: Vec<String> = args.iter().map(|e| e.to_string()).collect();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+        }
+    }
+}
+
+fn as_bool(value: &Value) -> Result<Option<bool>, String> {
+    match value {
+        Value::Boolean(b) => Ok(Some(*b)),
+        Value::Null => Ok(None),
+        other => Err(format!("Expected a boolean, found {}", other)),
+    }
+}
+
+fn as_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::SmallInt(v) => Some(*v as i64),
+        Value::Int(v) => Some(*v as i64),
+        Value::BigInt(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Integers and DECIMALs as exact decimals.
+fn as_decimal(value: &Value) -> Option<Decimal> {
+    match value {
+        Value::Decimal(v) => Some(*v),
+        _ => as_integer(value).map(|v| Decimal { unscaled: v as i128, scale: 0 }),
+    }
+}
+
+fn as_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(v) => Some(*v as f64),
+        Value::Double(v) => Some(*v),
+        Value::Decimal(v) => v.to_string().parse().ok(),
+        _ => as_integer(value).map(|v| v as f64),
+    }
+}
+
+/// Type to parse a text literal as when it is compared with `value` (e.g. '2024-01-01' with a DATE).
+fn literal_type(value: &Value) -> Option<DataType> {
+    match value {
+        Value::Boolean(_) => Some(DataType::Boolean),
+        Value::Date(_) => Some(DataType::Date),
+        Value::Time(_) => Some(DataType::Time),
+        Value::Timestamp(_) => Some(DataType::Timestamp),
+        Value::TimestampTz(_) => Some(DataType::TimestampTz),
+        Value::Interval(_) => Some(DataType::Interval),
+        Value::Bytes(_) => Some(DataType::Bytea),
+        _ => None,
+    }
+}
+
+/// Compares two non-NULL values, converting numbers and text literals as needed.
+fn compare(left: &Value, right: &Value) -> Result<Ordering, String> {
+    let incomparable = || format!("Cannot compare {} with {}", left, right);
+
+    if let (Some(a), Some(b)) = (as_integer(left), as_integer(right)) {
+        return Ok(a.cmp(&b));
+    }
+    if let (Some(a), Some(b)) = (as_decimal(left), as_decimal(right)) {
+        return Ok(a.numeric_cmp(&b));
+    }
+    if let (Some(a), Some(b)) = (as_float(left), as_float(right)) {
+        return a.partial_cmp(&b).ok_or_else(incomparable);
+    }
+    match (left, right) {
+        (Value::Text(text), other) if literal_type(other).is_some() => {
+            let converted = parse_value(literal_type(other).unwrap(), text)?;
+            converted.partial_cmp(other).ok_or_else(incomparable)
+        }
+        (other, Value::Text(text)) if literal_type(other).is_some() => {
+            let converted = parse_value(literal_type(other).unwrap(), text)?;
+            other.partial_cmp(&converted).ok_or_else(incomparable)
+        }
+        _ => left.partial_cmp(right).ok_or_else(incomparable),
+    }
+}
+
+fn arithmetic(op: BinaryOp, left: &Value, right: &Value) -> Result<Value, String> {
+    let overflow = || format!("Arithmetic overflow in {} {} {}", left, op.symbol(), right);
+
+    if let (Some(a), Some(b)) = (as_integer(left), as_integer(right)) {
+        if matches!(op, BinaryOp::Div | BinaryOp::Mod) && b == 0 {
+            return Err("Division by zero".to_string());
+        }
+        let result = match op {
+            BinaryOp::Add => a.checked_add(b),
+            BinaryOp::Sub => a.checked_sub(b),
+            BinaryOp::Mul => a.checked_mul(b),
+            BinaryOp::Div => a.checked_div(b),
+            _ => a.checked_rem(b),
+        };
+        return result.map(Value::BigInt).ok_or_else(overflow);
+    }
+    if let (Some(a), Some(b)) = (as_decimal(left), as_decimal(right)) {
+        if matches!(op, BinaryOp::Div | BinaryOp::Mod) && b.unscaled == 0 {
+            return Err("Division by zero".to_string());
+        }
+        return decimal_arithmetic(op, a, b).map(Value::Decimal).ok_or_else(overflow);
+    }
+
+    let (Some(a), Some(b)) = (as_float(left), as_float(right)) else {
+        return Err(format!("Cannot apply '{}' to {} and {}", op.symbol(), left, right));
+    };
+    if matches!(op, BinaryOp::Div | BinaryOp::Mod) && b == 0.0 {
+        return Err("Division by zero".to_string());
+    }
+    Ok(Value::Double(match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Sub => a - b,
+        BinaryOp::Mul => a * b,
+        BinaryOp::Div => a / b,
+        _ => a % b,
+    }))
+}
+
+/// Smallest scale of a DECIMAL quotient (1 / 3 = 0.333333).
+const DECIMAL_DIVISION_SCALE: u8 = 6;
+
+/// Exact DECIMAL arithmetic. Sums, differences and remainders keep the larger scale, products the
+/// sum of the scales (at most `DECIMAL_MAX_PRECISION`), quotients at least `DECIMAL_DIVISION_SCALE`
+/// digits, rounded half away from zero. `None` on overflow.
+fn decimal_arithmetic(op: BinaryOp, a: Decimal, b: Decimal) -> Option<Decimal> {
+    let scale = a.scale.max(b.scale);
+    match op {
+        BinaryOp::Mul => {
+            let product = Decimal {
+                unscaled: a.unscaled.checked_mul(b.unscaled)?,
+                scale: a.scale + b.scale,
+            };
+            product.rescale(product.scale.min(DECIMAL_MAX_PRECISION))
+        }
+        BinaryOp::Div => {
+            // a / b at `scale` digits: a * 10^(scale + b.scale - a.scale) / b
+            let scale = scale.max(DECIMAL_DIVISION_SCALE);
+            let shift = scale + b.scale - a.scale;
+            if shift > DECIMAL_MAX_PRECISION {
+                return None;
+            }
+            let dividend = Decimal { unscaled: a.unscaled, scale: 0 }.rescale(shift)?.unscaled;
+            let quotient = dividend / b.unscaled;
+            let remainder = dividend % b.unscaled;
+            let unscaled = if remainder.unsigned_abs() * 2 >= b.unscaled.unsigned_abs() {
+                quotient + dividend.signum() * b.unscaled.signum()
+            } else {
+                quotient
+            };
+            Some(Decimal { unscaled, scale })
+        }
+        _ => {
+            let (x, y) = (a.rescale(scale)?.unscaled, b.rescale(scale)?.unscaled);
+            let unscaled = match op {
+                BinaryOp::Add => x.checked_add(y),
+                BinaryOp::Sub => x.checked_sub(y),
+                _ => x.checked_rem(y),
+            }?;
+            Some(Decimal { unscaled, scale })
+        }
+    }
+}
+
+fn call_function(name: &str, args: &[Value]) -> Result<Value, String> {
+    if args.contains(&Value::Null) {
+        return Ok(Value::Null);
+    }
+    if let "JSON_EXTRACT_PATH" | "JSON_EXTRACT_PATH_TEXT" = name {
+        let [json @ Value::Json(_), path @ ..] = args else {
+            return Err(format!("{}() takes a JSON value followed by path elements", name));
+        };
+        // Path elements are object keys or array indexes
+        let path = path
+            .iter()
+            .map(|step| match step {
+                Value::Text(key) => Ok(key.clone()),
+                index if as_integer(index).is_some() => Ok(index.to_string()),
+                other => Err(format!("Invalid JSON path element {}", other)),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        return Ok(match name {
+            "JSON_EXTRACT_PATH" => json_extract_path(json, &path),
+            _ => json_extract_path_text(json, &path),
+        });
+    }
+
+    let [arg] = args else {
+        return Err(format!("{}() takes exactly one argument", name));
+    };
+    match (name, arg) {
+        ("LENGTH", Value::Text(text)) => Ok(Value::BigInt(text.chars().count() as i64)),
+        ("LENGTH", Value::Bytes(bytes)) => Ok(Value::BigInt(bytes.len() as i64)),
+        ("LOWER", Value::Text(text)) => Ok(Value::Text(text.to_lowercase())),
+        ("UPPER", Value::Text(text)) => Ok(Value::Text(text.to_uppercase())),
+        ("ABS", value) if as_integer(value).is_some() => as_integer(value)
+            .unwrap()
+            .checked_abs()
+            .map(Value::BigInt)
+            .ok_or_else(|| "Arithmetic overflow in ABS()".to_string()),
+        ("ABS", Value::Decimal(v)) => v
+            .unscaled
+            .checked_abs()
+            .map(|unscaled| Value::Decimal(Decimal { unscaled, scale: v.scale }))
+            .ok_or_else(|| "Arithmetic overflow in ABS()".to_string()),
+        ("ABS", value) if as_float(value).is_some() => {
+            Ok(Value::Double(as_float(value).unwrap().abs()))
+        }
+        ("JSON_TYPEOF", json @ Value::Json(_)) => {
+            Ok(json_typeof(json).map_or(Value::Null, |kind| Value::Text(kind.to_string())))
+        }
+        _ => Err(format!("{}() cannot be applied to {}", name, arg)),
+    }
+}
+
+//-------------------------------------------
+// Tokenizer and parser
+//-------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Text(String),
+    Word(String), // Identifier or keyword, as written
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Text(t) => write!(f, "'{}'", t),
+            Token::Word(w) => write!(f, "{}", w),
+            Token::Symbol(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+const SYMBOLS: &[&str] = &[
+    "<=", ">=", "<>", "!=", "=", "<", ">", "+", "-", "*", "/", "%", "(", ")", ",",
+];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                // Exponent sign: 1e-10
+                if matches!(chars[i], 'e' | 'E') && matches!(chars.get(i + 1), Some('+' | '-')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            tokens.push(Token::Number(chars[start..i].iter().collect()));
+        } else if c == '\'' {
+            let mut literal = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(format!("Unterminated string in expression '{}'", text)),
+                    Some('\'') if chars.get(i + 1) == Some(&'\'') => {
+                        literal.push('\'');
+                        i += 2;
+                    }
+                    Some('\'') => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&ch) => {
+                        literal.push(ch);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(Token::Text(literal));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) else {
+                return Err(format!("Unexpected character '{}' in expression '{}'", c, text));
+            };
+            tokens.push(Token::Symbol(symbol));
+            i += symbol.len();
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Consumes the next token if it is the given keyword (case-insensitive).
+    fn keyword(&mut self, keyword: &str) -> bool {
+        if let Some(Token::Word(word)) = self.peek()
+            && word.eq_ignore_ascii_case(keyword)
+        {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        if self.peek() == Some(&Token::Symbol(SYMBOLS.iter().find(|s| **s == symbol).unwrap())) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        if self.symbol(symbol) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(token) => Err(format!("Expected '{}', found '{}'", symbol, token)),
+            None => Err(format!("Expected '{}' at end of expression", symbol)),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.keyword("OR") {
+            let right = self.and()?;
+            left = binary(BinaryOp::Or, left, right);
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.not()?;
+        while self.keyword("AND") {
+            let right = self.not()?;
+            left = binary(BinaryOp::And, left, right);
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.additive()?;
+
+        if self.keyword("IS") {
+            let negated = self.keyword("NOT");
+            if !self.keyword("NULL") {
+                return Err("Expected NULL after IS".to_string());
+            }
+            return Ok(Expr::IsNull { expr: Box::new(left), negated });
+        }
+
+        let negated = self.keyword("NOT");
+        if self.keyword("IN") {
+            self.expect("(")?;
+            let mut list = vec![self.or()?];
+            while self.symbol(",") {
+                list.push(self.or()?);
+            }
+            self.expect(")")?;
+            return Ok(Expr::InList { expr: Box::new(left), list, negated });
+        }
+        if negated {
+            return Err("Expected IN after NOT".to_string());
+        }
+
+        let op = match self.peek() {
+            Some(Token::Symbol("=")) => BinaryOp::Eq,
+            Some(Token::Symbol("<>" | "!=")) => BinaryOp::NotEq,
+            Some(Token::Symbol("<")) => BinaryOp::Lt,
+            Some(Token::Symbol("<=")) => BinaryOp::LtEq,
+            Some(Token::Symbol(">")) => BinaryOp::Gt,
+            Some(Token::Symbol(">=")) => BinaryOp::GtEq,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.additive()?;
+        Ok(binary(op, left, right))
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut left = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("+")) => BinaryOp::Add,
+                Some(Token::Symbol("-")) => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.term()?;
+            left = binary(op, left, right);
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("*")) => BinaryOp::Mul,
+                Some(Token::Symbol("/")) => BinaryOp::Div,
+                Some(Token::Symbol("%")) => BinaryOp::Mod,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.unary()?;
+            left = binary(op, left, right);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.symbol("-") {
+            return Ok(Expr::Negate(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(number)) => {
+                if let Ok(v) = number.parse::<i64>() {
+                    return Ok(Expr::Literal(Value::BigInt(v)));
+                }
+                // Fixed-point literals are exact; exponents and overlong literals are doubles
+                let scale = number.split_once('.').map_or(0, |(_, frac)| frac.len());
+                if scale <= DECIMAL_MAX_PRECISION as usize
+                    && let Ok(v) = Decimal::parse(&number, DECIMAL_MAX_PRECISION, scale as u8)
+                {
+                    return Ok(Expr::Literal(Value::Decimal(v)));
+                }
+                number
+                    .parse::<f64>()
+                    .map(|v| Expr::Literal(Value::Double(v)))
+                    .map_err(|_| format!("Invalid number '{}'", number))
+            }
+            Some(Token::Text(text)) => Ok(Expr::Literal(Value::Text(text))),
+            Some(Token::Symbol("(")) => {
+                let expr = self.or()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Some(Token::Word(word)) => {
+                match word.to_ascii_uppercase().as_str() {
+                    "TRUE" => return Ok(Expr::Literal(Value::Boolean(true))),
+                    "FALSE" => return Ok(Expr::Literal(Value::Boolean(false))),
+                    "NULL" => return Ok(Expr::Literal(Value::Null)),
+                    _ => {}
+                }
+                if !self.symbol("(") {
+                    return Ok(Expr::Column(word));
+                }
+
+                let name = word.to_ascii_uppercase();
+                if !FUNCTIONS.contains(&name.as_str()) {
+                    return Err(format!("Unknown function '{}'", word));
+                }
+                let mut args = Vec::new();
+                if !self.symbol(")") {
+                    args.push(self.or()?);
+                    while self.symbol(",") {
+                        args.push(self.or()?);
+                    }
+                    self.expect(")")?;
+                }
+                Ok(Expr::Function { name, args })
+            }
+            Some(token) => Err(format!("Unexpected '{}' in expression", token)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+    Expr::Binary {
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+    }
+}
@@ -1,6 +1,7 @@
 use std::fs::File;
-use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 
+use crate::error::Result;
 use crate::page::{Page, init_page, page_free_space};
 use crate::disk::{read_page, write_page};
 
@@ -13,14 +14,14 @@ pub const EXTENT_SIZE: u32 = 16;   // allocate 16 pages at a time
 // bytes[4..8]   = total_extents
 //-------------------------------------------
 
-pub fn read_u32_at(file: &mut File, pos: u64) -> io::Result<u32> {
+pub fn read_u32_at(file: &mut File, pos: u64) -> Result<u32> {
     file.seek(SeekFrom::Start(pos))?;
     let mut buf = [0u8; 4];
     file.read_exact(&mut buf)?;
     Ok(u32::from_le_bytes(buf))
 }
 
-pub fn write_u32_at(file: &mut File, pos: u64, value: u32) -> io::Result<()> {
+pub fn write_u32_at(file: &mut File, pos: u64, value: u32) -> Result<()> {
     file.seek(SeekFrom::Start(pos))?;
     file.write_all(&value.to_le_bytes())?;
     Ok(())
@@ -30,19 +31,19 @@ pub fn write_u32_at(file: &mut File, pos: u64, value: u32) -> io::Result<()> {
 //-----------------------------------------------------
 // Reads total pages and total extents from the header
 //-----------------------------------------------------
-pub fn read_total_pages(file: &mut File) -> io::Result<u32> {
+pub fn read_total_pages(file: &mut File) -> Result<u32> {
     read_u32_at(file, 0)
 }
 
-pub fn read_total_extents(file: &mut File) -> io::Result<u32> {
+pub fn read_total_extents(file: &mut File) -> Result<u32> {
     read_u32_at(file, 4)
 }
 
-pub fn write_total_pages(file: &mut File, value: u32) -> io::Result<()> {
+pub fn write_total_pages(file: &mut File, value: u32) -> Result<()> {
     write_u32_at(file, 0, value)
 }
 
-pub fn write_total_extents(file: &mut File, value: u32) -> io::Result<()> {
+pub fn write_total_extents(file: &mut File, value: u32) -> Result<()> {
     write_u32_at(file, 4, value)
 }
 
@@ -50,9 +51,9 @@ pub fn write_total_extents(file: &mut File, value: u32) -> io::Result<()> {
 //-----------------------------------------------------
 // Create a new extent → allocate 16 new pages
 //-----------------------------------------------------
-pub fn allocate_extent(file: &mut File) -> io::Result<u32> {
+pub fn allocate_extent(file: &mut File) -> Result<u32> {
     let mut total_pages = read_total_pages(file)?;
-    let mut total_extents = read_total_extents(file)?;
+    let total_extents = read_total_extents(file)?;
 
     let new_extent_id = total_extents;
 
@@ -76,7 +77,7 @@ pub fn allocate_extent(file: &mut File) -> io::Result<u32> {
 // Finds a page inside the last extent that has free space
 // If the extent is completely full → allocate a new one
 //-----------------------------------------------------
-pub fn find_page_for_insert(file: &mut File, required: u32) -> io::Result<u32> {
+pub fn find_page_for_insert(file: &mut File, required: u32) -> Result<u32> {
     // Read header
     let total_pages = read_total_pages(file)?;
     let total_extents = read_total_extents(file)?;
@@ -0,0 +1,192 @@
+use std::io;
+
+use serde_json::{Map, Number, Value as JsonValue};
+
+use crate::value::Value;
+
+//-------------------------------------------
+// Binary JSON Layout (one tag byte per node):
+// 0 = null, 1 = false, 2 = true
+// 3 = i64, 4 = u64, 5 = f64       (8 bytes little-endian)
+// 6 = string                      (u32 byte length + UTF-8 bytes)
+// 7 = array                       (u32 element count + elements)
+// 8 = object                      (u32 member count + (u32 key length, key, value)...)
+// Object keys are stored sorted and unique, like JSONB.
+//-------------------------------------------
+const TAG_NULL: u8 = 0;
+const TAG_FALSE: u8 = 1;
+const TAG_TRUE: u8 = 2;
+const TAG_I64: u8 = 3;
+const TAG_U64: u8 = 4;
+const TAG_F64: u8 = 5;
+const TAG_STRING: u8 = 6;
+const TAG_ARRAY: u8 = 7;
+const TAG_OBJECT: u8 = 8;
+
+pub const MAX_JSON_DEPTH: usize = 128; // Same nesting limit serde_json applies when parsing
+
+/// Validates JSON text and returns the parsed document.
+pub fn parse_json(text: &str) -> Result<JsonValue, String> {
+    serde_json::from_str(text).map_err(|e| format!("'{}' is not valid JSON: {}", text, e))
+}
+
+/// Appends the binary form of a JSON document.
+pub fn encode_json(json: &JsonValue, out: &mut Vec<u8>) {
+    match json {
+        JsonValue::Null => out.push(TAG_NULL),
+        JsonValue::Bool(false) => out.push(TAG_FALSE),
+        JsonValue::Bool(true) => out.push(TAG_TRUE),
+        JsonValue::Number(n) => {
+            if let Some(v) = n.as_i64() {
+                out.push(TAG_I64);
+                out.extend_from_slice(&v.to_le_bytes());
+            } else if let Some(v) = n.as_u64() {
+                out.push(TAG_U64);
+                out.extend_from_slice(&v.to_le_bytes());
+            } else {
+                out.push(TAG_F64);
+                out.extend_from_slice(&n.as_f64().unwrap_or_default().to_le_bytes());
+            }
+        }
+        JsonValue::String(s) => {
+            out.push(TAG_STRING);
+            encode_str(s, out);
+        }
+        JsonValue::Array(items) => {
+            out.push(TAG_ARRAY);
+            out.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            for item in items {
+                encode_json(item, out);
+            }
+        }
+        JsonValue::Object(members) => {
+            out.push(TAG_OBJECT);
+            out.extend_from_slice(&(members.len() as u32).to_le_bytes());
+            for (key, value) in members {
+                encode_str(key, out);
+                encode_json(value, out);
+            }
+        }
+    }
+}
+
+fn encode_str(s: &str, out: &mut Vec<u8>) {
+    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+/// Reads one binary JSON document at `cursor`, advancing it.
+pub fn decode_json(data: &[u8], cursor: &mut usize) -> io::Result<JsonValue> {
+    decode_node(data, cursor, 0)
+}
+
+fn decode_node(data: &[u8], cursor: &mut usize, depth: usize) -> io::Result<JsonValue> {
+    if depth > MAX_JSON_DEPTH {
+        return Err(invalid("JSON value is nested too deeply"));
+    }
+
+    let tag = take(data, cursor, 1)?[0];
+    let json = match tag {
+        TAG_NULL => JsonValue::Null,
+        TAG_FALSE => JsonValue::Bool(false),
+        TAG_TRUE => JsonValue::Bool(true),
+        TAG_I64 => JsonValue::from(i64::from_le_bytes(take(data, cursor, 8)?.try_into().unwrap())),
+        TAG_U64 => JsonValue::from(u64::from_le_bytes(take(data, cursor, 8)?.try_into().unwrap())),
+        TAG_F64 => {
+            let v = f64::from_le_bytes(take(data, cursor, 8)?.try_into().unwrap());
+            Number::from_f64(v)
+                .map(JsonValue::Number)
+                .ok_or_else(|| invalid("JSON number is not finite"))?
+        }
+        TAG_STRING => JsonValue::String(decode_str(data, cursor)?),
+        TAG_ARRAY => {
+            let count = decode_count(data, cursor)?;
+            let mut items = Vec::new();
+            for _ in 0..count {
+                items.push(decode_node(data, cursor, depth + 1)?);
+            }
+            JsonValue::Array(items)
+        }
+        TAG_OBJECT => {
+            let count = decode_count(data, cursor)?;
+            let mut members = Map::new();
+            for _ in 0..count {
+                let key = decode_str(data, cursor)?;
+                members.insert(key, decode_node(data, cursor, depth + 1)?);
+            }
+            JsonValue::Object(members)
+        }
+        _ => return Err(invalid(&format!("Unknown JSON tag {}", tag))),
+    };
+    Ok(json)
+}
+
+fn decode_count(data: &[u8], cursor: &mut usize) -> io::Result<usize> {
+    Ok(u32::from_le_bytes(take(data, cursor, 4)?.try_into().unwrap()) as usize)
+}
+
+fn decode_str(data: &[u8], cursor: &mut usize) -> io::Result<String> {
+    let length = decode_count(data, cursor)?;
+    let bytes = take(data, cursor, length)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| invalid("JSON string is not valid UTF-8"))
+}
+
+fn take<'a>(data: &'a [u8], cursor: &mut usize, length: usize) -> io::Result<&'a [u8]> {
+    let bytes = data
+        .get(*cursor..*cursor + length)
+        .ok_or_else(|| invalid("JSON value is truncated"))?;
+    *cursor += length;
+    Ok(bytes)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Follows a path of object keys / array indexes (like `jsonb_extract_path`).
+/// Returns NULL when the value is not JSON or the path does not exist.
+pub fn json_extract_path(value: &Value, path: &[&str]) -> Value {
+    let Value::Json(json) = value else {
+        return Value::Null;
+    };
+
+    let mut current = json;
+    for step in path {
+        let next = match current {
+            JsonValue::Object(members) => members.get(*step),
+            JsonValue::Array(items) => step.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        };
+        match next {
+            Some(next) => current = next,
+            None => return Value::Null,
+        }
+    }
+    Value::Json(current.clone())
+}
+
+/// Like `json_extract_path`, but returns strings unquoted and other values as JSON text.
+pub fn json_extract_path_text(value: &Value, path: &[&str]) -> Value {
+    match json_extract_path(value, path) {
+        Value::Json(JsonValue::String(s)) => Value::Text(s),
+        Value::Json(JsonValue::Null) | Value::Null => Value::Null,
+        Value::Json(json) => Value::Text(json.to_string()),
+        _ => Value::Null,
+    }
+}
+
+/// Name of the JSON type of a value (like `jsonb_typeof`), or None for non-JSON values.
+pub fn json_typeof(value: &Value) -> Option<&'static str> {
+    let Value::Json(json) = value else {
+        return None;
+    };
+    let name = match json {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    };
+    Some(name)
+}
@@ -1,6 +1,21 @@
 pub mod disk;
 pub mod page;
 pub mod table;
+pub mod config;
+pub mod error;
+pub mod lock;
 pub mod catalog;
 pub mod buffer;
-pub mod extent;
\ No newline at end of file
+pub mod extent;
+pub mod tuple;
+pub mod csv_import;
+pub mod export;
+pub mod bulk_load;
+pub mod value;
+pub mod datetime;
+pub mod json;
+pub mod toast;
+pub mod expr;
+pub mod constraint;
+pub mod system_catalog;
+pub mod stats;
@@ -0,0 +1,98 @@
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use tracing::warn;
+
+use crate::config::StorageConfig;
+
+//-------------------------------------------
+// Data directory lock ({root}/rook.lock):
+// A process holds an exclusive flock on the lock file for as long as it uses the data directory
+// and writes its PID into the file. The file is emptied (not deleted) on release, so a PID found
+// in it when the lock is free was left by a process that exited without releasing it.
+// The OS drops the flock when a process dies, so a stale file never blocks a new process.
+//-------------------------------------------
+
+/// Exclusive lock on a data directory, released when dropped.
+pub struct DataDirLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl DataDirLock {
+    /// Locks the data directory of `config`, creating the directory and lock file if needed.
+    /// Fails with `ResourceBusy` if another process (or another lock in this process) holds it.
+    pub fn acquire(config: &StorageConfig) -> io::Result<Self> {
+        // Step 1: Open the lock file without truncating it, its content may belong to the holder
+        fs::create_dir_all(&config.root)?;
+        let path = config.lock_file();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        // Step 2: Take the lock, naming the holder if it is taken
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let holder = match read_pid(&mut file) {
+                    Some(pid) => format!("another RookDB process (PID {})", pid),
+                    None => "another RookDB process".to_string(),
+                };
+                return Err(io::Error::new(
+                    io::ErrorKind::ResourceBusy,
+                    format!(
+                        "Data directory {} is in use by {}; stop it or choose another directory with --data-dir",
+                        config.root.display(),
+                        holder
+                    ),
+                ));
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("Failed to lock {}: {}", path.display(), e),
+                ));
+            }
+        }
+
+        // Step 3: A PID left in a free lock file is a stale lock of a process that did not exit cleanly
+        if let Some(pid) = read_pid(&mut file) {
+            warn!("Removing stale lock of process {} from {}", pid, path.display());
+        }
+
+        // Step 4: Record our PID
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        writeln!(file, "{}", process::id())?;
+        file.sync_all()?;
+
+        Ok(Self { file, path })
+    }
+
+    /// Path of the lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for DataDirLock {
+    fn drop(&mut self) {
+        // Empty the file before unlocking so the next process does not report a stale lock
+        let _ = self.file.set_len(0);
+        let _ = self.file.sync_all();
+        let _ = self.file.unlock();
+    }
+}
+
+/// PID recorded in the lock file, or None if it is empty.
+fn read_pid(file: &mut File) -> Option<u32> {
+    let mut content = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut content).ok()?;
+    content.trim().parse().ok()
+}
@@ -1,28 +1,78 @@
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 // use std::io::{self, Read, Seek, SeekFrom};
 use std::io::{self, Write};
 
 // use storage_manager::disk::{create_page, read_page};
 // use storage_manager::disk::create_page;
 use storage_manager::buffer::BufferManager;
+use storage_manager::bulk_load::load_csv_parallel;
 use storage_manager::catalog::{
-    Column, create_database, create_table, init_catalog, load_catalog, show_databases, show_tables,
+    Column, Constraint, DataType, StorageStrategy, alter_table_add_column,
+    alter_table_add_constraint, alter_table_drop_column, alter_table_drop_constraint,
+    alter_table_rename, alter_table_rename_column, alter_table_set_storage, analyze_table, bootstrap_catalog,
+    Catalog, create_database, create_table, drop_database, drop_table, list_databases, list_tables,
+    load_catalog, parse_constraint,
 };
-use storage_manager::page::{load_csv_and_insert, page_count, show_tuples};
+use storage_manager::config::{StorageConfig, log_level_from_args};
+use storage_manager::csv_import::{CsvOptions, LoadMode};
+use storage_manager::error;
+use storage_manager::export::{export_csv, export_json};
+use storage_manager::lock::DataDirLock;
+use storage_manager::page::{dump_tuples, page_count};
 // use storage_manager::table::init_table;
 
-fn main() -> io::Result<()> {
+fn main() -> error::Result<()> {
     println!("--------------------------------------");
     println!("Welcome to RookDB");
     println!("--------------------------------------\n");
 
-    // Initialize catalog if missing
-    println!("Initializing Catalog File...\n");
-    init_catalog();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Log events of the storage manager: --log-level <level>, info by default
+    let log_level = match log_level_from_args(&args) {
+        Ok(level) => level,
+        Err(e) => {
+            eprintln!("Cannot start: {}", e);
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, e).into());
+        }
+    };
+    tracing_subscriber::fmt().with_max_level(log_level).with_target(false).init();
+
+    // Data directory: --data-dir <path>, then ROOKDB_DATA_DIR, then ./database
+    let config = match StorageConfig::from_args(&args) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Cannot start: {}", e);
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, e).into());
+        }
+    };
+    println!("Data directory: {}\n", config.root.display());
+
+    // Keep other processes out of the data directory until we exit
+    let _lock = match DataDirLock::acquire(&config) {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("Cannot start: {}", e);
+            return Err(e.into());
+        }
+    };
+
+    // Create the catalog system tables if missing
+    println!("Bootstrapping Catalog...\n");
+    if let Err(e) = bootstrap_catalog(&config) {
+        eprintln!("Cannot start: {}", e);
+        return Err(e);
+    }
 
     // Load catalog from disk
     println!("Loading Catalog...\n");
-    let mut catalog = load_catalog();
+    let mut catalog = match load_catalog(&config) {
+        Ok(catalog) => catalog,
+        Err(e) => {
+            eprintln!("Cannot start: {}", e);
+            return Err(e);
+        }
+    };
 
     let mut buffer_manager = BufferManager::new();
 
@@ -40,6 +90,12 @@ fn main() -> io::Result<()> {
         println!("6. Load CSV");
         println!("7. Show Tuples");
         println!("8. Exit");
+        println!("9. Drop Table");
+        println!("10. Drop Database");
+        println!("11. Alter Table");
+        println!("12. Analyze Table");
+        println!("13. Show Table Statistics");
+        println!("14. Export Table");
         println!("=============================");
 
         // if let Some(ref db) = current_db {
@@ -57,7 +113,7 @@ fn main() -> io::Result<()> {
 
         match choice {
             "1" => {
-                show_databases(&catalog);
+                print_databases(&catalog);
             }
             // -----------------------
             // Option 1: Create Database
@@ -71,10 +127,8 @@ fn main() -> io::Result<()> {
 
                 if db_name.is_empty() {
                     println!("Database name cannot be empty.");
-                } else if create_database(&mut catalog, db_name) {
-                    println!("Database '{}' created successfully.", db_name);
-                } else {
-                    println!("Failed to create database '{}'.", db_name);
+                } else if let Err(e) = create_database(&mut catalog, db_name) {
+                    println!("Failed to create database '{}': {}", db_name, e);
                 }
             }
 
@@ -116,7 +170,7 @@ fn main() -> io::Result<()> {
                     }
                 };
 
-                show_tables(&catalog, &db_name);
+                print_tables(&catalog, &db_name);
             }
 
             // -----------------------
@@ -138,12 +192,16 @@ fn main() -> io::Result<()> {
                 io::stdin().read_line(&mut table_name)?;
                 let table_name = table_name.trim().to_string();
 
-                println!("\nEnter columns in the format: column_name:data_type");
+                println!("\nEnter columns in the format: column_name:data_type[:option]...");
+                println!("Supported types: {}", DataType::SUPPORTED.join(", "));
+                println!("Options: NOT NULL, PRIMARY KEY, UNIQUE, CHECK (expr), REFERENCES table [(column)] [ON DELETE action],");
+                println!("         DEFAULT value (must come last)");
                 println!("(Press Enter on an empty line to finish)\n");
 
                 let mut columns: Vec<Column> = Vec::new();
+                let mut constraints: Vec<Constraint> = Vec::new();
 
-                loop {
+                'columns: loop {
                     let mut input = String::new();
                     print!("Enter column (name:type): ");
                     io::stdout().flush()?;
@@ -155,18 +213,54 @@ fn main() -> io::Result<()> {
                     }
 
                     let parts: Vec<&str> = input.split(':').collect();
-                    if parts.len() != 2 {
-                        println!("Invalid format. Please use name:type (e.g. id:INT)");
+                    if parts.len() < 2 {
+                        println!("Invalid format. Please use name:type (e.g. id:INT or id:INT:PRIMARY KEY)");
                         continue;
                     }
 
-                    let column_name = parts[0].trim().to_string();
-                    let column_type = parts[1].trim().to_string();
-
-                    columns.push(Column {
-                        name: column_name,
-                        data_type: column_type,
-                    });
+                    let column_name = parts[0].trim();
+                    let column_type = match DataType::parse(parts[1]) {
+                        Ok(data_type) => data_type,
+                        Err(e) => {
+                            println!("{}", e);
+                            continue;
+                        }
+                    };
+
+                    let mut column = Column::new(column_name, column_type);
+                    let mut column_constraints = Vec::new();
+                    for (i, option) in parts.iter().enumerate().skip(2) {
+                        let option = option.trim();
+                        let upper = option.to_ascii_uppercase();
+                        if upper == "NULL" {
+                            continue;
+                        } else if upper.split_whitespace().eq(["NOT", "NULL"]) {
+                            column.not_null = true;
+                        } else if upper.starts_with("DEFAULT ") {
+                            // The default may itself contain ':' (e.g. a TIME), so it takes the rest
+                            let rest = parts[i..].join(":");
+                            column.default = Some(rest.trim()["DEFAULT".len()..].trim().to_string());
+                            break;
+                        } else {
+                            // Column constraints are table constraints on this one column
+                            let text = match upper.as_str() {
+                                "PRIMARY KEY" | "UNIQUE" => format!("{} ({})", option, column_name),
+                                _ if upper.starts_with("REFERENCES ") => {
+                                    format!("FOREIGN KEY ({}) {}", column_name, option)
+                                }
+                                _ => option.to_string(),
+                            };
+                            match parse_constraint(&table_name, &text) {
+                                Ok(constraint) => column_constraints.push(constraint),
+                                Err(e) => {
+                                    println!("{}", e);
+                                    continue 'columns;
+                                }
+                            }
+                        }
+                    }
+                    columns.push(column);
+                    constraints.extend(column_constraints);
                 }
 
                 if columns.is_empty() {
@@ -174,8 +268,37 @@ fn main() -> io::Result<()> {
                     continue;
                 }
 
-                create_table(&mut catalog, &db_name, &table_name, columns);
-                buffer_manager.load_table_on_create(&db_name, &table_name)?;
+                println!("\nEnter table constraints: PRIMARY KEY (a, b), UNIQUE (a), CHECK (expr),");
+                println!("FOREIGN KEY (a) REFERENCES table [(b)] [ON DELETE RESTRICT|CASCADE|SET NULL]");
+                println!("(Optionally prefixed by CONSTRAINT name; press Enter on an empty line to finish)\n");
+                loop {
+                    let mut input = String::new();
+                    print!("Enter constraint: ");
+                    io::stdout().flush()?;
+                    io::stdin().read_line(&mut input)?;
+                    let input = input.trim();
+
+                    if input.is_empty() {
+                        break;
+                    }
+                    match parse_constraint(&table_name, input) {
+                        Ok(constraint) => constraints.push(constraint),
+                        Err(e) => println!("{}", e),
+                    }
+                }
+
+                if let Err(e) = create_table(&mut catalog, &db_name, &table_name, columns) {
+                    println!("Cannot create table '{}': {}", table_name, e);
+                    continue;
+                }
+                for constraint in constraints {
+                    if let Err(e) = alter_table_add_constraint(&mut catalog, &db_name, &table_name, constraint) {
+                        println!("{}", e);
+                    }
+                }
+                if let Err(e) = buffer_manager.load_table_on_create(&catalog, &db_name, &table_name) {
+                    println!("Failed to load table '{}': {}", table_name, e);
+                }
             }
 
             // -----------------------
@@ -200,30 +323,86 @@ fn main() -> io::Result<()> {
                 io::stdin().read_line(&mut csv_path)?;
                 let csv_path = csv_path.trim();
 
-                let table_path = format!("database/base/{}/{}.dat", db_name, table_name);
+                print!("Enter CSV options (delimiter=;|tab null=\\N encoding=latin1 header=false mode=strict max_errors=10 reject=bad.csv workers=4, empty for defaults): ");
+                io::stdout().flush()?;
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                let mut options = CsvOptions::default();
+                let mut workers = 1;
+                let mut invalid = None;
+                for option in input.split_whitespace() {
+                    match option.split_once('=') {
+                        Some(("delimiter", "tab")) => options.delimiter = b'\t',
+                        Some(("delimiter", d)) if d.len() == 1 => options.delimiter = d.as_bytes()[0],
+                        Some(("null", null)) => options.null_strings = vec![null.to_string()],
+                        Some(("encoding", label)) => match CsvOptions::encoding_for_label(label) {
+                            Ok(encoding) => options.encoding = encoding,
+                            Err(e) => invalid = Some(e),
+                        },
+                        Some(("header", header)) => match header.parse() {
+                            Ok(header) => options.header = header,
+                            Err(_) => invalid = Some(format!("Invalid header option '{}'", header)),
+                        },
+                        Some(("mode", "strict")) => options.mode = LoadMode::Strict,
+                        Some(("mode", "permissive")) => options.mode = LoadMode::Permissive,
+                        Some(("max_errors", max)) => match max.parse() {
+                            Ok(max) => options.max_errors = Some(max),
+                            Err(_) => invalid = Some(format!("Invalid max_errors option '{}'", max)),
+                        },
+                        Some(("reject", path)) => options.reject_file = Some(path.into()),
+                        Some(("workers", count)) => match count.parse() {
+                            Ok(count) if count > 0 => workers = count,
+                            _ => invalid = Some(format!("Invalid workers option '{}'", count)),
+                        },
+                        _ => invalid = Some(format!("Invalid CSV option '{}'", option)),
+                    }
+                }
+                if let Some(e) = invalid {
+                    println!("{}", e);
+                    continue;
+                }
+
+                let table_path = catalog.table_file(&db_name, table_name)?;
                 let mut file = OpenOptions::new()
                     .read(true)
                     .write(true)
                     .open(&table_path)?;
 
-                let catalog = load_catalog();
+                let catalog = load_catalog(&config)?;
 
                 // Start the timer ⏱️
                 use std::time::Instant;
                 let start = Instant::now();
-                // load_csv_and_insert(&catalog, &db_name, table_name, &mut file, csv_path)?;
-                // Single call: load CSV, update header, flush to disk
-                buffer_manager.load_csv_to_buffer(&catalog, &db_name, &table_name, csv_path)?;
+                // load_csv_and_insert(&catalog, &db_name, table_name, &mut file, csv_path, &options)?;
+                // Single call: load CSV, update header, flush to disk (parsed on worker threads if asked)
+                let loaded = if workers > 1 {
+                    load_csv_parallel(&catalog, &db_name, table_name, csv_path, &options, workers)
+                } else {
+                    buffer_manager.load_csv_to_buffer(&catalog, &db_name, table_name, csv_path, &options)
+                };
+                let report = match loaded {
+                    Ok(report) => report,
+                    Err(e) => {
+                        println!("Cannot load CSV: {}", e);
+                        continue;
+                    }
+                };
 
                 // Stop the timer
                 let duration = start.elapsed();
 
                 // Print the result
                 println!(
-                    "\n CSV loaded successfully into '{}' in {:.3} seconds.",
+                    "\n CSV loaded into '{}' in {:.3} seconds ({:.0} rows/s, {:.2} MB/s): {}.",
                     table_name,
-                    duration.as_secs_f64()
+                    duration.as_secs_f64(),
+                    report.rows_per_sec(duration),
+                    report.mb_per_sec(duration),
+                    report
                 );
+                for rejected in &report.rejected {
+                    println!("  Row {}: {}", rejected.row, rejected.reason);
+                }
 
                 println!("Page Count: {}", page_count(&mut file)?);
             }
@@ -245,18 +424,16 @@ fn main() -> io::Result<()> {
                 io::stdin().read_line(&mut table_name)?;
                 let table_name = table_name.trim();
 
-                let table_path = format!("database/base/{}/{}.dat", db_name, table_name);
+                let table_path = catalog.table_file(&db_name, table_name)?;
                 let mut file = OpenOptions::new()
                     .read(true)
                     .write(true)
                     .open(&table_path)?;
 
-                let catalog = load_catalog();
-                show_tuples(&catalog, &db_name, table_name, &mut file)?;
+                let catalog = load_catalog(&config)?;
+                print_tuples(&catalog, &db_name, table_name, &mut file)?;
             }
 
-            
-
             // -----------------------
             // Exit
             // -----------------------
@@ -265,6 +442,276 @@ fn main() -> io::Result<()> {
                 break;
             }
 
+            // -----------------------
+            // Option 9: Drop Table
+            // -----------------------
+            "9" => {
+                let db_name = match &current_db {
+                    Some(name) => name.clone(),
+                    None => {
+                        println!("No database selected. Please select a database first.");
+                        continue;
+                    }
+                };
+
+                println!("Enter table name to drop: ");
+                let mut table_name = String::new();
+                io::stdin().read_line(&mut table_name)?;
+                let table_name = table_name.trim();
+
+                if let Err(e) = drop_table(&mut catalog, &mut buffer_manager, &db_name, table_name) {
+                    println!("Cannot drop table '{}': {}", table_name, e);
+                }
+            }
+
+            // -----------------------
+            // Option 10: Drop Database
+            // -----------------------
+            "10" => {
+                let mut db_name = String::new();
+                print!("\nEnter database name to drop: ");
+                io::stdout().flush()?;
+                io::stdin().read_line(&mut db_name)?;
+                let db_name = db_name.trim();
+
+                match drop_database(&mut catalog, &mut buffer_manager, db_name) {
+                    Ok(()) if current_db.as_deref() == Some(db_name) => current_db = None,
+                    Ok(()) => {}
+                    Err(e) => println!("Cannot drop database '{}': {}", db_name, e),
+                }
+            }
+
+            // -----------------------
+            // Option 11: Alter Table
+            // -----------------------
+            "11" => {
+                let db_name = match &current_db {
+                    Some(name) => name.clone(),
+                    None => {
+                        println!("No database selected. Please select a database first.");
+                        continue;
+                    }
+                };
+
+                println!("Enter table name to alter: ");
+                let mut table_name = String::new();
+                io::stdin().read_line(&mut table_name)?;
+                let table_name = table_name.trim();
+
+                println!("1. Add Column");
+                println!("2. Drop Column");
+                println!("3. Rename Column");
+                println!("4. Rename Table");
+                println!("5. Set Column Storage");
+                println!("6. Add Constraint");
+                println!("7. Drop Constraint");
+                print!("Enter alter option: ");
+                io::stdout().flush()?;
+                let mut alter_choice = String::new();
+                io::stdin().read_line(&mut alter_choice)?;
+
+                let altered = match alter_choice.trim() {
+                    "1" => {
+                        print!("Enter column (name:type[:default]): ");
+                        io::stdout().flush()?;
+                        let mut input = String::new();
+                        io::stdin().read_line(&mut input)?;
+
+                        let parts: Vec<&str> = input.trim().splitn(3, ':').collect();
+                        if parts.len() < 2 {
+                            println!("Invalid format. Please use name:type[:default] (e.g. age:INT:0)");
+                            continue;
+                        }
+
+                        let data_type = match DataType::parse(parts[1]) {
+                            Ok(data_type) => data_type,
+                            Err(e) => {
+                                println!("{}", e);
+                                continue;
+                            }
+                        };
+
+                        let mut column = Column::new(parts[0].trim(), data_type);
+                        column.default = parts.get(2).map(|d| d.trim().to_string());
+                        alter_table_add_column(&mut catalog, &db_name, table_name, column)
+                    }
+                    "2" => {
+                        print!("Enter column name to drop: ");
+                        io::stdout().flush()?;
+                        let mut column_name = String::new();
+                        io::stdin().read_line(&mut column_name)?;
+                        alter_table_drop_column(&mut catalog, &db_name, table_name, column_name.trim())
+                    }
+                    "3" => {
+                        print!("Enter column to rename (old:new): ");
+                        io::stdout().flush()?;
+                        let mut input = String::new();
+                        io::stdin().read_line(&mut input)?;
+
+                        let Some((old, new)) = input.trim().split_once(':') else {
+                            println!("Invalid format. Please use old:new");
+                            continue;
+                        };
+                        alter_table_rename_column(&mut catalog, &db_name, table_name, old.trim(), new.trim())
+                    }
+                    "4" => {
+                        print!("Enter new table name: ");
+                        io::stdout().flush()?;
+                        let mut new_name = String::new();
+                        io::stdin().read_line(&mut new_name)?;
+                        alter_table_rename(
+                            &mut catalog,
+                            &mut buffer_manager,
+                            &db_name,
+                            table_name,
+                            new_name.trim(),
+                        )
+                    }
+                    "5" => {
+                        print!("Enter column and strategy (column:PLAIN|MAIN|EXTERNAL|EXTENDED): ");
+                        io::stdout().flush()?;
+                        let mut input = String::new();
+                        io::stdin().read_line(&mut input)?;
+
+                        let Some((column_name, strategy)) = input.trim().split_once(':') else {
+                            println!("Invalid format. Please use column:strategy (e.g. body:EXTERNAL)");
+                            continue;
+                        };
+                        match StorageStrategy::parse(strategy) {
+                            Ok(storage) => alter_table_set_storage(
+                                &mut catalog,
+                                &db_name,
+                                table_name,
+                                column_name.trim(),
+                                storage,
+                            ),
+                            Err(e) => {
+                                println!("{}", e);
+                                continue;
+                            }
+                        }
+                    }
+                    "6" => {
+                        print!("Enter constraint (PRIMARY KEY (a, b) | UNIQUE (a) | CHECK (expr) | FOREIGN KEY (a) REFERENCES t (b)): ");
+                        io::stdout().flush()?;
+                        let mut input = String::new();
+                        io::stdin().read_line(&mut input)?;
+
+                        match parse_constraint(table_name, &input) {
+                            Ok(constraint) => alter_table_add_constraint(&mut catalog, &db_name, table_name, constraint),
+                            Err(e) => {
+                                println!("{}", e);
+                                continue;
+                            }
+                        }
+                    }
+                    "7" => {
+                        print!("Enter constraint name to drop: ");
+                        io::stdout().flush()?;
+                        let mut constraint_name = String::new();
+                        io::stdin().read_line(&mut constraint_name)?;
+                        alter_table_drop_constraint(&mut catalog, &db_name, table_name, constraint_name.trim())
+                    }
+                    _ => {
+                        println!("Invalid alter option.");
+                        continue;
+                    }
+                };
+                if let Err(e) = altered {
+                    println!("Cannot alter table '{}': {}", table_name, e);
+                }
+            }
+
+            // -----------------------
+            // Option 12: Analyze Table
+            // -----------------------
+            "12" => {
+                let db_name = match &current_db {
+                    Some(name) => name.clone(),
+                    None => {
+                        println!("No database selected. Please select a database first.");
+                        continue;
+                    }
+                };
+
+                println!("Enter table name to analyze: ");
+                let mut table_name = String::new();
+                io::stdin().read_line(&mut table_name)?;
+
+                if let Err(e) = analyze_table(&mut catalog, &db_name, table_name.trim()) {
+                    println!("Cannot analyze table '{}': {}", table_name.trim(), e);
+                }
+            }
+
+            // -----------------------
+            // Option 13: Show Table Statistics
+            // -----------------------
+            "13" => {
+                let db_name = match &current_db {
+                    Some(name) => name.clone(),
+                    None => {
+                        println!("No database selected. Please select a database first.");
+                        continue;
+                    }
+                };
+
+                println!("Enter table name: ");
+                let mut table_name = String::new();
+                io::stdin().read_line(&mut table_name)?;
+
+                print_table_stats(&catalog, &db_name, table_name.trim());
+            }
+
+            // -----------------------
+            // Option 14: Export Table
+            // -----------------------
+            "14" => {
+                let db_name = match &current_db {
+                    Some(name) => name.clone(),
+                    None => {
+                        println!("No database selected. Please select a database first.");
+                        continue;
+                    }
+                };
+
+                println!("Enter table name: ");
+                let mut table_name = String::new();
+                io::stdin().read_line(&mut table_name)?;
+                let table_name = table_name.trim();
+
+                println!("Enter export format (csv or json): ");
+                let mut format = String::new();
+                io::stdin().read_line(&mut format)?;
+                let json = match format.trim().to_ascii_lowercase().as_str() {
+                    "" | "csv" => false,
+                    "json" => true,
+                    other => {
+                        println!("Unknown export format '{}'.", other);
+                        continue;
+                    }
+                };
+
+                println!("Enter output file path: ");
+                let mut out_path = String::new();
+                io::stdin().read_line(&mut out_path)?;
+                let out_path = out_path.trim();
+
+                let exported = catalog
+                    .table_file(&db_name, table_name)
+                    .and_then(|path| Ok(OpenOptions::new().read(true).open(path)?))
+                    .and_then(|mut file| {
+                        if json {
+                            export_json(&catalog, &db_name, table_name, &mut file, out_path)
+                        } else {
+                            export_csv(&catalog, &db_name, table_name, &mut file, out_path, &CsvOptions::default())
+                        }
+                    });
+                match exported {
+                    Ok(rows) => println!("Exported {} rows of '{}' to {}.", rows, table_name, out_path),
+                    Err(e) => println!("Cannot export table '{}': {}", table_name, e),
+                }
+            }
+
             // -----------------------
             // Invalid Option
             // -----------------------
@@ -275,3 +722,109 @@ fn main() -> io::Result<()> {
     }
     Ok(())
 }
+
+fn print_databases(catalog: &Catalog) {
+    println!("--------------------------");
+    println!("Databases in Catalog");
+    println!("--------------------------");
+
+    let databases = list_databases(catalog);
+    if databases.is_empty() {
+        println!("No databases found.\n");
+        return;
+    }
+
+    for db_name in databases {
+        println!("- {}", db_name);
+    }
+
+    println!();
+}
+
+fn print_tables(catalog: &Catalog, db_name: &str) {
+    println!("--------------------------");
+    println!("Tables in Database: {}", db_name);
+    println!("--------------------------");
+
+    let tables = match list_tables(catalog, db_name) {
+        Ok(tables) => tables,
+        Err(e) => {
+            println!("{}.\n", e);
+            return;
+        }
+    };
+    if tables.is_empty() {
+        println!("No tables found in '{}'.\n", db_name);
+        return;
+    }
+
+    for table_name in tables {
+        println!("- {}", table_name);
+    }
+
+    println!();
+}
+
+fn print_tuples(catalog: &Catalog, db_name: &str, table_name: &str, file: &mut File) -> error::Result<()> {
+    let pages = dump_tuples(catalog, db_name, table_name, file)?;
+    let columns = &catalog.databases[db_name].tables[table_name].columns;
+
+    println!("\n=== Tuples in '{}.{}' ===", db_name, table_name);
+    println!("Total pages: {}", pages.len() + 1);
+    for page in pages {
+        println!("\n-- Page {} --", page.page_num);
+        println!("Lower: {}, Upper: {}, Tuples: {}", page.lower, page.upper, page.tuples.len());
+
+        for (item_index, values) in page.tuples {
+            print!("Tuple {}: ", item_index + 1);
+            for (col, val) in columns.iter().zip(values.iter()) {
+                print!("{}={} ", col.name, val);
+            }
+            println!();
+        }
+    }
+
+    println!("\n=== End of tuples ===\n");
+    Ok(())
+}
+
+fn print_table_stats(catalog: &Catalog, db_name: &str, table_name: &str) {
+    let Some(table) = catalog.databases.get(db_name).and_then(|db| db.tables.get(table_name)) else {
+        println!("Table '{}' does not exist in database '{}'.", table_name, db_name);
+        return;
+    };
+    let Some(stats) = &table.stats else {
+        println!("Table '{}' has not been analyzed yet.", table_name);
+        return;
+    };
+
+    println!("--------------------------");
+    println!("Statistics of '{}.{}'", db_name, table_name);
+    println!("--------------------------");
+    println!("Rows: {}, Pages: {}, Average tuple width: {} bytes", stats.row_count, stats.page_count, stats.avg_width);
+
+    for column in &table.columns {
+        let Some(column_stats) = stats.column(column.id) else {
+            println!("\n{}: not analyzed (added after the last ANALYZE)", column.name);
+            continue;
+        };
+        println!("\n{} ({})", column.name, column.data_type);
+        println!("  Null fraction: {:.3}", column_stats.null_frac);
+        println!("  Distinct values: ~{}", column_stats.n_distinct);
+        if let (Some(min), Some(max)) = (&column_stats.min, &column_stats.max) {
+            println!("  Min: {}, Max: {}", min, max);
+        }
+        if !column_stats.most_common.is_empty() {
+            let mcvs: Vec<String> = column_stats
+                .most_common
+                .iter()
+                .map(|(value, freq)| format!("{} ({:.3})", value, freq))
+                .collect();
+            println!("  Most common: {}", mcvs.join(", "));
+        }
+        if !column_stats.histogram.is_empty() {
+            println!("  Histogram bounds: {}", column_stats.histogram.join(" | "));
+        }
+    }
+    println!();
+}
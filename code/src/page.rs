@@ -1,11 +1,21 @@
 use std::fs::{File};
-use std::io::{self, Read, Seek, SeekFrom, BufRead, BufReader};
-use crate::catalog::Catalog;
+use std::io::{Read, Seek, SeekFrom};
+use tracing::{info, instrument};
+use crate::catalog::{Catalog, Table};
+use crate::error::{Result, RookError};
+use crate::toast::{ToastFile, delete_toasted_values, detoast_tuple, toast_tuple};
+use crate::constraint::{ConstraintChecker, PlannedChange, RowChange, check_key_update, plan_delete};
+use crate::csv_import::{CsvOptions, CsvReader, CsvRow, LoadReport};
+use crate::tuple::{decode_tuple, encode_tuple, parse_row};
+use crate::value::Value;
 
 pub const PAGE_SIZE: usize = 8192; // Page size - storing as 8 bytes and as usize only because most pointers(file pointers and otheres requires it to be 8 bytes)
 pub const PAGE_HEADER_SIZE: u32 = 8;
 pub const ITEM_ID_SIZE: u32  = 8;
 
+/// Location of a tuple: (page number, item index within the page).
+pub type TupleId = (u32, u32);
+
 use crate::disk::{create_page, read_page, write_page};
 
 // pub struct PageHeader {
@@ -15,7 +25,7 @@ use crate::disk::{create_page, read_page, write_page};
 
 // pub struct ItemId {
 //     pub offset: u32, // Offset of the item or Tuple - 4 bytes
-//     pub length: u32, // Length of the item or Tuple - 4 bytes
+//     pub length: u32, // Length of the item or Tuple - 4 bytes (0 = deleted)
 // }
 
 pub struct Page {
@@ -30,6 +40,12 @@ impl Page {
     }
 }
 
+impl Default for Page {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub fn init_page(page: &mut Page) {
     // Lower offset in first 4 bytes
     let lower = PAGE_HEADER_SIZE.to_le_bytes();
@@ -40,7 +56,7 @@ pub fn init_page(page: &mut Page) {
     page.data[4..8].copy_from_slice(&upper);
 }
 
-pub fn page_count(file: &mut File) -> io::Result<u32> {
+pub fn page_count(file: &mut File) -> Result<u32> {
     // Move cursor to the start of the file header
     file.seek(SeekFrom::Start(0))?;
 
@@ -54,23 +70,74 @@ pub fn page_count(file: &mut File) -> io::Result<u32> {
     Ok(total_pages)
 }
 
+/// Drops whatever follows the pages counted in the file header: pages of a bulk load that
+/// never committed. Returns true if anything was removed.
+pub fn truncate_uncommitted_pages(file: &mut File) -> Result<bool> {
+    let committed_len = page_count(file)? as u64 * PAGE_SIZE as u64;
+    if file.metadata()?.len() <= committed_len {
+        return Ok(false);
+    }
+    file.set_len(committed_len)?;
+    file.sync_data()?;
+    Ok(true)
+}
+
+/// Makes the pages written after the committed ones durable, then counts them in the header:
+/// the single write that commits a bulk load.
+pub fn publish_page_count(file: &mut File, total_pages: u32) -> Result<()> {
+    file.sync_data()?;
+    let mut header = Page::new();
+    read_page(file, &mut header, 0)?;
+    header.data[0..4].copy_from_slice(&total_pages.to_le_bytes());
+    write_page(file, &mut header, 0)?;
+    file.sync_data()?;
+    Ok(())
+}
+
 
 
-pub fn page_free_space(page: &Page) -> io::Result<u32> {
+pub fn page_free_space(page: &Page) -> Result<u32> {
     // Get Lower and Upper of page
     let lower = u32::from_le_bytes(page.data[0..4].try_into().unwrap());
     let upper = u32::from_le_bytes(page.data[4..8].try_into().unwrap());
-    // println!("UPPER: {}", upper);
-    // println!("LOWER: {}", lower);
     // Freespace is equal to upper - lower
     Ok(upper - lower)
 }
 
-pub fn insert_tuple(file: &mut File, data: &[u8]) -> io::Result<()> {
+/// Writes a tuple and its ItemId into a page with at least `data.len() + ITEM_ID_SIZE` bytes free.
+/// Returns the item index of the tuple.
+pub fn place_tuple(page: &mut Page, data: &[u8]) -> u32 {
+    // Get current header offsets
+    let mut lower = u32::from_le_bytes(page.data[0..4].try_into().unwrap());
+    let mut upper = u32::from_le_bytes(page.data[4..8].try_into().unwrap());
+
+    // Compute where to place data
+    let start = upper - data.len() as u32;
+
+    // Copy tuple data into the page
+    page.data[start as usize..upper as usize].copy_from_slice(data);
+
+    // Update upper pointer
+    upper = start;
+    page.data[4..8].copy_from_slice(&upper.to_le_bytes());
+
+    // Write ItemId entry (offset + length)
+    let item_id_pos = lower as usize;
+    page.data[item_id_pos..item_id_pos + 4].copy_from_slice(&start.to_le_bytes());
+    page.data[item_id_pos + 4..item_id_pos + 8].copy_from_slice(&(data.len() as u32).to_le_bytes());
+
+    // Update lower pointer
+    lower += ITEM_ID_SIZE;
+    page.data[0..4].copy_from_slice(&lower.to_le_bytes());
+
+    (item_id_pos as u32 - PAGE_HEADER_SIZE) / ITEM_ID_SIZE
+}
+
+/// Appends a tuple to the last page of a table file (or a new page) and returns its location.
+pub fn insert_tuple(file: &mut File, data: &[u8]) -> Result<TupleId> {
     // Get total number of pages in the file
     let mut total_pages: u32 = page_count(file)?;
     let mut last_page_num: u32 = total_pages - 1;
-    // println!("Inserting into page {}", last_page_num);
 
     // Read last page into memory
     let mut last_page: Page = Page::new();
@@ -78,252 +145,427 @@ pub fn insert_tuple(file: &mut File, data: &[u8]) -> io::Result<()> {
 
     // Calculate free space in the page
     let free_space: u32 = page_free_space(&last_page)?;
-    // println!("Free space in last page: {} bytes", free_space);
 
     // Total bytes required = tuple data + item header (offset + length)
     let total_required = data.len() as u32 + ITEM_ID_SIZE;
+    if total_required > PAGE_SIZE as u32 - PAGE_HEADER_SIZE {
+        return Err(RookError::TupleTooLarge(format!(
+            "Tuple of {} bytes does not fit in a page",
+            data.len()
+        )));
+    }
 
     if total_required > free_space {
         // Not enough space — create a new page
-        // println!("Not enough free space in last page. Creating a new page...");
         create_page(file)?;
         total_pages += 1;
         last_page_num = total_pages - 1;
 
         // Read the newly created page (it should be empty)
         read_page(file, &mut last_page, last_page_num)?;
-        // println!("Inserting into newly created page {}", last_page_num);
     }
 
     // === Insert into last_page (either old or new) ===
+    let item_index = place_tuple(&mut last_page, data);
 
-    // Get current header offsets
-    let mut lower = u32::from_le_bytes(last_page.data[0..4].try_into().unwrap());
-    let mut upper = u32::from_le_bytes(last_page.data[4..8].try_into().unwrap());
+    // Write page back to disk
+    write_page(file, &mut last_page, last_page_num)?;
 
-    // Compute where to place data
-    let start = upper - data.len() as u32;
+    Ok((last_page_num, item_index))
+}
 
-    // Copy tuple data into the page
-    last_page.data[start as usize..upper as usize].copy_from_slice(data);
+/// Marks the tuple at `item_index` of a page as deleted (its ItemId length becomes 0)
+/// and returns the bytes it had. The space is not reclaimed.
+pub fn delete_tuple(file: &mut File, page_num: u32, item_index: u32) -> Result<Vec<u8>> {
+    let mut page = Page::new();
+    read_page(file, &mut page, page_num)?;
 
-    // Update upper pointer
-    upper = start;
-    last_page.data[4..8].copy_from_slice(&upper.to_le_bytes());
+    let lower = u32::from_le_bytes(page.data[0..4].try_into().unwrap());
+    if item_index >= (lower - PAGE_HEADER_SIZE) / ITEM_ID_SIZE {
+        return Err(RookError::not_found("Tuple", format!("{}:{}", page_num, item_index)));
+    }
 
-    // Write ItemId entry (offset + length)
-    let item_id_pos = lower as usize;
-    last_page.data[item_id_pos..item_id_pos + 4].copy_from_slice(&start.to_le_bytes());
-    last_page.data[item_id_pos + 4..item_id_pos + 8]
-        .copy_from_slice(&(data.len() as u32).to_le_bytes());
+    let base = (PAGE_HEADER_SIZE + item_index * ITEM_ID_SIZE) as usize;
+    let offset = u32::from_le_bytes(page.data[base..base + 4].try_into().unwrap()) as usize;
+    let length = u32::from_le_bytes(page.data[base + 4..base + 8].try_into().unwrap()) as usize;
+    if length == 0 {
+        return Err(RookError::not_found("Tuple", format!("{}:{}", page_num, item_index)));
+    }
 
-    // Update lower pointer
-    lower += ITEM_ID_SIZE;
-    last_page.data[0..4].copy_from_slice(&lower.to_le_bytes());
+    let data = page.data[offset..offset + length].to_vec();
+    page.data[base + 4..base + 8].copy_from_slice(&0u32.to_le_bytes());
+    write_page(file, &mut page, page_num)?;
+    Ok(data)
+}
 
-    // Write page back to disk
-    write_page(file, &mut last_page, last_page_num)?;
-    // println!("Tuple inserted successfully ({} bytes).", data.len());
+/// Reads the row at a tuple location, detoasted and decoded with the table schema.
+pub fn read_row(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+    (page_num, item_index): TupleId,
+) -> Result<Vec<Value>> {
+    let table = find_table(catalog, db_name, table_name)?;
+    let mut page = Page::new();
+    read_page(file, &mut page, page_num)?;
+
+    let (_, tuple_data) = page_tuples(&page)
+        .into_iter()
+        .find(|(i, _)| *i == item_index)
+        .ok_or_else(|| RookError::not_found("Tuple", format!("{}:{}", page_num, item_index)))?;
+    let path = catalog.table_file(db_name, table_name)?;
+    let mut toast = ToastFile::new(catalog.toast_file(db_name, table_name)?);
+    detoast_tuple(table, tuple_data, &mut toast)
+        .and_then(|tuple_data| decode_tuple(table, &tuple_data))
+        .map_err(|e| RookError::corrupt_page(e, path, page_num))
+}
 
+/// Deletes a row of a table, including any of its values stored in the TOAST file.
+/// Rows of other tables that reference it are handled by their foreign key's ON DELETE action
+/// (RESTRICT fails before anything is deleted, CASCADE deletes them, SET NULL clears their key).
+pub fn delete_row(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+    page_num: u32,
+    item_index: u32,
+) -> Result<()> {
+    let values = read_row(catalog, db_name, table_name, file, (page_num, item_index))?;
+    let plan = plan_delete(catalog, db_name, table_name, (page_num, item_index), &values)?;
+
+    for change in &plan {
+        catalog.checkers.invalidate(catalog, db_name, &change.table);
+        if change.table == table_name {
+            apply_change(catalog, db_name, file, change)?;
+        } else {
+            let path = catalog.table_file(db_name, &change.table)?;
+            let mut child_file = File::options().read(true).write(true).open(path)?;
+            apply_change(catalog, db_name, &mut child_file, change)?;
+        }
+    }
     Ok(())
 }
 
+/// Applies one planned change to a row of `change.table` stored in `file`.
+fn apply_change(catalog: &Catalog, db_name: &str, file: &mut File, change: &PlannedChange) -> Result<()> {
+    let table = find_table(catalog, db_name, &change.table)?;
+    let mut toast = ToastFile::new(catalog.toast_file(db_name, &change.table)?);
+    let (page_num, item_index) = change.tuple;
 
+    match &change.change {
+        RowChange::Delete => {
+            let data = delete_tuple(file, page_num, item_index)?;
+            delete_toasted_values(table, &data, &mut toast)
+        }
+        RowChange::SetNull(columns) => {
+            let mut values = read_row(catalog, db_name, &change.table, file, change.tuple)?;
+            for &i in columns {
+                values[i] = Value::Null;
+            }
+            let tuple_bytes = toast_tuple(table, encode_tuple(table, &values), &mut toast)?;
+            replace_tuple(table, file, &mut toast, change.tuple, &tuple_bytes)
+        }
+    }
+}
 
-pub fn load_csv_and_insert(
+/// Stores a new version of the tuple at `tuple`. The new version is written first and the old one
+/// deleted after, so a failure leaves the old row in place; the old version's TOAST values are
+/// freed last, once no tuple points at them.
+fn replace_tuple(table: &Table, file: &mut File, toast: &mut ToastFile, tuple: TupleId, tuple_bytes: &[u8]) -> Result<()> {
+    let new_tuple = store_tuple(table, file, toast, tuple_bytes)?;
+    let data = match delete_tuple(file, tuple.0, tuple.1) {
+        Ok(data) => data,
+        Err(e) => {
+            // Undo the new version, so the row is not stored twice
+            if delete_tuple(file, new_tuple.0, new_tuple.1).is_ok() {
+                let _ = delete_toasted_values(table, tuple_bytes, toast);
+            }
+            return Err(e);
+        }
+    };
+    delete_toasted_values(table, &data, toast)
+}
+
+/// Inserts a toasted tuple; if that fails, its TOAST chunks are freed, since nothing points at them.
+fn store_tuple(table: &Table, file: &mut File, toast: &mut ToastFile, tuple_bytes: &[u8]) -> Result<TupleId> {
+    insert_tuple(file, tuple_bytes).inspect_err(|_| {
+        let _ = delete_toasted_values(table, tuple_bytes, toast);
+    })
+}
+
+/// Replaces the row at a tuple location with new text fields, enforcing the table's constraints.
+/// The key of a row that other rows reference cannot change. The new version is appended
+/// like an insert, so the row gets a new location.
+pub fn update_row(
     catalog: &Catalog,
     db_name: &str,
     table_name: &str,
     file: &mut File,
-    csv_path: &str,
-) -> io::Result<()> {
-    // --- 1. Fetch table schema from catalog ---
-    let db = catalog
+    tuple: TupleId,
+    fields: &[&str],
+) -> Result<()> {
+    let old_values = read_row(catalog, db_name, table_name, file, tuple)?;
+
+    catalog.checkers.with_checker(catalog, db_name, table_name, file, |checker, file| {
+        // Check the new row as if the old one were already gone
+        checker.unindex_row(&old_values);
+        let new_values = match replace_row(catalog, db_name, table_name, file, tuple, &old_values, fields, checker) {
+            Ok(new_values) => new_values,
+            Err(e) => {
+                checker.index_row(&old_values);
+                return Err(e);
+            }
+        };
+        checker.index_row(&new_values);
+        Ok(())
+    })?;
+    // Rows referencing the old key were checked above; the new key is not in their indexes yet
+    catalog.checkers.invalidate_references(catalog, db_name, table_name);
+    Ok(())
+}
+
+/// Checks the new version of a row and stores it in place of the old one. Returns its values.
+#[allow(clippy::too_many_arguments)]
+fn replace_row(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+    tuple: TupleId,
+    old_values: &[Value],
+    fields: &[&str],
+    checker: &ConstraintChecker,
+) -> Result<Vec<Value>> {
+    let table = find_table(catalog, db_name, table_name)?;
+    let new_values = parse_row(table, fields)
+        .and_then(|values| checker.check_row(&values).map(|_| values))?;
+    check_key_update(catalog, db_name, table_name, tuple, old_values, &new_values)?;
+
+    let mut toast = ToastFile::new(catalog.toast_file(db_name, table_name)?);
+    let tuple_bytes = toast_tuple(table, encode_tuple(table, &new_values), &mut toast)?;
+    replace_tuple(table, file, &mut toast, tuple, &tuple_bytes)?;
+    Ok(new_values)
+}
+
+/// Inserts one row given as text fields, enforcing the table's constraints.
+/// `insert_tuple` stores raw tuple bytes and knows nothing about the schema; this is the checked path.
+pub fn insert_row(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+    fields: &[&str],
+) -> Result<()> {
+    let table = find_table(catalog, db_name, table_name)?;
+    catalog.checkers.with_checker(catalog, db_name, table_name, file, |checker, file| {
+        let values = parse_row(table, fields)
+            .and_then(|values| checker.check_row(&values).map(|_| values))?;
+
+        let mut toast = ToastFile::new(catalog.toast_file(db_name, table_name)?);
+        let tuple_bytes = toast_tuple(table, encode_tuple(table, &values), &mut toast)?;
+        store_tuple(table, file, &mut toast, &tuple_bytes)?;
+        checker.index_row(&values);
+        Ok(())
+    })?;
+    // Tables referencing this one do not have the new key yet
+    catalog.checkers.invalidate_references(catalog, db_name, table_name);
+    Ok(())
+}
+
+/// Live tuples of a data page with their item index, in item order (deleted items are skipped).
+pub fn page_tuples(page: &Page) -> Vec<(u32, &[u8])> {
+    let lower = u32::from_le_bytes(page.data[0..4].try_into().unwrap());
+    let num_items = (lower - PAGE_HEADER_SIZE) / ITEM_ID_SIZE;
+
+    let mut tuples = Vec::with_capacity(num_items as usize);
+    for i in 0..num_items {
+        let base = (PAGE_HEADER_SIZE + i * ITEM_ID_SIZE) as usize;
+        let offset = u32::from_le_bytes(page.data[base..base + 4].try_into().unwrap()) as usize;
+        let length = u32::from_le_bytes(page.data[base + 4..base + 8].try_into().unwrap()) as usize;
+        if length == 0 {
+            continue; // Deleted tuple
+        }
+        tuples.push((i, &page.data[offset..offset + length]));
+    }
+    tuples
+}
+
+/// Looks up a table in the catalog.
+fn find_table<'a>(catalog: &'a Catalog, db_name: &str, table_name: &str) -> Result<&'a Table> {
+    catalog
         .databases
         .get(db_name)
-        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Database '{}' not found", db_name)))?;
-
-    let table = db
+        .ok_or_else(|| RookError::not_found("Database", db_name))?
         .tables
         .get(table_name)
-        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Table '{}' not found", table_name)))?;
+        .ok_or_else(|| RookError::not_found("Table", table_name))
+}
+
+
+
+#[instrument(name = "csv_load", skip(catalog, file, options))]
+pub fn load_csv_and_insert(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+    csv_path: &str,
+    options: &CsvOptions,
+) -> Result<LoadReport> {
+    // --- 1. Fetch table schema from catalog ---
+    let table = find_table(catalog, db_name, table_name)?;
 
     let columns = &table.columns;
     if columns.is_empty() {
-        return Err(io::Error::new(io::ErrorKind::InvalidData, "Table has no columns"));
+        return Err(RookError::SchemaMismatch(format!("Table '{}' has no columns", table_name)));
     }
 
-    // println!(
-    //     "Loading CSV '{}' into table '{}.{}' ({} columns)",
-    //     csv_path,
-    //     db_name,
-    //     table_name,
-    //     columns.len()
-    // );
 
-    // --- 2. Open and read the CSV file ---
-    let csv_file = File::open(csv_path)?;
-    let reader = BufReader::new(csv_file);
+    // --- 2. Open the CSV file and match its header to the columns ---
+    let mut rows = CsvReader::open(csv_path, table, options)?;
 
-    let mut lines = reader.lines();
+    // --- 3. Iterate through rows (key constraints see the rows already stored) ---
+    catalog.checkers.invalidate(catalog, db_name, table_name);
+    let mut checker = ConstraintChecker::for_table(catalog, db_name, table_name, file)?;
+    let mut toast = ToastFile::new(catalog.toast_file(db_name, table_name)?);
+    while let Some(row) = rows.next() {
+        let CsvRow { row, values } = row?;
 
-    // Skip header line
-    if let Some(Ok(header)) = lines.next() {
-        // println!("Header: {}", header);
+        // --- 4. Parse row based on schema and check constraints ---
+        let values = match values.and_then(|values| checker.check_row(&values).map(|_| values)) {
+            Ok(values) => values,
+            Err(e) => {
+                rows.reject(row, &e)?;
+                continue;
+            }
+        };
+
+        // --- 5. Serialize, move large values out of line, then insert tuple into page system ---
+        let inserted_tuple = toast_tuple(table, encode_tuple(table, &values), &mut toast)
+            .and_then(|tuple_bytes| store_tuple(table, file, &mut toast, &tuple_bytes));
+        match inserted_tuple {
+            // Only a stored row's keys count for the rows after it
+            Ok(_) => {
+                checker.index_row(&values);
+                rows.accept();
+            }
+            Err(e) => rows.reject(row, &e)?,
+        }
     }
 
-    // --- 3. Iterate through rows ---
-    let mut inserted = 0;
-    for (i, line) in lines.enumerate() {
-        let row = line?;
-        if row.trim().is_empty() {
-            continue;
-        }
+    let report = rows.finish()?;
+    info!("Loaded '{}.{}': {}.", db_name, table_name, report);
 
-        // Split CSV fields by comma
-        let values: Vec<&str> = row.split(',').map(|v| v.trim()).collect();
-
-        // Validate number of columns
-        if values.len() != columns.len() {
-            println!(
-                "Skipping row {}: expected {} columns, found {}",
-                i + 1,
-                columns.len(),
-                values.len()
-            );
-            continue;
-        }
+    Ok(report)
+}
 
-        // --- 4. Serialize row based on schema ---
-        let mut tuple_bytes: Vec<u8> = Vec::new();
-
-        for (val, col) in values.iter().zip(columns.iter()) {
-            match col.data_type.as_str() {
-                "INT" => {
-                    let num: i32 = val.parse().unwrap_or_default();
-                    tuple_bytes.extend_from_slice(&num.to_le_bytes());
-                }
-                "TEXT" => {
-                    let mut text_bytes = val.as_bytes().to_vec();
-                    if text_bytes.len() > 10 {
-                        text_bytes.truncate(10);
-                    } else if text_bytes.len() < 10 {
-                        text_bytes.extend(vec![b' '; 10 - text_bytes.len()]);
-                    }
-                    tuple_bytes.extend_from_slice(&text_bytes);
-                }
-                _ => {
-                    println!(
-                        "Unsupported column type '{}' in column '{}'",
-                        col.data_type, col.name
-                    );
-                    continue;
-                }
-            }
-        }
 
-        // --- 5. Insert tuple into page system ---
-        if let Err(e) = insert_tuple(file, &tuple_bytes) {
-            println!("Failed to insert row {}: {}", i + 1, e);
-        } else {
-            inserted += 1;
-        }
-    }
 
-    
-     // --- 6️⃣. Inspect first page (for verification) ---
-    // let mut page = Page::new();
+/// One data page of a table file as shown by `dump_tuples`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageDump {
+    pub page_num: u32,
+    pub lower: u32,
+    pub upper: u32,
+    pub tuples: Vec<(u32, Vec<Value>)>, // Live tuples with their item index, decoded with the table schema
+}
 
-    // Move to the first data page (page number 1)
-    // read_page(file, &mut page, 1)?;
+/// Reads every data page of a table file with its header offsets and decoded tuples.
+pub fn dump_tuples(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+) -> Result<Vec<PageDump>> {
+    // 1. Get schema from catalog
+    let table = find_table(catalog, db_name, table_name)?;
+    let path = catalog.table_file(db_name, table_name)?;
+    let mut toast = ToastFile::new(catalog.toast_file(db_name, table_name)?);
 
-    // println!("\n--- First Data Page [1] Snapshot ---");
-    // println!("First 32 bytes: {:?}", &page.data[0..32]);
-    // println!("------------------------------------\n");
-    Ok(())
-}
+    // 2. Loop through each page
+    let total_pages = page_count(file)?;
+    let mut pages = Vec::new();
+    for page_num in 1..total_pages {
+        let mut page = Page::new();
+        read_page(file, &mut page, page_num)?;
+
+        let lower = u32::from_le_bytes(page.data[0..4].try_into().unwrap());
+        let upper = u32::from_le_bytes(page.data[4..8].try_into().unwrap());
+        if lower < PAGE_HEADER_SIZE || lower > upper || upper > PAGE_SIZE as u32 {
+            return Err(RookError::Corruption {
+                file: path,
+                page: Some(page_num),
+                message: format!("invalid page header: lower {}, upper {}", lower, upper),
+            });
+        }
 
+        // 3. Decode each tuple (using the schema version stored in the tuple)
+        let mut tuples = Vec::new();
+        for (item_index, tuple_data) in page_tuples(&page) {
+            let values = detoast_tuple(table, tuple_data, &mut toast)
+                .and_then(|tuple_data| decode_tuple(table, &tuple_data))
+                .map_err(|e| RookError::corrupt_page(e, &path, page_num))?;
+            tuples.push((item_index, values));
+        }
+        pages.push(PageDump { page_num, lower, upper, tuples });
+    }
 
+    Ok(pages)
+}
 
-pub fn show_tuples(
+/// Reads every live tuple of a table file, detoasts it and decodes it with the table schema.
+pub fn scan_tuples(
     catalog: &Catalog,
     db_name: &str,
     table_name: &str,
     file: &mut File,
-) -> io::Result<()> {
-    // 1. Get schema from catalog
-    let db = catalog
-        .databases
-        .get(db_name)
-        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Database '{}' not found", db_name)))?;
+) -> Result<Vec<Vec<Value>>> {
+    let rows = scan_rows(catalog, db_name, table_name, file)?;
+    Ok(rows.into_iter().map(|(_, values)| values).collect())
+}
 
-    let table = db
-        .tables
-        .get(table_name)
-        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Table '{}' not found", table_name)))?;
+/// Like `scan_tuples`, but also returns where each row is stored.
+pub fn scan_rows(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+) -> Result<Vec<(TupleId, Vec<Value>)>> {
+    let mut rows = Vec::new();
+    for_each_row(catalog, db_name, table_name, file, |tuple_id, values| {
+        rows.push((tuple_id, values));
+        Ok(())
+    })?;
+    Ok(rows)
+}
 
-    let columns = &table.columns;
+/// Reads the live rows of a table file one at a time, without keeping them in memory.
+/// Stops at the first error of `visit`.
+pub fn for_each_row(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+    mut visit: impl FnMut(TupleId, Vec<Value>) -> Result<()>,
+) -> Result<()> {
+    let table = find_table(catalog, db_name, table_name)?;
+    let path = catalog.table_file(db_name, table_name)?;
+    let mut toast = ToastFile::new(catalog.toast_file(db_name, table_name)?);
+    let total_pages = page_count(file)?;
 
-    // 2. Read total number of pages
-    file.seek(SeekFrom::Start(0))?;
-    let mut buf = [0u8; 4];
-    file.read_exact(&mut buf)?;
-    let mut total_pages = u32::from_le_bytes(buf);
-
-    println!("\n=== Tuples in '{}.{}' ===", db_name, table_name);
-    println!("Total pages: {}", total_pages);
-    total_pages = total_pages;
-    // 3. Loop through each page
     for page_num in 1..total_pages {
         let mut page = Page::new();
         read_page(file, &mut page, page_num)?;
-        println!("\n-- Page {} --", page_num);
 
-        let lower = u32::from_le_bytes(page.data[0..4].try_into().unwrap());
-        let upper = u32::from_le_bytes(page.data[4..8].try_into().unwrap());
-        println!("Lower: {}, Upper: {}", lower, upper);
-        let num_items = (lower - PAGE_HEADER_SIZE) / ITEM_ID_SIZE;
-
-        println!("Lower: {}, Upper: {}, Tuples: {}", lower, upper, num_items);
-
-        // 4. For each tuple
-        for i in 0..num_items {
-            let base = (PAGE_HEADER_SIZE + i * ITEM_ID_SIZE) as usize;
-            let offset = u32::from_le_bytes(page.data[base..base + 4].try_into().unwrap());
-            let length = u32::from_le_bytes(page.data[base + 4..base + 8].try_into().unwrap());
-            let tuple_data = &page.data[offset as usize..(offset + length) as usize];
-
-            print!("Tuple {}: ", i + 1);
-
-            // 5. Decode each column
-            let mut cursor = 0usize;
-            for col in columns {
-                match col.data_type.as_str() {
-                    "INT" => {
-                        if cursor + 4 <= tuple_data.len() {
-                            let val = i32::from_le_bytes(tuple_data[cursor..cursor + 4].try_into().unwrap());
-                            print!("{}={} ", col.name, val);
-                            cursor += 4;
-                        }
-                    }
-                    "TEXT" => {
-                        if cursor + 10 <= tuple_data.len() {
-                            let text_bytes = &tuple_data[cursor..cursor + 10];
-                            let text = String::from_utf8_lossy(text_bytes).trim().to_string();
-                            print!("{}='{}' ", col.name, text);
-                            cursor += 10;
-                        }
-                    }
-                    _ => {
-                        print!("{}=<unsupported> ", col.name);
-                    }
-                }
-            }
-            println!();
+        for (item_index, tuple_data) in page_tuples(&page) {
+            let values = detoast_tuple(table, tuple_data, &mut toast)
+                .and_then(|tuple_data| decode_tuple(table, &tuple_data))
+                .map_err(|e| RookError::corrupt_page(e, &path, page_num))?;
+            visit((page_num, item_index), values)?;
         }
-        // break;
     }
 
-    println!("\n=== End of tuples ===\n");
     Ok(())
-}
\ No newline at end of file
+}
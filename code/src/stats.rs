@@ -0,0 +1,274 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::{DefaultHasher, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::{Catalog, Column};
+use crate::disk::read_page;
+use crate::error::{self, RookError};
+use crate::export::field_text;
+use crate::page::{Page, for_each_row, page_count, page_tuples};
+use crate::value::{Value, encode_value};
+
+//-------------------------------------------
+// Table statistics (collected by ANALYZE, stored in rook_table and rook_statistic):
+// Per table:  row count, page count, average stored tuple width (after TOAST)
+// Per column: null fraction, distinct values (HyperLogLog estimate), min/max,
+//             most common values with their frequency, equi-depth histogram bounds
+// Values are stored as text in the same form as column defaults. Min/max and histogram
+// bounds are cut to STATS_MAX_VALUE_WIDTH bytes and wider values are left out of the MCVs,
+// so a statistics row always fits a system table page.
+//-------------------------------------------
+pub const STATS_TARGET: usize = 10; // Most common values and histogram buckets per column
+pub const STATS_MAX_VALUE_WIDTH: usize = 64; // Longest value text kept in statistics
+pub const STATS_SAMPLE_SIZE: usize = 300 * STATS_TARGET; // Values sampled per column for MCVs and the histogram
+pub const HLL_PRECISION: u32 = 12; // 2^12 HyperLogLog registers, ~1.6% standard error
+
+/// Statistics of one table as of its last ANALYZE.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableStats {
+    pub row_count: u64,
+    pub page_count: u32, // Including the header page
+    pub avg_width: u32, // Average bytes per stored tuple
+    pub columns: Vec<ColumnStats>, // One per column of the current schema at ANALYZE time
+}
+
+impl TableStats {
+    /// Statistics of a column by id, None if it did not exist at the last ANALYZE.
+    pub fn column(&self, column_id: u32) -> Option<&ColumnStats> {
+        self.columns.iter().find(|c| c.column_id == column_id)
+    }
+}
+
+/// Statistics of one column. Keyed by column id so they survive column renames.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub column_id: u32,
+    pub null_frac: f64, // Fraction of rows that are NULL
+    pub n_distinct: u64, // Estimated number of distinct non-NULL values
+    pub min: Option<String>, // None if there are no comparable values
+    pub max: Option<String>,
+    pub most_common: Vec<(String, f64)>, // Values seen more than once and their frequency, most common first
+    pub histogram: Vec<String>, // Bucket bounds: each bucket holds about the same number of values
+}
+
+/// Estimates the number of distinct items with a fixed amount of memory.
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self { registers: vec![0; 1 << HLL_PRECISION] }
+    }
+
+    pub fn insert(&mut self, item: &[u8]) {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(item);
+        let hash = hasher.finish();
+
+        // Step 1: The top bits pick a register
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+
+        // Step 2: The register keeps the longest run of leading zeros seen in the remaining bits
+        let rank = ((hash << HLL_PRECISION).leading_zeros() + 1).min(64 - HLL_PRECISION + 1) as u8;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+
+        // Small cardinalities: count the empty registers instead (linear counting)
+        let empty = self.registers.iter().filter(|&&r| r == 0).count();
+        let estimate = if raw <= 2.5 * m && empty > 0 {
+            m * (m / empty as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
+
+/// Scans a table file and computes its statistics.
+pub fn collect_stats(
+    catalog: &Catalog,
+    db_name: &str,
+    table_name: &str,
+    file: &mut File,
+) -> error::Result<TableStats> {
+    let table = catalog
+        .databases
+        .get(db_name)
+        .and_then(|db| db.tables.get(table_name))
+        .ok_or_else(|| RookError::not_found("Table", table_name))?;
+
+    // Step 1: Page count and stored tuple widths
+    let total_pages = page_count(file)?;
+    let mut total_width = 0u64;
+    for page_num in 1..total_pages {
+        let mut page = Page::new();
+        read_page(file, &mut page, page_num)?;
+        total_width += page_tuples(&page).iter().map(|(_, data)| data.len() as u64).sum::<u64>();
+    }
+
+    // Step 2: Feed each row, detoasted and in the current schema, to one sampler per column
+    let mut samplers: Vec<ColumnSampler> = table.columns.iter().map(|_| ColumnSampler::new()).collect();
+    let mut row_count = 0u64;
+    for_each_row(catalog, db_name, table_name, file, |_, row| {
+        row_count += 1;
+        for (sampler, value) in samplers.iter_mut().zip(row) {
+            sampler.add(value);
+        }
+        Ok(())
+    })?;
+
+    // Step 3: Per column statistics
+    let columns = table
+        .columns
+        .iter()
+        .zip(samplers)
+        .map(|(column, sampler)| sampler.finish(column, row_count))
+        .collect();
+
+    Ok(TableStats {
+        row_count,
+        page_count: total_pages,
+        avg_width: total_width.checked_div(row_count).unwrap_or(0) as u32,
+        columns,
+    })
+}
+
+/// Statistics of one column gathered while its values stream by. Min and max are kept
+/// over every value; most common values and the histogram come from a uniform sample of
+/// at most STATS_SAMPLE_SIZE values (reservoir sampling), so memory stays bounded.
+struct ColumnSampler {
+    nulls: u64,
+    non_nulls: u64,
+    distinct: HyperLogLog,
+    min: Option<Value>,
+    max: Option<Value>,
+    sample: Vec<Value>,
+    rng: u64, // xorshift state, fixed seed so ANALYZE of the same rows gives the same statistics
+}
+
+impl ColumnSampler {
+    fn new() -> Self {
+        Self {
+            nulls: 0,
+            non_nulls: 0,
+            distinct: HyperLogLog::new(),
+            min: None,
+            max: None,
+            sample: Vec::new(),
+            rng: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    fn add(&mut self, value: Value) {
+        if matches!(value, Value::Null) {
+            self.nulls += 1;
+            return;
+        }
+        self.non_nulls += 1;
+        let mut key = Vec::new();
+        encode_value(&value, &mut key);
+        self.distinct.insert(&key);
+
+        // Values without an order (NaN, JSON) are left out of min/max
+        if value.partial_cmp(&value).is_some() {
+            if self.min.as_ref().is_none_or(|min| value < *min) {
+                self.min = Some(value.clone());
+            }
+            if self.max.as_ref().is_none_or(|max| value > *max) {
+                self.max = Some(value.clone());
+            }
+        }
+
+        // Keep the first values, then replace a random one with probability sample size / values seen
+        if self.sample.len() < STATS_SAMPLE_SIZE {
+            self.sample.push(value);
+        } else {
+            let slot = (self.next_random() % self.non_nulls) as usize;
+            if slot < STATS_SAMPLE_SIZE {
+                self.sample[slot] = value;
+            }
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    fn finish(self, column: &Column, row_count: u64) -> ColumnStats {
+        let fraction = |count: u64| if row_count == 0 { 0.0 } else { count as f64 / row_count as f64 };
+        // A value seen k times in the sample stands for k / sample size of the non-NULL rows
+        let sample_fraction =
+            |count: u64| count as f64 / self.sample.len() as f64 * fraction(self.non_nulls);
+
+        // Step 1: Most common values: seen more than once in the sample, most frequent first.
+        // Wide values are left out, a truncated text would name a different value.
+        let mut counts: HashMap<Vec<u8>, (&Value, u64)> = HashMap::new();
+        for value in &self.sample {
+            if field_text(value).len() <= STATS_MAX_VALUE_WIDTH {
+                let mut key = Vec::new();
+                encode_value(value, &mut key);
+                counts.entry(key).or_insert((value, 0)).1 += 1;
+            }
+        }
+        let mut most_common: Vec<(&Value, u64)> = counts.into_values().filter(|(_, count)| *count > 1).collect();
+        most_common.sort_by(|(a, a_count), (b, b_count)| {
+            b_count.cmp(a_count).then_with(|| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        });
+        most_common.truncate(STATS_TARGET);
+
+        // Step 2: Histogram bounds over the ordered values of the sample
+        let mut ordered: Vec<&Value> = self.sample.iter().filter(|v| v.partial_cmp(v).is_some()).collect();
+        ordered.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let bounds = STATS_TARGET.min(ordered.len().saturating_sub(1));
+        let histogram = match bounds {
+            0 => Vec::new(),
+            _ => (0..=bounds).map(|i| display_text(ordered[i * (ordered.len() - 1) / bounds])).collect(),
+        };
+
+        ColumnStats {
+            column_id: column.id,
+            null_frac: fraction(self.nulls),
+            n_distinct: self.distinct.estimate(),
+            min: self.min.as_ref().map(display_text),
+            max: self.max.as_ref().map(display_text),
+            most_common: most_common
+                .into_iter()
+                .map(|(value, count)| (field_text(value), sample_fraction(count)))
+                .collect(),
+            histogram,
+        }
+    }
+}
+
+/// A value as stored in min/max and histogram bounds: its text, cut to STATS_MAX_VALUE_WIDTH bytes.
+fn display_text(value: &Value) -> String {
+    let mut text = field_text(value);
+    if text.len() > STATS_MAX_VALUE_WIDTH {
+        let mut end = STATS_MAX_VALUE_WIDTH;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
+
@@ -0,0 +1,510 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::catalog::{
+    Catalog, Column, Constraint, DataType, Database, Oid, ReferentialAction, SchemaVersion,
+    StorageStrategy, Table,
+};
+use crate::config::StorageConfig;
+use crate::disk::read_page;
+use crate::error::{self, RookError};
+use crate::page::{ITEM_ID_SIZE, Page, init_page, page_count, page_free_space, page_tuples, place_tuple};
+use crate::stats::{ColumnStats, TableStats};
+use crate::table::{FILE_FORMAT_VERSION, check_file_format};
+use crate::tuple::{MAX_TUPLE_SIZE, decode_tuple, encode_tuple};
+use crate::value::Value;
+
+//-------------------------------------------
+// System tables:
+// rook_database   = one row per database (name -> OID)
+// rook_table      = one row per table (name -> OID, current schema version, next column id,
+//                   row/page count and average width as of the last ANALYZE, NULL if never analyzed)
+// rook_column     = one row per column of every schema version of every table
+// rook_constraint = one row per table constraint, in declaration order
+// rook_statistic  = one row per column of every analyzed table (see stats.rs)
+// Each is a regular table file (header page + slotted pages) in a catalog generation directory.
+// A save only writes the system tables that changed; the others are hard links to the files of
+// the previous generation, which are never modified once written.
+//-------------------------------------------
+pub const SYSTEM_TABLES: [&str; 5] = [
+    "rook_database",
+    "rook_table",
+    "rook_column",
+    "rook_constraint",
+    "rook_statistic",
+];
+
+/// Fixed schema of a system table. System tables never change shape, so they stay at version 0.
+pub fn system_table(name: &str) -> Option<Table> {
+    let text = |name: &str| Column::new(name, DataType::Text);
+    let int = |name: &str| Column::new(name, DataType::Int);
+    let boolean = |name: &str| Column::new(name, DataType::Boolean);
+    let json = |name: &str| Column::new(name, DataType::Json);
+    let oid = |name: &str| Column::new(name, DataType::BigInt);
+    let bigint = |name: &str| Column::new(name, DataType::BigInt);
+    let double = |name: &str| Column::new(name, DataType::Double);
+
+    let columns = match name {
+        "rook_database" => vec![text("name"), oid("oid")],
+        "rook_table" => vec![
+            text("database"),
+            text("name"),
+            int("schema_version"),
+            int("next_column_id"),
+            oid("oid"),
+            bigint("row_count"),
+            int("page_count"),
+            int("avg_width"),
+        ],
+        "rook_column" => vec![
+            text("database"),
+            text("table_name"),
+            int("schema_version"),
+            int("position"),
+            int("id"),
+            text("name"),
+            text("data_type"),
+            text("default_value"),
+            boolean("not_null"),
+            text("storage"),
+        ],
+        "rook_constraint" => vec![
+            text("database"),
+            text("table_name"),
+            int("position"),
+            text("name"),
+            text("kind"),
+            json("columns"),
+            text("expression"),
+            text("ref_table"),
+            json("ref_columns"),
+            text("on_delete"),
+        ],
+        "rook_statistic" => vec![
+            text("database"),
+            text("table_name"),
+            int("column_id"),
+            double("null_frac"),
+            bigint("n_distinct"),
+            text("min_value"),
+            text("max_value"),
+            json("most_common"),
+            json("histogram"),
+        ],
+        _ => return None,
+    };
+    Some(Table::new(columns))
+}
+
+/// Path of a system table file inside a catalog generation directory.
+pub fn system_table_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.dat", name))
+}
+
+/// Writes every system table of the catalog into `dir` and fsyncs the files. A system table
+/// identical to the one in `previous` (the current generation) is hard-linked instead.
+/// Each row must fit a page: system tables have no TOAST file, so a table whose CHECK expressions,
+/// defaults or key column lists are too long is refused with `InvalidDefinition`.
+pub fn write_system_tables(catalog: &Catalog, dir: &Path, previous: Option<&Path>) -> error::Result<()> {
+    // Step 1: Flatten the catalog into system table rows
+    let mut rows: HashMap<&str, Vec<Vec<Value>>> = HashMap::new();
+    let mut database_names: Vec<&String> = catalog.databases.keys().collect();
+    database_names.sort();
+
+    for db_name in database_names {
+        let database = &catalog.databases[db_name];
+        rows.entry("rook_database").or_default().push(vec![text(db_name), Value::BigInt(database.oid as i64)]);
+
+        let mut table_names: Vec<&String> = database.tables.keys().collect();
+        table_names.sort();
+        for table_name in table_names {
+            let table = &database.tables[table_name];
+            rows.entry("rook_table").or_default().push(vec![
+                text(db_name),
+                text(table_name),
+                Value::Int(table.schema_version as i32),
+                Value::Int(table.next_column_id as i32),
+                Value::BigInt(table.oid as i64),
+                table.stats.as_ref().map_or(Value::Null, |s| Value::BigInt(s.row_count as i64)),
+                table.stats.as_ref().map_or(Value::Null, |s| Value::Int(s.page_count as i32)),
+                table.stats.as_ref().map_or(Value::Null, |s| Value::Int(s.avg_width as i32)),
+            ]);
+
+            for column in table.stats.iter().flat_map(|stats| &stats.columns) {
+                rows.entry("rook_statistic").or_default().push(vec![
+                    text(db_name),
+                    text(table_name),
+                    Value::Int(column.column_id as i32),
+                    Value::Double(column.null_frac),
+                    Value::BigInt(column.n_distinct as i64),
+                    column.min.as_deref().map_or(Value::Null, text),
+                    column.max.as_deref().map_or(Value::Null, text),
+                    Value::Json(serde_json::json!(column.most_common)),
+                    Value::Json(serde_json::json!(column.histogram)),
+                ]);
+            }
+
+            let current = SchemaVersion { version: table.schema_version, columns: table.columns.clone() };
+            for schema in table.schema_history.iter().chain(std::iter::once(&current)) {
+                for (position, column) in schema.columns.iter().enumerate() {
+                    rows.entry("rook_column").or_default().push(vec![
+                        text(db_name),
+                        text(table_name),
+                        Value::Int(schema.version as i32),
+                        Value::Int(position as i32),
+                        Value::Int(column.id as i32),
+                        text(&column.name),
+                        text(&column.data_type.to_string()),
+                        column.default.as_deref().map_or(Value::Null, text),
+                        Value::Boolean(column.not_null),
+                        text(&column.storage.to_string()),
+                    ]);
+                }
+            }
+
+            for (position, constraint) in table.constraints.iter().enumerate() {
+                rows.entry("rook_constraint").or_default().push(constraint_row(
+                    db_name,
+                    table_name,
+                    position,
+                    constraint,
+                ));
+            }
+        }
+    }
+
+    // Step 2: Lay out each system table as a regular table file
+    for name in SYSTEM_TABLES {
+        let schema = system_table(name).expect("known system table");
+        let mut tuples = Vec::new();
+        for row in rows.get(name).map(Vec::as_slice).unwrap_or_default() {
+            let tuple = encode_tuple(&schema, row);
+            if tuple.len() > MAX_TUPLE_SIZE {
+                let owner = match name {
+                    "rook_database" => row[0].to_string(),
+                    _ => format!("{}.{}", row[0], row[1]),
+                };
+                return Err(RookError::InvalidDefinition(format!(
+                    "The {} entry of '{}' is {} bytes, larger than the {} a catalog page holds. \
+                     Shorten its CHECK expressions, defaults or key column lists.",
+                    name,
+                    owner,
+                    tuple.len(),
+                    MAX_TUPLE_SIZE
+                )));
+            }
+            tuples.push(tuple);
+        }
+        let bytes = table_file_bytes(&tuples)?;
+
+        // Step 3: Link it to the previous generation if nothing changed, otherwise write it
+        let path = system_table_path(dir, name);
+        let unchanged = previous
+            .map(|previous| system_table_path(previous, name))
+            .filter(|old| fs::read(old).is_ok_and(|old_bytes| old_bytes == bytes));
+        match unchanged {
+            Some(old) => fs::hard_link(old, &path)?,
+            None => {
+                let mut file = File::create(&path)?;
+                file.write_all(&bytes)?;
+                file.sync_all()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Bytes of a table file holding the given tuples, packed into pages in order.
+fn table_file_bytes(tuples: &[Vec<u8>]) -> error::Result<Vec<u8>> {
+    let mut pages = vec![Page::new()];
+    let mut page = Page::new();
+    init_page(&mut page);
+    pages.push(page);
+    for tuple in tuples {
+        let last = pages.last_mut().unwrap();
+        if page_free_space(last)? < tuple.len() as u32 + ITEM_ID_SIZE {
+            let mut page = Page::new();
+            init_page(&mut page);
+            pages.push(page);
+        }
+        place_tuple(pages.last_mut().unwrap(), tuple);
+    }
+
+    let page_count = pages.len() as u32;
+    let header = &mut pages[0].data;
+    header[0..4].copy_from_slice(&page_count.to_le_bytes());
+    header[4..8].copy_from_slice(&FILE_FORMAT_VERSION.to_le_bytes());
+    Ok(pages.iter().flat_map(|page| page.data.iter().copied()).collect())
+}
+
+/// Builds the catalog by scanning the system tables in `dir`.
+/// Rows that do not decode or point at unknown databases/tables make the catalog corrupt.
+pub fn read_system_tables(dir: &Path) -> error::Result<Catalog> {
+    let mut catalog = Catalog::new(&StorageConfig::default()); // `load_catalog` sets the real config
+
+    // Step 1: Databases
+    for row in scan_system_table(dir, "rook_database")? {
+        let database = Database { oid: as_oid(&row[1])?, tables: HashMap::new() };
+        catalog.databases.insert(as_text(&row[0])?, database);
+    }
+
+    // Step 2: Tables (columns and constraints are filled in below)
+    for row in scan_system_table(dir, "rook_table")? {
+        let table = Table {
+            oid: as_oid(&row[4])?,
+            stats: match &row[5] {
+                Value::Null => None,
+                row_count => Some(TableStats {
+                    row_count: as_u64(row_count)?,
+                    page_count: as_u32(&row[6])?,
+                    avg_width: as_u32(&row[7])?,
+                    columns: Vec::new(),
+                }),
+            },
+            columns: Vec::new(),
+            schema_version: as_u32(&row[2])?,
+            schema_history: Vec::new(),
+            next_column_id: as_u32(&row[3])?,
+            constraints: Vec::new(),
+        };
+        database_mut(&mut catalog, &row[0])?.tables.insert(as_text(&row[1])?, table);
+    }
+
+    // Step 3: Columns, grouped by table and schema version and ordered by position
+    let mut layouts: BTreeMap<(String, String, u32), Vec<(u32, Column)>> = BTreeMap::new();
+    for row in scan_system_table(dir, "rook_column")? {
+        let column = Column {
+            name: as_text(&row[5])?,
+            data_type: DataType::parse(&as_text(&row[6])?).map_err(corrupt)?,
+            id: as_u32(&row[4])?,
+            default: as_optional_text(&row[7])?,
+            not_null: as_bool(&row[8])?,
+            storage: StorageStrategy::parse(&as_text(&row[9])?).map_err(corrupt)?,
+        };
+        let key = (as_text(&row[0])?, as_text(&row[1])?, as_u32(&row[2])?);
+        layouts.entry(key).or_default().push((as_u32(&row[3])?, column));
+    }
+    for ((db_name, table_name, version), mut columns) in layouts {
+        columns.sort_by_key(|(position, _)| *position);
+        let columns = columns.into_iter().map(|(_, column)| column).collect();
+        let table = table_mut(&mut catalog, &db_name, &table_name)?;
+        if version == table.schema_version {
+            table.columns = columns;
+        } else {
+            table.schema_history.push(SchemaVersion { version, columns });
+        }
+    }
+
+    // Step 4: Constraints, in declaration order
+    let mut constraints: BTreeMap<(String, String), Vec<(u32, Constraint)>> = BTreeMap::new();
+    for row in scan_system_table(dir, "rook_constraint")? {
+        let key = (as_text(&row[0])?, as_text(&row[1])?);
+        constraints.entry(key).or_default().push((as_u32(&row[2])?, parse_constraint_row(&row)?));
+    }
+    for ((db_name, table_name), mut table_constraints) in constraints {
+        table_constraints.sort_by_key(|(position, _)| *position);
+        table_mut(&mut catalog, &db_name, &table_name)?.constraints =
+            table_constraints.into_iter().map(|(_, constraint)| constraint).collect();
+    }
+
+    // Step 5: Column statistics of analyzed tables
+    for row in scan_system_table(dir, "rook_statistic")? {
+        let column = ColumnStats {
+            column_id: as_u32(&row[2])?,
+            null_frac: as_f64(&row[3])?,
+            n_distinct: as_u64(&row[4])?,
+            min: as_optional_text(&row[5])?,
+            max: as_optional_text(&row[6])?,
+            most_common: as_json(&row[7])?,
+            histogram: as_json(&row[8])?,
+        };
+        let (db_name, table_name) = (as_text(&row[0])?, as_text(&row[1])?);
+        table_mut(&mut catalog, &db_name, &table_name)?
+            .stats
+            .as_mut()
+            .ok_or_else(|| corrupt(format!("statistics of unanalyzed table '{}.{}'", db_name, table_name)))?
+            .columns
+            .push(column);
+    }
+
+    for database in catalog.databases.values_mut() {
+        for table in database.tables.values_mut() {
+            table.schema_history.sort_by_key(|schema| schema.version);
+        }
+    }
+
+    Ok(catalog)
+}
+
+/// Reads every live row of a system table.
+fn scan_system_table(dir: &Path, name: &str) -> error::Result<Vec<Vec<Value>>> {
+    let schema = system_table(name).expect("known system table");
+    let path = system_table_path(dir, name);
+    let corrupt_page = |page: Option<u32>, e: &dyn std::fmt::Display| RookError::Corruption {
+        file: path.clone(),
+        page,
+        message: e.to_string(),
+    };
+
+    let mut file = File::open(&path).map_err(|e| {
+        io::Error::new(e.kind(), format!("System table {} is unreadable: {}", path.display(), e))
+    })?;
+    check_file_format(&mut file, &path)?;
+    let total_pages = page_count(&mut file).map_err(|e| corrupt_page(None, &e))?;
+    let mut rows = Vec::new();
+
+    for page_num in 1..total_pages {
+        let mut page = Page::new();
+        read_page(&mut file, &mut page, page_num).map_err(|e| corrupt_page(Some(page_num), &e))?;
+
+        for (_, tuple_data) in page_tuples(&page) {
+            let row = decode_tuple(&schema, tuple_data).map_err(|e| corrupt_page(Some(page_num), &e))?;
+            if row.len() != schema.columns.len() {
+                let message = format!("expected {} columns, found {}", schema.columns.len(), row.len());
+                return Err(corrupt_page(Some(page_num), &message));
+            }
+            rows.push(row);
+        }
+    }
+
+    Ok(rows)
+}
+
+fn constraint_row(db_name: &str, table_name: &str, position: usize, constraint: &Constraint) -> Vec<Value> {
+    let (kind, columns, expression, ref_table, ref_columns, on_delete) = match constraint {
+        Constraint::PrimaryKey { columns, .. } => ("PRIMARY KEY", names(columns), None, None, None, None),
+        Constraint::Unique { columns, .. } => ("UNIQUE", names(columns), None, None, None, None),
+        Constraint::Check { expression, .. } => ("CHECK", Value::Null, Some(expression), None, None, None),
+        Constraint::ForeignKey { columns, ref_table, ref_columns, on_delete, .. } => (
+            "FOREIGN KEY",
+            names(columns),
+            None,
+            Some(ref_table),
+            Some(names(ref_columns)),
+            Some(on_delete.to_string()),
+        ),
+    };
+    vec![
+        text(db_name),
+        text(table_name),
+        Value::Int(position as i32),
+        text(constraint.name()),
+        text(kind),
+        columns,
+        expression.map_or(Value::Null, |e| text(e)),
+        ref_table.map_or(Value::Null, |t| text(t)),
+        ref_columns.unwrap_or(Value::Null),
+        on_delete.map_or(Value::Null, |a| text(&a)),
+    ]
+}
+
+fn parse_constraint_row(row: &[Value]) -> error::Result<Constraint> {
+    let name = as_text(&row[3])?;
+    match as_text(&row[4])?.as_str() {
+        "PRIMARY KEY" => Ok(Constraint::PrimaryKey { name, columns: as_names(&row[5])? }),
+        "UNIQUE" => Ok(Constraint::Unique { name, columns: as_names(&row[5])? }),
+        "CHECK" => Ok(Constraint::Check { name, expression: as_text(&row[6])? }),
+        "FOREIGN KEY" => Ok(Constraint::ForeignKey {
+            name,
+            columns: as_names(&row[5])?,
+            ref_table: as_text(&row[7])?,
+            ref_columns: as_names(&row[8])?,
+            on_delete: ReferentialAction::parse(&as_text(&row[9])?).map_err(corrupt)?,
+        }),
+        kind => Err(corrupt(format!("unknown constraint kind '{}'", kind))),
+    }
+}
+
+fn database_mut<'a>(catalog: &'a mut Catalog, db_name: &Value) -> error::Result<&'a mut Database> {
+    let db_name = as_text(db_name)?;
+    catalog
+        .databases
+        .get_mut(&db_name)
+        .ok_or_else(|| corrupt(format!("unknown database '{}'", db_name)))
+}
+
+fn table_mut<'a>(catalog: &'a mut Catalog, db_name: &str, table_name: &str) -> error::Result<&'a mut Table> {
+    catalog
+        .databases
+        .get_mut(db_name)
+        .and_then(|database| database.tables.get_mut(table_name))
+        .ok_or_else(|| corrupt(format!("unknown table '{}.{}'", db_name, table_name)))
+}
+
+fn text(s: &str) -> Value {
+    Value::Text(s.to_string())
+}
+
+fn names(names: &[String]) -> Value {
+    Value::Json(serde_json::Value::from(names.to_vec()))
+}
+
+fn corrupt(message: impl Into<String>) -> RookError {
+    io::Error::new(io::ErrorKind::InvalidData, format!("Catalog system tables are corrupt: {}", message.into())).into()
+}
+
+fn as_text(value: &Value) -> error::Result<String> {
+    match value {
+        Value::Text(s) => Ok(s.clone()),
+        other => Err(corrupt(format!("expected text, found {}", other))),
+    }
+}
+
+fn as_optional_text(value: &Value) -> error::Result<Option<String>> {
+    match value {
+        Value::Null => Ok(None),
+        other => as_text(other).map(Some),
+    }
+}
+
+fn as_u32(value: &Value) -> error::Result<u32> {
+    match value {
+        Value::Int(n) if *n >= 0 => Ok(*n as u32),
+        other => Err(corrupt(format!("expected a non-negative integer, found {}", other))),
+    }
+}
+
+fn as_oid(value: &Value) -> error::Result<Oid> {
+    match value {
+        Value::BigInt(n) => Oid::try_from(*n).map_err(|_| corrupt(format!("invalid OID {}", n))),
+        other => Err(corrupt(format!("expected an OID, found {}", other))),
+    }
+}
+
+fn as_u64(value: &Value) -> error::Result<u64> {
+    match value {
+        Value::BigInt(n) if *n >= 0 => Ok(*n as u64),
+        other => Err(corrupt(format!("expected a non-negative integer, found {}", other))),
+    }
+}
+
+fn as_f64(value: &Value) -> error::Result<f64> {
+    match value {
+        Value::Double(n) => Ok(*n),
+        other => Err(corrupt(format!("expected a number, found {}", other))),
+    }
+}
+
+fn as_bool(value: &Value) -> error::Result<bool> {
+    match value {
+        Value::Boolean(b) => Ok(*b),
+        other => Err(corrupt(format!("expected a boolean, found {}", other))),
+    }
+}
+
+fn as_names(value: &Value) -> error::Result<Vec<String>> {
+    match value {
+        Value::Json(json) => serde_json::from_value(json.clone()).map_err(|e| corrupt(e.to_string())),
+        other => Err(corrupt(format!("expected a list of names, found {}", other))),
+    }
+}
+
+fn as_json<T: serde::de::DeserializeOwned>(value: &Value) -> error::Result<T> {
+    match value {
+        Value::Json(json) => serde_json::from_value(json.clone()).map_err(|e| corrupt(e.to_string())),
+        other => Err(corrupt(format!("expected JSON, found {}", other))),
+    }
+}
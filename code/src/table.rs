@@ -1,8 +1,11 @@
 use std::fs::File;
-use std::io::{self, Seek, SeekFrom, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
 
 use crate::disk::create_page;
+use crate::error::{self, RookError};
 pub const TABLE_HEADER_SIZE: u32 = 8192;
+pub const FILE_FORMAT_VERSION: u32 = 1; // Header bytes[4..8]; 0 = written before tuples had a header
 
 pub struct Table {
     pub data: Vec<u8>, // Fixed-size buffer holds the raw bytes of a table.
@@ -25,7 +28,13 @@ impl Table {
     }
 }
 
-pub fn init_table(file: &mut File) -> io::Result<()> {
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn init_table(file: &mut File) -> error::Result<()> {
     // Move cursor to the beginning of the file
     file.seek(SeekFrom::Start(0))?;
 
@@ -35,6 +44,7 @@ pub fn init_table(file: &mut File) -> io::Result<()> {
     //  Write "1" into the first 4 bytes (little-endian u32)
     // This can represent the total number of pages, e.g. 1
     zero_buf[0..4].copy_from_slice(&1u32.to_le_bytes());
+    zero_buf[4..8].copy_from_slice(&FILE_FORMAT_VERSION.to_le_bytes());
 
     // Write the full buffer (header) to the file
     file.write_all(&zero_buf)?;
@@ -47,3 +57,21 @@ pub fn init_table(file: &mut File) -> io::Result<()> {
 
     Ok(())
 }
+
+/// Fails with `Corruption` unless the header of a table, TOAST or system table file carries the
+/// format version this build writes. Older files are refused rather than misread.
+pub fn check_file_format(file: &mut File, path: &Path) -> error::Result<()> {
+    let mut buffer = [0u8; 4];
+    file.seek(SeekFrom::Start(4))?;
+    file.read_exact(&mut buffer).map_err(|e| RookError::corrupt_page(e, path, 0))?;
+
+    let version = u32::from_le_bytes(buffer);
+    if version != FILE_FORMAT_VERSION {
+        return Err(RookError::Corruption {
+            file: path.to_path_buf(),
+            page: Some(0),
+            message: format!("file format version {} is not supported (expected {})", version, FILE_FORMAT_VERSION),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,559 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use lz4_flex::block::{compress_prepend_size, decompress_size_prepended};
+
+use crate::catalog::{StorageStrategy, Table};
+use crate::disk::{read_page, write_page};
+use crate::error::{self, RookError};
+use crate::page::{
+    ITEM_ID_SIZE, PAGE_HEADER_SIZE, PAGE_SIZE, Page, init_page, insert_tuple, page_count, page_free_space,
+    place_tuple, publish_page_count, truncate_uncommitted_pages,
+};
+use crate::table::init_table;
+use crate::tuple::{MAX_TUPLE_SIZE, tuple_layout};
+use crate::value::{
+    VARLEN_COMPRESSED, VARLEN_EXTERNAL, VARLEN_HEADER_SIZE, VARLEN_LENGTH_MASK, fixed_size,
+};
+
+//-------------------------------------------
+// TOAST (compression and out-of-line storage for large values):
+// Variable-length values above COMPRESSION_THRESHOLD are LZ4 compressed (u32 raw size +
+// LZ4 block, varlen header flag VARLEN_COMPRESSED) when their column's storage strategy allows it.
+// Tuples still larger than TOAST_TUPLE_THRESHOLD have their largest values moved to the
+// table's TOAST file ({table_oid}.toast, same page format as the table file), split into
+// chunks. The main tuple keeps a pointer datum in place of the value:
+//   varlen header = VARLEN_EXTERNAL [| VARLEN_COMPRESSED] | TOAST_POINTER_SIZE
+//   bytes[0..8]   = value id
+//   bytes[8..12]  = size of the stored (possibly compressed) value in bytes
+//   bytes[12..16] = first TOAST page holding a chunk of the value
+// Chunk tuple = value id (u64) + chunk number (u32) + up to TOAST_CHUNK_SIZE bytes.
+// TOAST file header bytes[8..16] = next value id (bytes[4..8] hold the file format version).
+//
+// Bulk loads stage their chunks: they go to pages after the committed ones and only count once
+// the load publishes them, before the tuples pointing at them. A load that fails or crashes first
+// leaves them in the uncommitted tail, which the next staged load or `recover_uncommitted_pages` cuts.
+// A crash between the two publishes leaves chunks nothing points at; they are never read, only
+// their space is lost.
+//-------------------------------------------
+pub const TOAST_TUPLE_THRESHOLD: usize = PAGE_SIZE / 4; // Tuples above this get values moved out
+pub const COMPRESSION_THRESHOLD: usize = 1024; // Values above this are compressed if allowed
+pub const TOAST_CHUNK_SIZE: usize = 2000; // Four chunks fit in one TOAST page
+pub const TOAST_POINTER_SIZE: usize = 16;
+const CHUNK_HEADER_SIZE: usize = 12;
+
+/// Pointer datum stored in the main tuple for an out-of-line value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToastPointer {
+    pub value_id: u64,
+    pub size: u32,
+    pub first_page: u32,
+    pub compressed: bool, // The chunks hold the compressed form of the value
+}
+
+impl ToastPointer {
+    /// Appends the pointer as a varlen value (flagged header + body).
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        let mut header = VARLEN_EXTERNAL | TOAST_POINTER_SIZE as u32;
+        if self.compressed {
+            header |= VARLEN_COMPRESSED;
+        }
+        out.extend_from_slice(&header.to_le_bytes());
+        out.extend_from_slice(&self.value_id.to_le_bytes());
+        out.extend_from_slice(&self.size.to_le_bytes());
+        out.extend_from_slice(&self.first_page.to_le_bytes());
+    }
+
+    /// Reads a pointer from a varlen value, or None if the value is stored inline.
+    pub fn decode(varlen: &[u8]) -> Option<Self> {
+        let header = u32::from_le_bytes(varlen.get(0..VARLEN_HEADER_SIZE)?.try_into().unwrap());
+        if header & VARLEN_EXTERNAL == 0 {
+            return None;
+        }
+        let body = varlen.get(VARLEN_HEADER_SIZE..VARLEN_HEADER_SIZE + TOAST_POINTER_SIZE)?;
+        Some(Self {
+            value_id: u64::from_le_bytes(body[0..8].try_into().unwrap()),
+            size: u32::from_le_bytes(body[8..12].try_into().unwrap()),
+            first_page: u32::from_le_bytes(body[12..16].try_into().unwrap()),
+            compressed: header & VARLEN_COMPRESSED != 0,
+        })
+    }
+}
+
+/// The TOAST file of a table, opened on first use and created on first write.
+pub struct ToastFile {
+    path: PathBuf,
+    file: Option<File>,
+    staging: bool, // Chunks stay uncommitted until `publish`
+    staged_pages: Option<u32>, // Page count including the staged pages, once a chunk was staged
+}
+
+impl ToastFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            file: None,
+            staging: false,
+            staged_pages: None,
+        }
+    }
+
+    /// A TOAST file for a bulk load: chunks are written after the committed pages and only
+    /// become visible with `publish`.
+    pub fn staged(path: PathBuf) -> Self {
+        Self {
+            staging: true,
+            ..Self::new(path)
+        }
+    }
+
+    /// Counts the staged pages in the file header. Must happen before the tuples pointing at
+    /// the staged chunks are published.
+    pub fn publish(&mut self) -> error::Result<()> {
+        if let (Some(pages), Some(file)) = (self.staged_pages.take(), self.file.as_mut()) {
+            publish_page_count(file, pages)?;
+        }
+        Ok(())
+    }
+
+    /// Drops the staged chunks of a load that will not be published.
+    pub fn discard(&mut self) {
+        if let (Some(_), Some(file)) = (self.staged_pages.take(), self.file.as_mut()) {
+            let _ = truncate_uncommitted_pages(file);
+        }
+    }
+
+    /// Opens the file for reading; fails if the table has never stored a value out of line.
+    fn open(&mut self) -> error::Result<&mut File> {
+        if self.file.is_none() {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&self.path)
+                .map_err(|e| {
+                    io::Error::new(e.kind(), format!("TOAST file '{}': {}", self.path.display(), e))
+                })?;
+            self.file = Some(file);
+        }
+        Ok(self.file.as_mut().unwrap())
+    }
+
+    /// Opens the file for writing, creating and initializing it if needed.
+    fn open_or_create(&mut self) -> error::Result<&mut File> {
+        if self.file.is_none() && !self.path.exists() {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&self.path)?;
+            init_table(&mut file)?;
+            self.file = Some(file);
+        }
+        self.open()
+    }
+
+    /// Whether the table has a TOAST file at all.
+    fn exists(&self) -> bool {
+        self.file.is_some() || self.path.exists()
+    }
+}
+
+/// An attribute of a tuple being toasted.
+enum Slot {
+    Raw(Range<usize>), // Copied as-is (fixed-length or already toasted)
+    Varlen {
+        storage: StorageStrategy,
+        body: Vec<u8>,
+        compressed: bool,
+        external: bool,
+    },
+}
+
+impl Slot {
+    fn size(&self) -> usize {
+        match self {
+            Slot::Raw(range) => range.len(),
+            Slot::Varlen { external: true, .. } => VARLEN_HEADER_SIZE + TOAST_POINTER_SIZE,
+            Slot::Varlen { body, .. } => VARLEN_HEADER_SIZE + body.len(),
+        }
+    }
+
+    fn body_len(&self) -> usize {
+        match self {
+            Slot::Varlen {
+                body,
+                external: false,
+                ..
+            } => body.len(),
+            _ => 0,
+        }
+    }
+}
+
+/// Shrinks a tuple according to the storage strategy of each column, largest values first:
+/// 1. values above `COMPRESSION_THRESHOLD` in MAIN/EXTENDED columns are compressed,
+/// 2. while the tuple exceeds `TOAST_TUPLE_THRESHOLD`, EXTENDED/EXTERNAL values move to the TOAST file,
+/// 3. MAIN values move out of line only if the tuple still does not fit in a page.
+///
+/// PLAIN values are never touched. Fails without writing anything if the tuple cannot fit a page.
+pub fn toast_tuple(table: &Table, data: Vec<u8>, toast: &mut ToastFile) -> error::Result<Vec<u8>> {
+    shrink_tuple(table, data, |value| toast.store_value(value))
+}
+
+/// `toast_tuple` with a TOAST file shared by several threads: values are compressed without
+/// holding the lock, which is only taken to store the values moved out of line.
+pub fn toast_tuple_shared(table: &Table, data: Vec<u8>, toast: &Mutex<ToastFile>) -> error::Result<Vec<u8>> {
+    shrink_tuple(table, data, |value| {
+        // A thread that panicked while storing may have left the file half written
+        let mut toast = toast
+            .lock()
+            .map_err(|_| io::Error::other("TOAST file was left unusable by a failed thread"))?;
+        toast.store_value(value)
+    })
+}
+
+/// Shrinks a tuple as described at `toast_tuple`, storing out-of-line values with `store_value`.
+fn shrink_tuple(
+    table: &Table,
+    data: Vec<u8>,
+    mut store_value: impl FnMut(&[u8]) -> error::Result<ToastPointer>,
+) -> error::Result<Vec<u8>> {
+    if data.len() <= COMPRESSION_THRESHOLD {
+        return Ok(data);
+    }
+
+    let layout = tuple_layout(table, &data)?;
+    let mut slots: Vec<Slot> = Vec::with_capacity(layout.attributes.len());
+    for span in &layout.attributes {
+        let Some(range) = span.range.clone() else {
+            continue;
+        };
+        if fixed_size(span.column.data_type).is_some()
+            || varlen_header(&data, &range) & !VARLEN_LENGTH_MASK != 0
+        {
+            slots.push(Slot::Raw(range));
+            continue;
+        }
+        slots.push(Slot::Varlen {
+            storage: span.column.storage,
+            body: data[range.start + VARLEN_HEADER_SIZE..range.end].to_vec(),
+            compressed: false,
+            external: false,
+        });
+    }
+    let mut size = layout.values_start + slots.iter().map(Slot::size).sum::<usize>();
+
+    // Largest inline values first among the slots whose strategy allows `allowed`
+    let by_size = |slots: &[Slot], allowed: &dyn Fn(StorageStrategy) -> bool| {
+        let mut indexes: Vec<usize> = (0..slots.len())
+            .filter(|&i| {
+                matches!(slots[i], Slot::Varlen { storage, external: false, .. } if allowed(storage))
+            })
+            .collect();
+        indexes.sort_by_key(|&i| std::cmp::Reverse(slots[i].body_len()));
+        indexes
+    };
+
+    // Step 1: Compress large values where allowed and worthwhile
+    for i in by_size(&slots, &StorageStrategy::allows_compression) {
+        if let Slot::Varlen {
+            body, compressed, ..
+        } = &mut slots[i]
+            && body.len() > COMPRESSION_THRESHOLD
+        {
+            let packed = compress_prepend_size(body);
+            if packed.len() < body.len() {
+                size -= body.len() - packed.len();
+                *body = packed;
+                *compressed = true;
+            }
+        }
+    }
+
+    // Step 2: Move EXTENDED/EXTERNAL values out of line until the tuple is small enough,
+    // Step 3: then MAIN values, but only as far as needed to fit a page
+    let passes: [(&dyn Fn(StorageStrategy) -> bool, usize); 2] = [
+        (
+            &|s| matches!(s, StorageStrategy::Extended | StorageStrategy::External),
+            TOAST_TUPLE_THRESHOLD,
+        ),
+        (&|s| s == StorageStrategy::Main, MAX_TUPLE_SIZE),
+    ];
+    for (allowed, target) in passes {
+        for i in by_size(&slots, allowed) {
+            if size <= target || slots[i].body_len() <= TOAST_POINTER_SIZE {
+                break;
+            }
+            let before = slots[i].size();
+            if let Slot::Varlen { external, .. } = &mut slots[i] {
+                *external = true;
+            }
+            size -= before - slots[i].size();
+        }
+    }
+
+    if size > MAX_TUPLE_SIZE {
+        return Err(RookError::TupleTooLarge(format!(
+            "row is {} bytes even after compression and out-of-line storage, larger than the maximum tuple size of {} bytes",
+            size, MAX_TUPLE_SIZE
+        )));
+    }
+
+    // Step 4: Rebuild the tuple, writing out-of-line values to the TOAST file
+    let mut toasted = data[..layout.values_start].to_vec();
+    for slot in &slots {
+        match slot {
+            Slot::Raw(range) => toasted.extend_from_slice(&data[range.clone()]),
+            Slot::Varlen {
+                body,
+                compressed,
+                external: true,
+                ..
+            } => {
+                let mut pointer = store_value(body)?;
+                pointer.compressed = *compressed;
+                pointer.encode(&mut toasted);
+            }
+            Slot::Varlen {
+                body, compressed, ..
+            } => {
+                let mut header = body.len() as u32;
+                if *compressed {
+                    header |= VARLEN_COMPRESSED;
+                }
+                toasted.extend_from_slice(&header.to_le_bytes());
+                toasted.extend_from_slice(body);
+            }
+        }
+    }
+    Ok(toasted)
+}
+
+/// Replaces every TOAST pointer and compressed value in a tuple with the plain value.
+pub fn detoast_tuple(table: &Table, data: &[u8], toast: &mut ToastFile) -> error::Result<Vec<u8>> {
+    let layout = tuple_layout(table, data)?;
+
+    let mut detoasted = data[..layout.values_start].to_vec();
+    for span in &layout.attributes {
+        let Some(range) = span.range.clone() else {
+            continue;
+        };
+        if fixed_size(span.column.data_type).is_some() {
+            detoasted.extend_from_slice(&data[range]);
+            continue;
+        }
+
+        let header = varlen_header(data, &range);
+        let value = match ToastPointer::decode(&data[range.clone()]) {
+            Some(pointer) => {
+                let stored = fetch_value(toast.open()?, &pointer)?;
+                if pointer.compressed {
+                    decompress(&stored)?
+                } else {
+                    stored
+                }
+            }
+            None if header & VARLEN_COMPRESSED != 0 => {
+                decompress(&data[range.start + VARLEN_HEADER_SIZE..range.end])?
+            }
+            None => {
+                detoasted.extend_from_slice(&data[range]);
+                continue;
+            }
+        };
+        detoasted.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        detoasted.extend_from_slice(&value);
+    }
+    Ok(detoasted)
+}
+
+/// Header of the variable-length value at `range` (tuple_layout guarantees it is present).
+fn varlen_header(data: &[u8], range: &Range<usize>) -> u32 {
+    u32::from_le_bytes(
+        data[range.start..range.start + VARLEN_HEADER_SIZE]
+            .try_into()
+            .unwrap(),
+    )
+}
+
+fn decompress(compressed: &[u8]) -> error::Result<Vec<u8>> {
+    decompress_size_prepended(compressed).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Corrupt compressed value: {}", e),
+        ).into()
+    })
+}
+
+/// Deletes the TOAST chunks of every out-of-line value of a tuple (used when the row is deleted).
+pub fn delete_toasted_values(table: &Table, data: &[u8], toast: &mut ToastFile) -> error::Result<()> {
+    let layout = tuple_layout(table, data)?;
+    for span in &layout.attributes {
+        if let Some(range) = span.range.clone()
+            && let Some(pointer) = ToastPointer::decode(&data[range])
+            && toast.exists()
+        {
+            delete_value(toast.open()?, &pointer)?;
+        }
+    }
+    Ok(())
+}
+
+impl ToastFile {
+    /// Writes a value as chunks at the end of the file (or of the staged pages) and returns its pointer.
+    fn store_value(&mut self, value: &[u8]) -> error::Result<ToastPointer> {
+        let staging = self.staging;
+        let staged_pages = self.staged_pages;
+        let file = self.open_or_create()?;
+        let (pointer, staged_pages) = store_value(file, value, staging.then_some(staged_pages))?;
+        self.staged_pages = staged_pages;
+        Ok(pointer)
+    }
+}
+
+/// Writes a value as chunks at the end of the TOAST file and returns its pointer.
+/// `staged` is None to commit each chunk, or the staged page count (None before the first
+/// staged chunk); the new staged page count is returned with the pointer.
+fn store_value(
+    file: &mut File,
+    value: &[u8],
+    staged: Option<Option<u32>>,
+) -> error::Result<(ToastPointer, Option<u32>)> {
+    // Step 1: Allocate a value id from the file header
+    let mut id_bytes = [0u8; 8];
+    file.seek(SeekFrom::Start(8))?;
+    file.read_exact(&mut id_bytes)?;
+    let value_id = u64::from_le_bytes(id_bytes);
+    file.seek(SeekFrom::Start(8))?;
+    file.write_all(&(value_id + 1).to_le_bytes())?;
+
+    // Step 2: Append the chunks (always to the last page or new pages after it)
+    let committed = page_count(file)?;
+    let mut staged_pages = match staged {
+        Some(None) => {
+            truncate_uncommitted_pages(file)?; // Left by a staged load that never published
+            Some(committed)
+        }
+        staged => staged.flatten(),
+    };
+    let first_page = staged_pages.unwrap_or(committed) - 1;
+    for (chunk_number, chunk) in value.chunks(TOAST_CHUNK_SIZE).enumerate() {
+        let mut chunk_tuple = Vec::with_capacity(CHUNK_HEADER_SIZE + chunk.len());
+        chunk_tuple.extend_from_slice(&value_id.to_le_bytes());
+        chunk_tuple.extend_from_slice(&(chunk_number as u32).to_le_bytes());
+        chunk_tuple.extend_from_slice(chunk);
+        match staged_pages.as_mut() {
+            Some(pages) => stage_chunk(file, committed, pages, &chunk_tuple)?,
+            None => {
+                insert_tuple(file, &chunk_tuple)?;
+            }
+        }
+    }
+
+    let pointer = ToastPointer {
+        value_id,
+        size: value.len() as u32,
+        first_page,
+        compressed: false,
+    };
+    Ok((pointer, staged_pages))
+}
+
+/// Adds a chunk to the last staged page, or to a new page after it. Committed pages are never
+/// written, and the header count is left alone.
+fn stage_chunk(file: &mut File, committed: u32, pages: &mut u32, chunk_tuple: &[u8]) -> error::Result<()> {
+    let mut page = Page::new();
+    let last_page = *pages - 1;
+    if last_page >= committed {
+        read_page(file, &mut page, last_page)?;
+        if page_free_space(&page)? >= chunk_tuple.len() as u32 + ITEM_ID_SIZE {
+            place_tuple(&mut page, chunk_tuple);
+            write_page(file, &mut page, last_page)?;
+            return Ok(());
+        }
+    }
+
+    init_page(&mut page);
+    place_tuple(&mut page, chunk_tuple);
+    write_page(file, &mut page, *pages)?;
+    *pages += 1;
+    Ok(())
+}
+
+/// Calls `visit(page, item position, chunk number, chunk bytes)` for every live chunk of a value.
+fn for_each_chunk(
+    file: &mut File,
+    pointer: &ToastPointer,
+    mut visit: impl FnMut(&mut Page, usize, u32, &[u8]) -> bool,
+) -> error::Result<()> {
+    let total_pages = page_count(file)?;
+    let mut seen = 0;
+    for page_num in pointer.first_page.max(1)..total_pages {
+        let mut page = Page::new();
+        read_page(file, &mut page, page_num)?;
+
+        let lower = u32::from_le_bytes(page.data[0..4].try_into().unwrap());
+        let mut dirty = false;
+        for i in 0..(lower - PAGE_HEADER_SIZE) / ITEM_ID_SIZE {
+            let base = (PAGE_HEADER_SIZE + i * ITEM_ID_SIZE) as usize;
+            let offset = u32::from_le_bytes(page.data[base..base + 4].try_into().unwrap()) as usize;
+            let length =
+                u32::from_le_bytes(page.data[base + 4..base + 8].try_into().unwrap()) as usize;
+            if length < CHUNK_HEADER_SIZE {
+                continue; // Deleted chunk
+            }
+
+            let chunk = page.data[offset..offset + length].to_vec();
+            if u64::from_le_bytes(chunk[0..8].try_into().unwrap()) != pointer.value_id {
+                continue;
+            }
+            let chunk_number = u32::from_le_bytes(chunk[8..12].try_into().unwrap());
+            seen += chunk.len() - CHUNK_HEADER_SIZE;
+            dirty |= visit(&mut page, base, chunk_number, &chunk[CHUNK_HEADER_SIZE..]);
+        }
+
+        if dirty {
+            write_page(file, &mut page, page_num)?;
+        }
+        // Chunks are written consecutively, so the value ends once all its bytes were seen
+        if seen >= pointer.size as usize {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Reassembles an out-of-line value from its chunks.
+fn fetch_value(file: &mut File, pointer: &ToastPointer) -> error::Result<Vec<u8>> {
+    let mut chunks: Vec<(u32, Vec<u8>)> = Vec::new();
+    for_each_chunk(file, pointer, |_, _, chunk_number, bytes| {
+        chunks.push((chunk_number, bytes.to_vec()));
+        false
+    })?;
+    chunks.sort_by_key(|(chunk_number, _)| *chunk_number);
+
+    let value: Vec<u8> = chunks.into_iter().flat_map(|(_, bytes)| bytes).collect();
+    if value.len() != pointer.size as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "TOAST value {} is {} bytes, expected {}",
+                pointer.value_id,
+                value.len(),
+                pointer.size
+            ),
+        ).into());
+    }
+    Ok(value)
+}
+
+/// Marks every chunk of an out-of-line value as deleted.
+fn delete_value(file: &mut File, pointer: &ToastPointer) -> error::Result<()> {
+    for_each_chunk(file, pointer, |page, item_id_pos, _, _| {
+        page.data[item_id_pos + 4..item_id_pos + 8].copy_from_slice(&0u32.to_le_bytes());
+        true
+    })
+}
@@ -0,0 +1,213 @@
+use std::ops::Range;
+
+use crate::catalog::{Column, Table};
+use crate::error::{self, RookError};
+use crate::page::{ITEM_ID_SIZE, PAGE_HEADER_SIZE, PAGE_SIZE};
+use crate::value::{
+    VARLEN_HEADER_SIZE, VARLEN_LENGTH_MASK, Value, decode_value, encode_value, fixed_size,
+    parse_value,
+};
+
+//-------------------------------------------
+// Tuple Layout:
+// bytes[0..2]   = schema version the tuple was written with
+// bytes[2..4]   = number of attributes stored (high bit set = null bitmap follows)
+// [bitmap]      = ceil(attributes / 8) bytes, bit i set = attribute i is NULL
+// then          = non-NULL attribute values, in column order of that schema version
+//-------------------------------------------
+pub const TUPLE_HEADER_SIZE: usize = 4;
+pub const TUPLE_HAS_NULLS: u16 = 0x8000;
+pub const MAX_SCHEMA_VERSION: u32 = u16::MAX as u32; // Highest version the header can store
+pub const MAX_TUPLE_SIZE: usize = PAGE_SIZE - (PAGE_HEADER_SIZE + ITEM_ID_SIZE) as usize; // Must fit an empty page
+
+/// Builds a tuple (header + values) for the current schema version of the table.
+/// The null bitmap is only written when at least one value is NULL.
+pub fn encode_tuple(table: &Table, values: &[Value]) -> Vec<u8> {
+    let mut tuple_bytes = Vec::with_capacity(TUPLE_HEADER_SIZE + values.len() * 4);
+    let has_nulls = values.contains(&Value::Null);
+    let mut attributes = values.len() as u16;
+    if has_nulls {
+        attributes |= TUPLE_HAS_NULLS;
+    }
+    tuple_bytes.extend_from_slice(&(table.schema_version as u16).to_le_bytes());
+    tuple_bytes.extend_from_slice(&attributes.to_le_bytes());
+
+    if has_nulls {
+        let mut bitmap = vec![0u8; values.len().div_ceil(8)];
+        for (i, value) in values.iter().enumerate() {
+            if *value == Value::Null {
+                bitmap[i / 8] |= 1 << (i % 8);
+            }
+        }
+        tuple_bytes.extend_from_slice(&bitmap);
+    }
+
+    for value in values {
+        encode_value(value, &mut tuple_bytes);
+    }
+    tuple_bytes
+}
+
+/// Parses the fields of a CSV row according to the table schema.
+/// Empty or missing trailing fields take the column DEFAULT, or NULL when there is none;
+/// NULL in a NOT NULL column is rejected.
+pub fn parse_row(table: &Table, fields: &[&str]) -> error::Result<Vec<Value>> {
+    let fields: Vec<Option<&str>> = fields.iter().map(|&field| Some(field).filter(|f| !f.is_empty())).collect();
+    parse_fields(table, &fields)
+}
+
+/// Like `parse_row`, with the missing fields given as None: an empty field is an empty value,
+/// not a NULL.
+pub fn parse_fields(table: &Table, fields: &[Option<&str>]) -> error::Result<Vec<Value>> {
+    if fields.len() > table.columns.len() {
+        return Err(RookError::SchemaMismatch(format!(
+            "expected {} columns, found {}",
+            table.columns.len(),
+            fields.len()
+        )));
+    }
+
+    let mut values = Vec::with_capacity(table.columns.len());
+    for (i, column) in table.columns.iter().enumerate() {
+        let field = fields.get(i).copied().flatten().or(column.default.as_deref());
+
+        // Missing fields without a DEFAULT are NULL
+        let Some(field) = field else {
+            if column.not_null {
+                return Err(RookError::ConstraintViolation(format!("column '{}' is NOT NULL", column.name)));
+            }
+            values.push(Value::Null);
+            continue;
+        };
+
+        let value = parse_value(column.data_type, field)
+            .map_err(|e| RookError::SchemaMismatch(format!("column '{}': {}", column.name, e)))?;
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+/// Parses the fields of a CSV row according to the table schema and encodes the tuple.
+/// Large rows are returned as-is; `toast::toast_tuple` moves big values out of line.
+pub fn encode_row(table: &Table, fields: &[&str]) -> error::Result<Vec<u8>> {
+    Ok(encode_tuple(table, &parse_row(table, fields)?))
+}
+
+/// Where one attribute of a stored tuple lives: its column and byte range (None for NULL).
+/// Variable-length ranges include the varlen header.
+pub struct AttributeSpan<'a> {
+    pub column: &'a Column,
+    pub range: Option<Range<usize>>,
+}
+
+/// Byte layout of a stored tuple, located without decoding any value.
+pub struct TupleLayout<'a> {
+    pub version: u32,
+    pub values_start: usize, // First byte after the header and null bitmap
+    pub attributes: Vec<AttributeSpan<'a>>,
+}
+
+/// Locates every attribute of a tuple, using the layout of the schema version it was written with.
+pub fn tuple_layout<'a>(table: &'a Table, data: &[u8]) -> error::Result<TupleLayout<'a>> {
+    if data.len() < TUPLE_HEADER_SIZE {
+        return Err(RookError::MalformedTuple("Tuple header is truncated".to_string()));
+    }
+
+    let version = u16::from_le_bytes(data[0..2].try_into().unwrap()) as u32;
+    let columns = table
+        .columns_for_version(version)
+        .ok_or_else(|| RookError::MalformedTuple(format!("Tuple has unknown schema version {}", version)))?;
+
+    let attributes = u16::from_le_bytes(data[2..4].try_into().unwrap());
+    let mut cursor = TUPLE_HEADER_SIZE;
+    let mut bitmap: &[u8] = &[];
+    if attributes & TUPLE_HAS_NULLS != 0 {
+        let count = (attributes & !TUPLE_HAS_NULLS) as usize;
+        bitmap = data
+            .get(cursor..cursor + count.div_ceil(8))
+            .ok_or_else(|| RookError::MalformedTuple("Tuple null bitmap is truncated".to_string()))?;
+        cursor += bitmap.len();
+    }
+    let is_null = |i: usize| bitmap.get(i / 8).is_some_and(|byte| byte & (1 << (i % 8)) != 0);
+    let values_start = cursor;
+
+    let mut spans = Vec::with_capacity(columns.len());
+    for (i, column) in columns.iter().enumerate() {
+        if is_null(i) {
+            spans.push(AttributeSpan { column, range: None });
+            continue;
+        }
+
+        let size = match fixed_size(column.data_type) {
+            Some(size) => size,
+            None => {
+                let header = data.get(cursor..cursor + VARLEN_HEADER_SIZE);
+                let length = header.map(|h| u32::from_le_bytes(h.try_into().unwrap()));
+                VARLEN_HEADER_SIZE + (length.unwrap_or(0) & VARLEN_LENGTH_MASK) as usize
+            }
+        };
+        if cursor + size > data.len() {
+            return Err(RookError::MalformedTuple(format!(
+                "Column '{}': tuple too short for a {} value",
+                column.name, column.data_type
+            )));
+        }
+        spans.push(AttributeSpan { column, range: Some(cursor..cursor + size) });
+        cursor += size;
+    }
+
+    Ok(TupleLayout { version, values_start, attributes: spans })
+}
+
+/// Decodes a tuple into values of the table's *current* columns.
+///
+/// Tuples written under an older schema version are decoded with that version's layout
+/// and mapped to the current columns by column id: dropped columns are skipped and
+/// columns added later report their default (or NULL).
+/// Tuples with out-of-line values must be detoasted first (see `toast::detoast_tuple`).
+pub fn decode_tuple(table: &Table, data: &[u8]) -> error::Result<Vec<Value>> {
+    let layout = tuple_layout(table, data)?;
+
+    let mut stored = Vec::with_capacity(layout.attributes.len());
+    for span in &layout.attributes {
+        let Some(range) = span.range.clone() else {
+            stored.push(Value::Null);
+            continue;
+        };
+        let value = decode_value(span.column.data_type, &data[range], &mut 0).map_err(|e| match e {
+            RookError::MalformedTuple(message) => {
+                RookError::MalformedTuple(format!("Column '{}': {}", span.column.name, message))
+            }
+            other => other,
+        })?;
+        stored.push(value);
+    }
+
+    // Fast path: tuple already has the current layout
+    if layout.version == table.schema_version {
+        return Ok(stored);
+    }
+
+    table
+        .columns
+        .iter()
+        .map(|column| {
+            match layout.attributes.iter().position(|old| old.column.id == column.id) {
+                Some(index) => Ok(stored[index].clone()),
+                None => missing_value(column),
+            }
+        })
+        .collect()
+}
+
+/// Value of a column for rows written before the column was added: its default, or NULL.
+/// Defaults are validated when the column is defined, so one that does not parse means a bad catalog.
+fn missing_value(column: &Column) -> error::Result<Value> {
+    match column.default.as_deref() {
+        Some(raw) => parse_value(column.data_type, raw).map_err(|e| {
+            RookError::MalformedTuple(format!("Column '{}': invalid default '{}': {}", column.name, raw, e))
+        }),
+        None => Ok(Value::Null),
+    }
+}
@@ -0,0 +1,442 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+use crate::catalog::{DataType, DECIMAL_MAX_PRECISION};
+use crate::error::{self, RookError};
+use crate::datetime::{
+    DateDisplay, Interval, TimeDisplay, TimestampDisplay, parse_date, parse_time, parse_timestamp,
+};
+use crate::json::{decode_json, encode_json, parse_json};
+
+pub const VARLEN_HEADER_SIZE: usize = 4; // u32 byte length in front of variable-length values
+pub const VARLEN_EXTERNAL: u32 = 1 << 31; // Header flag: body is a TOAST pointer, not the value
+pub const VARLEN_COMPRESSED: u32 = 1 << 30; // Header flag: body (or TOAST value) is LZ4 compressed
+pub const VARLEN_LENGTH_MASK: u32 = !(VARLEN_EXTERNAL | VARLEN_COMPRESSED); // Bits holding the body length
+
+/// A single decoded attribute value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null, // Missing value of any type, recorded in the tuple's null bitmap
+    SmallInt(i16),
+    Int(i32),
+    BigInt(i64),
+    Float(f32),
+    Double(f64),
+    Boolean(bool),
+    Decimal(Decimal),
+    Date(i32),
+    Time(i64),
+    Timestamp(i64),
+    TimestampTz(i64),
+    Interval(Interval),
+    Text(String),
+    Bytes(Vec<u8>),
+    Json(serde_json::Value),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "NULL"),
+            Value::SmallInt(v) => write!(f, "{}", v),
+            Value::Int(v) => write!(f, "{}", v),
+            Value::BigInt(v) => write!(f, "{}", v),
+            Value::Float(v) => write_float(f, *v),
+            Value::Double(v) => write_float(f, *v),
+            Value::Boolean(v) => write!(f, "{}", v),
+            Value::Decimal(v) => write!(f, "{}", v),
+            Value::Date(v) => write!(f, "{}", DateDisplay(*v)),
+            Value::Time(v) => write!(f, "{}", TimeDisplay(*v)),
+            Value::Timestamp(v) => write!(
+                f,
+                "{}",
+                TimestampDisplay { micros: *v, with_time_zone: false }
+            ),
+            Value::TimestampTz(v) => write!(
+                f,
+                "{}",
+                TimestampDisplay { micros: *v, with_time_zone: true }
+            ),
+            Value::Interval(v) => write!(f, "{}", v),
+            Value::Text(v) => write!(f, "'{}'", v),
+            Value::Bytes(v) => {
+                write!(f, "\\x")?;
+                v.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+            Value::Json(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    /// Orders two values of the same type, the way indexes and sorts compare keys.
+    /// Values of different types, NULL and JSON documents are not comparable.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Value::SmallInt(a), Value::SmallInt(b)) => a.partial_cmp(b),
+            (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
+            (Value::BigInt(a), Value::BigInt(b)) => a.partial_cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+            (Value::Double(a), Value::Double(b)) => a.partial_cmp(b),
+            (Value::Boolean(a), Value::Boolean(b)) => a.partial_cmp(b),
+            (Value::Decimal(a), Value::Decimal(b)) => a.partial_cmp(b),
+            (Value::Date(a), Value::Date(b)) => a.partial_cmp(b),
+            (Value::Time(a), Value::Time(b)) => a.partial_cmp(b),
+            (Value::Timestamp(a), Value::Timestamp(b)) => a.partial_cmp(b),
+            (Value::TimestampTz(a), Value::TimestampTz(b)) => a.partial_cmp(b),
+            (Value::Interval(a), Value::Interval(b)) => a.partial_cmp(b),
+            (Value::Text(a), Value::Text(b)) => a.partial_cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+/// Shortest round-trip form, switching to exponent notation for very large/small magnitudes.
+fn write_float<T>(f: &mut fmt::Formatter<'_>, v: T) -> fmt::Result
+where
+    T: fmt::Display + fmt::LowerExp + Into<f64> + Copy,
+{
+    let magnitude = v.into().abs();
+    if magnitude.is_finite() && magnitude != 0.0 && !(1e-4..1e16).contains(&magnitude) {
+        write!(f, "{:e}", v)
+    } else {
+        write!(f, "{}", v)
+    }
+}
+
+/// Exact decimal number: `unscaled / 10^scale`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decimal {
+    pub unscaled: i128,
+    pub scale: u8,
+}
+
+impl Decimal {
+    /// Parses a plain decimal literal ("-12.345") for a DECIMAL(precision, scale) column.
+    /// Extra fractional digits are rounded half away from zero; values needing more than
+    /// `precision` digits are rejected.
+    pub fn parse(raw: &str, precision: u8, scale: u8) -> Result<Self, String> {
+        let text = raw.trim();
+        let invalid = || format!("'{}' is not a valid decimal", text);
+
+        let (negative, digits) = match text.as_bytes().first() {
+            Some(b'-') => (true, &text[1..]),
+            Some(b'+') => (false, &text[1..]),
+            _ => (false, text),
+        };
+
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        if int_part.is_empty() && frac_part.is_empty() {
+            return Err(invalid());
+        }
+        if !int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let out_of_range = || format!("'{}' does not fit DECIMAL({},{})", text, precision, scale);
+
+        // Integer digits followed by exactly `scale` fractional digits
+        let mut unscaled: i128 = 0;
+        let frac_digits = frac_part.bytes().chain(std::iter::repeat(b'0'));
+        for digit in int_part.bytes().chain(frac_digits.take(scale as usize)) {
+            unscaled = unscaled
+                .checked_mul(10)
+                .and_then(|v| v.checked_add((digit - b'0') as i128))
+                .ok_or_else(out_of_range)?;
+        }
+
+        // Round on the first dropped fractional digit
+        if frac_part.len() > scale as usize && frac_part.as_bytes()[scale as usize] >= b'5' {
+            unscaled = unscaled.checked_add(1).ok_or_else(out_of_range)?;
+        }
+
+        if unscaled >= pow10(precision) {
+            return Err(out_of_range());
+        }
+
+        Ok(Decimal {
+            unscaled: if negative { -unscaled } else { unscaled },
+            scale,
+        })
+    }
+}
+
+impl Decimal {
+    /// The same number at another scale (at most `DECIMAL_MAX_PRECISION`). Dropped digits are
+    /// rounded half away from zero; `None` if the value no longer fits an i128.
+    pub fn rescale(&self, scale: u8) -> Option<Decimal> {
+        let unscaled = if scale >= self.scale {
+            self.unscaled.checked_mul(pow10(scale - self.scale))?
+        } else {
+            let divisor = pow10(self.scale - scale);
+            let quotient = self.unscaled / divisor;
+            if (self.unscaled % divisor).unsigned_abs() * 2 >= divisor as u128 {
+                quotient + self.unscaled.signum()
+            } else {
+                quotient
+            }
+        };
+        Some(Decimal { unscaled, scale })
+    }
+
+    /// Numeric order, ignoring the scale: 1.5 and 1.50 are equal.
+    pub fn numeric_cmp(&self, other: &Decimal) -> Ordering {
+        let rescale = |d: &Decimal, scale: u8| {
+            d.unscaled
+                .checked_mul(pow10(scale - d.scale))
+                .ok_or(d.unscaled.signum())
+        };
+
+        let scale = self.scale.max(other.scale);
+        match (rescale(self, scale), rescale(other, scale)) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            // An overflowing side is larger in magnitude than anything representable
+            (Err(sign), _) => sign.cmp(&0),
+            (_, Err(sign)) => 0.cmp(&sign),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    /// Numeric order; values with different scales are compared at the larger scale.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.numeric_cmp(other).then(self.scale.cmp(&other.scale))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.unscaled < 0 { "-" } else { "" };
+        let magnitude = self.unscaled.unsigned_abs();
+
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, magnitude);
+        }
+
+        let divisor = pow10(self.scale) as u128;
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            sign,
+            magnitude / divisor,
+            magnitude % divisor,
+            width = self.scale as usize
+        )
+    }
+}
+
+/// 10^exp for exp <= DECIMAL_MAX_PRECISION.
+fn pow10(exp: u8) -> i128 {
+    debug_assert!(exp <= DECIMAL_MAX_PRECISION);
+    10i128.pow(exp as u32)
+}
+
+/// Parses a textual value (CSV field, column default) into a value of the given type.
+pub fn parse_value(data_type: DataType, raw: &str) -> Result<Value, String> {
+    let text = raw.trim();
+    let invalid = || format!("'{}' is not a valid {}", text, data_type);
+
+    match data_type {
+        DataType::SmallInt => text.parse().map(Value::SmallInt).map_err(|_| invalid()),
+        DataType::Int => text.parse().map(Value::Int).map_err(|_| invalid()),
+        DataType::BigInt => text.parse().map(Value::BigInt).map_err(|_| invalid()),
+        DataType::Float => text.parse().map(Value::Float).map_err(|_| invalid()),
+        DataType::Double => text.parse().map(Value::Double).map_err(|_| invalid()),
+        DataType::Boolean => match text.to_ascii_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "on" | "1" => Ok(Value::Boolean(true)),
+            "false" | "f" | "no" | "n" | "off" | "0" => Ok(Value::Boolean(false)),
+            _ => Err(invalid()),
+        },
+        DataType::Decimal { precision, scale } => {
+            Decimal::parse(text, precision, scale).map(Value::Decimal)
+        }
+        DataType::Date => parse_date(text).map(Value::Date),
+        DataType::Time => parse_time(text).map(Value::Time),
+        DataType::Timestamp => parse_timestamp(text, false).map(Value::Timestamp),
+        DataType::TimestampTz => parse_timestamp(text, true).map(Value::TimestampTz),
+        DataType::Interval => Interval::parse(text).map(Value::Interval),
+        DataType::Text => Ok(Value::Text(raw.to_string())),
+        DataType::Varchar(limit) => {
+            let length = raw.chars().count();
+            if length > limit as usize {
+                return Err(format!(
+                    "value of {} characters is too long for VARCHAR({})",
+                    length, limit
+                ));
+            }
+            Ok(Value::Text(raw.to_string()))
+        }
+        DataType::Char(limit) => {
+            // Trailing blanks beyond the limit are insignificant, anything else is an error
+            let trimmed = raw.trim_end_matches(' ');
+            let length = trimmed.chars().count();
+            if length > limit as usize {
+                return Err(format!(
+                    "value of {} characters is too long for CHAR({})",
+                    length, limit
+                ));
+            }
+            let padding = " ".repeat(limit as usize - length);
+            Ok(Value::Text(format!("{}{}", trimmed, padding)))
+        }
+        DataType::Bytea => parse_bytes(text).map(Value::Bytes),
+        DataType::Json => parse_json(text).map(Value::Json),
+    }
+}
+
+/// Parses BYTEA input: hex with a `\x` prefix ("\xdeadbeef"), otherwise standard base64.
+fn parse_bytes(text: &str) -> Result<Vec<u8>, String> {
+    let Some(hex) = text.strip_prefix("\\x").or_else(|| text.strip_prefix("\\X")) else {
+        return BASE64
+            .decode(text)
+            .map_err(|_| format!("'{}' is not valid base64 or \\x-prefixed hex", text));
+    };
+
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("'{}' is not valid hex", text));
+    }
+    if hex.len() % 2 != 0 {
+        return Err(format!("'{}' has an odd number of hex digits", text));
+    }
+    Ok((0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+/// Number of bytes a value of the given type occupies inside a tuple,
+/// or None for variable-length types (stored as a u32 length followed by the bytes).
+pub fn fixed_size(data_type: DataType) -> Option<usize> {
+    let size = match data_type {
+        DataType::SmallInt => 2,
+        DataType::Int => 4,
+        DataType::BigInt => 8,
+        DataType::Float => 4,
+        DataType::Double => 8,
+        DataType::Boolean => 1,
+        DataType::Decimal { .. } => 16,
+        DataType::Date => 4,
+        DataType::Time | DataType::Timestamp | DataType::TimestampTz => 8,
+        DataType::Interval => 16,
+        DataType::Text
+        | DataType::Varchar(_)
+        | DataType::Char(_)
+        | DataType::Bytea
+        | DataType::Json => {
+            return None;
+        }
+    };
+    Some(size)
+}
+
+/// Appends the on-disk representation of a value (little-endian for numbers).
+pub fn encode_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::SmallInt(v) => out.extend_from_slice(&v.to_le_bytes()),
+        Value::Int(v) => out.extend_from_slice(&v.to_le_bytes()),
+        Value::BigInt(v) => out.extend_from_slice(&v.to_le_bytes()),
+        Value::Float(v) => out.extend_from_slice(&v.to_le_bytes()),
+        Value::Double(v) => out.extend_from_slice(&v.to_le_bytes()),
+        Value::Boolean(v) => out.push(*v as u8),
+        Value::Decimal(v) => out.extend_from_slice(&v.unscaled.to_le_bytes()),
+        Value::Date(v) => out.extend_from_slice(&v.to_le_bytes()),
+        Value::Time(v) | Value::Timestamp(v) | Value::TimestampTz(v) => {
+            out.extend_from_slice(&v.to_le_bytes())
+        }
+        Value::Interval(v) => {
+            out.extend_from_slice(&v.months.to_le_bytes());
+            out.extend_from_slice(&v.days.to_le_bytes());
+            out.extend_from_slice(&v.micros.to_le_bytes());
+        }
+        Value::Text(v) => {
+            out.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            out.extend_from_slice(v.as_bytes());
+        }
+        Value::Bytes(v) => {
+            out.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            out.extend_from_slice(v);
+        }
+        Value::Json(v) => {
+            let mut body = Vec::new();
+            encode_json(v, &mut body);
+            out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+            out.extend_from_slice(&body);
+        }
+        Value::Null => {} // Takes no space; the null bitmap marks it
+    }
+}
+
+/// Reads one value of the given type at `cursor`, advancing it.
+pub fn decode_value(data_type: DataType, data: &[u8], cursor: &mut usize) -> error::Result<Value> {
+    let too_short = || RookError::MalformedTuple(format!("Tuple too short for a {} value", data_type));
+
+    let size = match fixed_size(data_type) {
+        Some(size) => size,
+        None => {
+            let header = data
+                .get(*cursor..*cursor + VARLEN_HEADER_SIZE)
+                .ok_or_else(too_short)?;
+            let header = u32::from_le_bytes(header.try_into().unwrap());
+            if header & (VARLEN_EXTERNAL | VARLEN_COMPRESSED) != 0 {
+                return Err(RookError::MalformedTuple(
+                    "Value is compressed or stored out of line; detoast the tuple first".to_string(),
+                ));
+            }
+            *cursor += VARLEN_HEADER_SIZE;
+            (header & VARLEN_LENGTH_MASK) as usize
+        }
+    };
+
+    let bytes = data.get(*cursor..*cursor + size).ok_or_else(too_short)?;
+    *cursor += size;
+
+    let value = match data_type {
+        DataType::SmallInt => Value::SmallInt(i16::from_le_bytes(bytes.try_into().unwrap())),
+        DataType::Int => Value::Int(i32::from_le_bytes(bytes.try_into().unwrap())),
+        DataType::BigInt => Value::BigInt(i64::from_le_bytes(bytes.try_into().unwrap())),
+        DataType::Float => Value::Float(f32::from_le_bytes(bytes.try_into().unwrap())),
+        DataType::Double => Value::Double(f64::from_le_bytes(bytes.try_into().unwrap())),
+        DataType::Boolean => Value::Boolean(bytes[0] != 0),
+        DataType::Decimal { scale, .. } => Value::Decimal(Decimal {
+            unscaled: i128::from_le_bytes(bytes.try_into().unwrap()),
+            scale,
+        }),
+        DataType::Date => Value::Date(i32::from_le_bytes(bytes.try_into().unwrap())),
+        DataType::Time => Value::Time(i64::from_le_bytes(bytes.try_into().unwrap())),
+        DataType::Timestamp => Value::Timestamp(i64::from_le_bytes(bytes.try_into().unwrap())),
+        DataType::TimestampTz => {
+            Value::TimestampTz(i64::from_le_bytes(bytes.try_into().unwrap()))
+        }
+        DataType::Interval => Value::Interval(Interval {
+            months: i32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+            days: i32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            micros: i64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+        }),
+        DataType::Text | DataType::Varchar(_) | DataType::Char(_) => {
+            let text = String::from_utf8(bytes.to_vec())
+                .map_err(|_| RookError::MalformedTuple("Text value is not valid UTF-8".to_string()))?;
+            Value::Text(text)
+        }
+        DataType::Bytea => Value::Bytes(bytes.to_vec()),
+        DataType::Json => {
+            let mut json_cursor = 0;
+            let json = decode_json(bytes, &mut json_cursor)
+                .map_err(|e| RookError::MalformedTuple(format!("Invalid JSON value: {}", e)))?;
+            if json_cursor != bytes.len() {
+                return Err(RookError::MalformedTuple("JSON value has trailing bytes".to_string()));
+            }
+            Value::Json(json)
+        }
+    };
+    Ok(value)
+}
@@ -0,0 +1,123 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    alter_table_add_column, alter_table_drop_column, alter_table_rename, alter_table_rename_column,
+    create_database, create_table, current_generation, drop_database, bootstrap_catalog, load_catalog,
+    Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::page::{load_csv_and_insert, scan_tuples};
+use storage_manager::error::RookError;
+use storage_manager::tuple::MAX_SCHEMA_VERSION;
+use storage_manager::value::Value;
+
+#[test]
+fn test_alter_table() {
+    let db_name = "test_alter_table_db";
+    let table_name = "users";
+    let csv_path = "test_alter_table.csv";
+
+    // Step 1: Create a table and insert rows with the original layout (version 0)
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_alter_table"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        table_name,
+        vec![
+            Column::new("id", DataType::Int),
+            Column::new("name", DataType::Text),
+            Column::new("city", DataType::Text),
+        ],
+    ).unwrap();
+
+    let table_path = catalog.table_file(db_name, table_name).unwrap();
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&table_path)
+        .expect("Failed to open table file");
+
+    fs::write(csv_path, "id,name,city\n1,alice,paris\n2,bob,rome\n").unwrap();
+    load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &CsvOptions::default()).unwrap();
+
+    // Step 2: Add a column with a default (version 1) and insert a row using it
+    let mut age = Column::new("age", DataType::Int);
+    age.default = Some("18".to_string());
+    alter_table_add_column(&mut catalog, db_name, table_name, age).unwrap();
+    assert!(alter_table_add_column(
+        &mut catalog,
+        db_name,
+        table_name,
+        Column::new("age", DataType::Int)
+    ).is_err());
+
+    fs::write(csv_path, "id,name,city,age\n3,carol,oslo,42\n").unwrap();
+    load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &CsvOptions::default()).unwrap();
+
+    // Step 3: Drop a column (version 2) and rename another one
+    alter_table_drop_column(&mut catalog, db_name, table_name, "city").unwrap();
+    alter_table_rename_column(&mut catalog, db_name, table_name, "name", "username").unwrap();
+    assert!(alter_table_drop_column(&mut catalog, db_name, table_name, "city").is_err());
+
+    fs::write(csv_path, "id,username,age\n4,dave,30\n").unwrap();
+    load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &CsvOptions::default()).unwrap();
+
+    // Step 4: Schema versions are persisted in the catalog
+    let reloaded = load_catalog(&config).unwrap();
+    let table = &reloaded.databases[db_name].tables[table_name];
+    assert_eq!(table.schema_version, 2);
+    assert_eq!(table.schema_history.len(), 2);
+    let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["id", "username", "age"]);
+
+    // Step 5: Every tuple is decoded into the current layout
+    let rows = scan_tuples(&reloaded, db_name, table_name, &mut file).expect("Failed to scan table");
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::Int(1), Value::Text("alice".to_string()), Value::Int(18)],
+            vec![Value::Int(2), Value::Text("bob".to_string()), Value::Int(18)],
+            vec![Value::Int(3), Value::Text("carol".to_string()), Value::Int(42)],
+            vec![Value::Int(4), Value::Text("dave".to_string()), Value::Int(30)],
+        ]
+    );
+
+    // Step 6: An ALTER whose catalog save fails leaves the in-memory catalog as it was
+    let next_generation = config.generation_dir(current_generation(&config).unwrap() + 1);
+    fs::write(&next_generation, "not a directory").unwrap();
+    assert!(alter_table_add_column(&mut catalog, db_name, table_name, Column::new("email", DataType::Text)).is_err());
+    assert!(alter_table_drop_column(&mut catalog, db_name, table_name, "age").is_err());
+    assert!(alter_table_rename_column(&mut catalog, db_name, table_name, "username", "login").is_err());
+    assert!(alter_table_rename(&mut catalog, &mut buffer_manager, db_name, table_name, "people").is_err());
+    let table = &catalog.databases[db_name].tables[table_name];
+    assert_eq!(table.schema_version, 2);
+    let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["id", "username", "age"]);
+    assert!(!catalog.databases[db_name].tables.contains_key("people"));
+    fs::remove_file(&next_generation).unwrap();
+
+    // Step 7: Tuples store the schema version in 16 bits, so an ALTER past the last one is refused
+    catalog.databases.get_mut(db_name).unwrap().tables.get_mut(table_name).unwrap().schema_version = MAX_SCHEMA_VERSION;
+    let err = alter_table_drop_column(&mut catalog, db_name, table_name, "age").unwrap_err();
+    assert!(matches!(err, RookError::InvalidDefinition(_)), "{}", err);
+    catalog.databases.get_mut(db_name).unwrap().tables.get_mut(table_name).unwrap().schema_version = 2;
+
+    // Step 8: A default that does not parse is an error when old rows are read, not a wrong value
+    let table = catalog.databases.get_mut(db_name).unwrap().tables.get_mut(table_name).unwrap();
+    table.columns[2].default = Some("eighteen".to_string());
+    assert!(scan_tuples(&catalog, db_name, table_name, &mut file).is_err());
+
+    // Step 9: Clean up
+    let _ = fs::remove_file(csv_path);
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    alter_table_rename, create_database, create_table, drop_database, bootstrap_catalog, load_catalog,
+    Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+
+#[test]
+fn test_alter_table_rename() {
+    let db_name = "test_alter_rename_db";
+
+    // Step 1: Create two tables, one of them loaded in the buffer
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_alter_table_rename"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(&mut catalog, db_name, "old_name", vec![Column::new("id", DataType::Int)]).unwrap();
+    create_table(&mut catalog, db_name, "taken", vec![Column::new("id", DataType::Int)]).unwrap();
+    buffer_manager.load_table_on_create(&catalog, db_name, "old_name").unwrap();
+
+    let table_path = catalog.table_file(db_name, "old_name").unwrap();
+
+    // Step 2: Renaming onto an existing table fails
+    assert!(alter_table_rename(&mut catalog, &mut buffer_manager, db_name, "old_name", "taken").is_err());
+
+    // Step 3: Rename
+    alter_table_rename(&mut catalog, &mut buffer_manager, db_name, "old_name", "new_name").unwrap();
+
+    let reloaded = load_catalog(&config).unwrap();
+    assert!(reloaded.databases[db_name].tables.contains_key("new_name"));
+    assert!(!reloaded.databases[db_name].tables.contains_key("old_name"));
+    assert!(catalog.table_file(db_name, "old_name").is_err());
+    assert_eq!(catalog.table_file(db_name, "new_name").unwrap(), table_path, "The file is named by OID");
+    assert!(Path::new(&table_path).exists());
+    assert!(buffer_manager.loaded_table.is_none(), "Renamed table was not evicted");
+
+    // Step 4: Clean up
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+}
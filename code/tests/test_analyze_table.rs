@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::Path;
+
+use storage_manager::catalog::{
+    alter_table_rename_column, analyze_table, bootstrap_catalog, create_database, create_table,
+    load_catalog, Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::page::load_csv_and_insert;
+use storage_manager::stats::{HyperLogLog, STATS_MAX_VALUE_WIDTH, STATS_SAMPLE_SIZE, STATS_TARGET};
+
+#[test]
+fn test_analyze_table() {
+    let db_name = "test_analyze_db";
+    let table_name = "visits";
+    let csv_path = "test_analyze_table.csv";
+
+    // Step 1: HyperLogLog estimates distinct counts within a few percent
+    let mut hll = HyperLogLog::new();
+    for i in 0..20_000u32 {
+        hll.insert(&(i % 10_000).to_le_bytes());
+    }
+    let estimate = hll.estimate() as f64;
+    assert!((estimate - 10_000.0).abs() < 500.0, "estimated {} distinct values", estimate);
+
+    // Step 2: Load 100 rows: unique ids, a skewed city and a half-NULL note
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_analyze_table"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        table_name,
+        vec![
+            Column::new("id", DataType::Int),
+            Column::new("city", DataType::Text),
+            Column::new("note", DataType::Text),
+        ],
+    ).unwrap();
+
+    let mut csv = String::from("id,city,note\n");
+    for id in 1..=100 {
+        let city = match id % 4 {
+            0 | 1 => "austin",
+            2 => "boston",
+            _ => "chicago",
+        };
+        let note = if id % 2 == 0 { "seen" } else { "" };
+        csv.push_str(&format!("{},{},{}\n", id, city, note));
+    }
+    fs::write(csv_path, csv).unwrap();
+    let mut file = fs::File::options()
+        .read(true)
+        .write(true)
+        .open(catalog.table_file(db_name, table_name).unwrap())
+        .unwrap();
+    load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &CsvOptions::default()).unwrap();
+
+    // Step 3: ANALYZE stores table and column statistics
+    assert!(catalog.databases[db_name].tables[table_name].stats.is_none());
+    analyze_table(&mut catalog, db_name, table_name).unwrap();
+    let table = &catalog.databases[db_name].tables[table_name];
+    let stats = table.stats.as_ref().unwrap();
+    assert_eq!(stats.row_count, 100);
+    assert!(stats.page_count >= 2);
+    assert!(stats.avg_width > 0);
+
+    let id = stats.column(table.columns[0].id).unwrap();
+    assert_eq!(id.null_frac, 0.0);
+    assert!(id.n_distinct.abs_diff(100) <= 3, "estimated {} distinct ids", id.n_distinct);
+    assert_eq!((id.min.as_deref(), id.max.as_deref()), (Some("1"), Some("100")));
+    assert!(id.most_common.is_empty(), "unique values are not common");
+    assert_eq!(id.histogram.len(), STATS_TARGET + 1);
+    assert_eq!((id.histogram[0].as_str(), id.histogram[STATS_TARGET].as_str()), ("1", "100"));
+
+    let city = stats.column(table.columns[1].id).unwrap().clone();
+    assert_eq!(city.n_distinct, 3);
+    assert_eq!(city.most_common[0], ("austin".to_string(), 0.5));
+    assert_eq!(city.most_common.len(), 3);
+    assert_eq!((city.min.as_deref(), city.max.as_deref()), (Some("austin"), Some("chicago")));
+
+    let note = stats.column(table.columns[2].id).unwrap();
+    assert_eq!(note.null_frac, 0.5);
+    assert_eq!(note.n_distinct, 1);
+
+    // Step 4: Statistics persist and follow a column rename
+    let saved = stats.clone();
+    alter_table_rename_column(&mut catalog, db_name, table_name, "city", "town").unwrap();
+    let reloaded = load_catalog(&config).unwrap();
+    let table = &reloaded.databases[db_name].tables[table_name];
+    assert_eq!(table.stats.as_ref(), Some(&saved));
+    assert_eq!(table.stats.as_ref().unwrap().column(table.columns[1].id), Some(&city));
+
+    // Step 5: More rows than the sample: min/max cover every row, a leading NaN and
+    // wide values do not hide them, and only the stored text is cut
+    create_table(
+        &mut catalog,
+        db_name,
+        "readings",
+        vec![Column::new("score", DataType::Double), Column::new("body", DataType::Text)],
+    ).unwrap();
+    let row_total = STATS_SAMPLE_SIZE + 2000;
+    let mut csv = String::from("score,body\n");
+    for i in 1..=row_total {
+        let score = if i == 1 { "NaN".to_string() } else { i.to_string() };
+        let letter = (b'a' + (i % 26) as u8) as char;
+        csv.push_str(&format!("{},{}\n", score, letter.to_string().repeat(100)));
+    }
+    fs::write(csv_path, csv).unwrap();
+    let mut file = fs::File::options()
+        .read(true)
+        .write(true)
+        .open(catalog.table_file(db_name, "readings").unwrap())
+        .unwrap();
+    load_csv_and_insert(&catalog, db_name, "readings", &mut file, csv_path, &CsvOptions::default()).unwrap();
+    analyze_table(&mut catalog, db_name, "readings").unwrap();
+    let table = &catalog.databases[db_name].tables["readings"];
+    let stats = table.stats.as_ref().unwrap();
+    assert_eq!(stats.row_count, row_total as u64);
+
+    let score = stats.column(table.columns[0].id).unwrap();
+    let parse = |text: &Option<String>| text.as_deref().unwrap().parse::<f64>().unwrap();
+    assert_eq!((parse(&score.min), parse(&score.max)), (2.0, row_total as f64));
+    assert_eq!(score.histogram.len(), STATS_TARGET + 1);
+    assert!(score.histogram.iter().all(|bound| (2.0..=row_total as f64).contains(&bound.parse::<f64>().unwrap())));
+
+    let body = stats.column(table.columns[1].id).unwrap();
+    assert_eq!(body.min, Some("a".repeat(STATS_MAX_VALUE_WIDTH)));
+    assert_eq!(body.max, Some("z".repeat(STATS_MAX_VALUE_WIDTH)));
+    assert_eq!(body.histogram.len(), STATS_TARGET + 1);
+    assert!(body.most_common.is_empty(), "wide values are not kept as common values");
+    assert!(body.n_distinct.abs_diff(26) <= 1, "estimated {} distinct bodies", body.n_distinct);
+
+    // Step 6: Unknown tables cannot be analyzed
+    assert!(analyze_table(&mut catalog, db_name, "missing").is_err());
+
+    // Step 7: Clean up
+    let _ = fs::remove_file(csv_path);
+    fs::remove_dir_all(&config.root).unwrap();
+}
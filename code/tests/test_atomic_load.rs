@@ -0,0 +1,119 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::bulk_load::load_csv_parallel;
+use storage_manager::catalog::{
+    bootstrap_catalog, create_database, create_table, load_catalog, Catalog, Column, DataType,
+    StorageStrategy,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::{CsvOptions, LoadMode};
+use storage_manager::page::{page_count, scan_tuples, PAGE_SIZE};
+use storage_manager::toast::{toast_tuple, ToastFile};
+use storage_manager::tuple::encode_tuple;
+use storage_manager::value::Value;
+
+const DB_NAME: &str = "shop";
+const TABLE_NAME: &str = "items";
+
+/// Data pages of a file; TOAST headers also hold a value id counter that failed loads still advance.
+fn data_pages(path: &Path) -> Vec<u8> {
+    fs::read(path).unwrap().split_off(PAGE_SIZE)
+}
+
+fn ids(catalog: &Catalog) -> Vec<Value> {
+    let mut file = File::open(catalog.table_file(DB_NAME, TABLE_NAME).unwrap()).unwrap();
+    scan_tuples(catalog, DB_NAME, TABLE_NAME, &mut file).unwrap().into_iter().map(|row| row[0].clone()).collect()
+}
+
+#[test]
+fn test_atomic_load() {
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_atomic_load"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    create_database(&mut catalog, DB_NAME).unwrap();
+    create_table(&mut catalog, DB_NAME, TABLE_NAME, vec![Column::new("id", DataType::Int)]).unwrap();
+
+    let table_path = catalog.table_file(DB_NAME, TABLE_NAME).unwrap();
+    let csv_path = config.root.join("items.csv");
+    let csv_path = csv_path.to_str().unwrap();
+    let mut buffer_manager = BufferManager::new();
+
+    // Step 1: A load publishes only the pages it used
+    fs::write(csv_path, "id\n1\n2\n3\n").unwrap();
+    buffer_manager.load_csv_to_buffer(&catalog, DB_NAME, TABLE_NAME, csv_path, &CsvOptions::default()).unwrap();
+    assert_eq!(ids(&catalog), vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+    let committed = fs::read(&table_path).unwrap();
+    let pages = page_count(&mut File::open(&table_path).unwrap()).unwrap();
+    assert_eq!(committed.len(), pages as usize * PAGE_SIZE);
+
+    // Step 2: A load that aborts leaves the table file untouched
+    fs::write(csv_path, "id\n4\n5\nsix\n").unwrap();
+    let strict = CsvOptions { mode: LoadMode::Strict, ..CsvOptions::default() };
+    assert!(buffer_manager.load_csv_to_buffer(&catalog, DB_NAME, TABLE_NAME, csv_path, &strict).is_err());
+    assert_eq!(fs::read(&table_path).unwrap(), committed);
+
+    // Step 3: Pages written by a load that crashed before its header update are invisible...
+    let mut file = OpenOptions::new().write(true).open(&table_path).unwrap();
+    file.seek(SeekFrom::End(0)).unwrap();
+    file.write_all(&committed[PAGE_SIZE..]).unwrap(); // Copies of the data pages, rows 1-3 again
+    assert_eq!(ids(&catalog), vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+
+    // ...and removed when the data directory is opened again
+    bootstrap_catalog(&config).unwrap();
+    assert_eq!(fs::read(&table_path).unwrap(), committed);
+
+    // Step 4: The next load appends new pages; the committed data pages are not rewritten
+    fs::write(csv_path, "id\n4\n5\n").unwrap();
+    buffer_manager.load_csv_to_buffer(&catalog, DB_NAME, TABLE_NAME, csv_path, &CsvOptions::default()).unwrap();
+    assert_eq!(ids(&catalog), (1..=5).map(Value::Int).collect::<Vec<_>>());
+    let loaded = fs::read(&table_path).unwrap();
+    assert_eq!(loaded[PAGE_SIZE..committed.len()], committed[PAGE_SIZE..]);
+    assert_eq!(page_count(&mut File::open(&table_path).unwrap()).unwrap(), pages + 1);
+
+    // Step 5: TOAST chunks of a load are only published with its rows
+    let mut body = Column::new("body", DataType::Text);
+    body.storage = StorageStrategy::External;
+    create_table(&mut catalog, DB_NAME, "notes", vec![Column::new("id", DataType::Int), body]).unwrap();
+    let toast_path = catalog.toast_file(DB_NAME, "notes").unwrap();
+    let long = "x".repeat(5000);
+    fs::write(csv_path, format!("id,body
+1,{}
+", long)).unwrap();
+    buffer_manager.load_csv_to_buffer(&catalog, DB_NAME, "notes", csv_path, &CsvOptions::default()).unwrap();
+    let committed_toast = data_pages(&toast_path);
+
+    fs::write(csv_path, format!("id,body
+2,{}
+three,{}
+", long, long)).unwrap();
+    assert!(buffer_manager.load_csv_to_buffer(&catalog, DB_NAME, "notes", csv_path, &strict).is_err());
+    assert_eq!(data_pages(&toast_path), committed_toast);
+    assert!(load_csv_parallel(&catalog, DB_NAME, "notes", csv_path, &strict, 2).is_err());
+    assert_eq!(data_pages(&toast_path), committed_toast);
+
+    // Chunks staged by a load that crashed are cut when the data directory is opened again
+    let table = &catalog.databases[DB_NAME].tables["notes"];
+    let mut toast = ToastFile::staged(toast_path.clone());
+    let row = vec![Value::Int(2), Value::Text(long.clone())];
+    toast_tuple(table, encode_tuple(table, &row), &mut toast).unwrap();
+    drop(toast);
+    assert!(data_pages(&toast_path).len() > committed_toast.len());
+    bootstrap_catalog(&config).unwrap();
+    assert_eq!(data_pages(&toast_path), committed_toast);
+
+    fs::write(csv_path, format!("id,body
+2,{}
+", long)).unwrap();
+    load_csv_parallel(&catalog, DB_NAME, "notes", csv_path, &CsvOptions::default(), 2).unwrap();
+    let mut file = File::open(catalog.table_file(DB_NAME, "notes").unwrap()).unwrap();
+    let rows = scan_tuples(&catalog, DB_NAME, "notes", &mut file).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|row| row[1] == Value::Text(long.clone())));
+
+    // Step 6: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
@@ -0,0 +1,76 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::catalog::{
+    bootstrap_catalog, current_generation, load_catalog, Constraint, DataType, FIRST_OID,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::system_catalog::{system_table_path, SYSTEM_TABLES};
+use storage_manager::table::init_table;
+
+#[test]
+fn test_bootstrap_catalog() {
+    // Step 1: Start without any catalog
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_bootstrap_catalog"));
+    let _ = fs::remove_dir_all(&config.root);
+
+    // Step 2: Bootstrap creates the control file and every system table
+    bootstrap_catalog(&config).unwrap();
+    assert!(config.control_file().exists(), "control file was not created");
+    let dir = config.generation_dir(current_generation(&config).unwrap());
+    for name in SYSTEM_TABLES {
+        assert!(system_table_path(&dir, name).exists(), "{} was not created", name);
+    }
+    assert!(load_catalog(&config).unwrap().databases.is_empty());
+
+    // Step 3: An existing catalog.json is migrated once, schema history and constraints included
+    fs::remove_dir_all(&config.root).unwrap();
+    fs::create_dir_all(config.catalog_dir()).unwrap();
+    fs::write(
+        config.catalog_file(),
+        r#"{ "databases": { "shop": { "tables": { "items": {
+            "columns": [
+                { "name": "id", "data_type": "INT", "id": 1, "not_null": true },
+                { "name": "label", "data_type": "VARCHAR(20)", "id": 3, "default": "none", "storage": "MAIN" }
+            ],
+            "schema_version": 1,
+            "schema_history": [ { "version": 0, "columns": [ { "name": "id", "data_type": "INT", "id": 1 } ] } ],
+            "next_column_id": 4,
+            "constraints": [
+                { "kind": "primary_key", "name": "items_pkey", "columns": ["id"] },
+                { "kind": "check", "name": "items_id_check", "expression": "(id > 0)" }
+            ]
+        } } } } }"#,
+    )
+    .unwrap();
+    let old_table_file = config.database_dir().join("shop").join("items.dat");
+    fs::create_dir_all(old_table_file.parent().unwrap()).unwrap();
+    let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&old_table_file).unwrap();
+    init_table(&mut file).unwrap();
+    let old_table = fs::read(&old_table_file).unwrap();
+    bootstrap_catalog(&config).unwrap();
+    assert!(!config.catalog_file().exists());
+    assert!(config.catalog_migrated_file().exists());
+
+    let catalog = load_catalog(&config).unwrap();
+    let items = &catalog.databases["shop"].tables["items"];
+    assert_eq!(items.columns[1].data_type, DataType::Varchar(20));
+    assert_eq!(items.columns[1].default.as_deref(), Some("none"));
+    assert!(items.columns[0].not_null);
+    assert_eq!((items.schema_version, items.next_column_id), (1, 4));
+    assert_eq!(items.columns_for_version(0).unwrap().len(), 1);
+    assert_eq!(items.constraints[0], Constraint::PrimaryKey {
+        name: "items_pkey".to_string(),
+        columns: vec!["id".to_string()],
+    });
+    assert_eq!(items.constraints[1].name(), "items_id_check");
+
+    // Step 4: Migrated objects get OIDs and their files move to the OID paths
+    assert_eq!((catalog.databases["shop"].oid, items.oid), (FIRST_OID, FIRST_OID + 1));
+    assert!(!old_table_file.exists());
+    assert_eq!(fs::read(catalog.table_file("shop", "items").unwrap()).unwrap(), old_table);
+    assert_eq!(catalog.next_oid, FIRST_OID + 2);
+
+    // Step 5: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
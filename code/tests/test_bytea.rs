@@ -0,0 +1,33 @@
+use storage_manager::catalog::{Column, DataType, Table};
+use storage_manager::tuple::{decode_tuple, encode_row};
+use storage_manager::value::{parse_value, Value};
+
+#[test]
+fn test_bytea() {
+    // Step 1: The type is recognized under both names
+    assert_eq!(DataType::parse("bytea"), Ok(DataType::Bytea));
+    assert_eq!(DataType::parse("BLOB"), Ok(DataType::Bytea));
+
+    // Step 2: Hex (\x prefix) and base64 input decode to the same bytes
+    let hex = parse_value(DataType::Bytea, "\\xDEADbeef00").unwrap();
+    let base64 = parse_value(DataType::Bytea, "3q2+7wA=").unwrap();
+    assert_eq!(hex, Value::Bytes(vec![0xde, 0xad, 0xbe, 0xef, 0x00]));
+    assert_eq!(hex, base64);
+    assert_eq!(parse_value(DataType::Bytea, "\\x").unwrap(), Value::Bytes(vec![]));
+
+    // Step 3: Invalid input is rejected
+    assert!(parse_value(DataType::Bytea, "\\xabc").is_err());
+    assert!(parse_value(DataType::Bytea, "\\x+f").is_err());
+    assert!(parse_value(DataType::Bytea, "not base64!").is_err());
+
+    // Step 4: Values round-trip through a tuple and print as lowercase hex
+    let table = Table::new(vec![
+        Column::new("id", DataType::Int),
+        Column::new("digest", DataType::Bytea),
+    ]);
+    let digest = "\\x".to_string() + &"ab".repeat(32);
+    let tuple = encode_row(&table, &["1", &digest]).unwrap();
+    let values = decode_tuple(&table, &tuple).unwrap();
+    assert_eq!(values[1], Value::Bytes(vec![0xab; 32]));
+    assert_eq!(values[1].to_string(), digest);
+}
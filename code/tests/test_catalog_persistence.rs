@@ -0,0 +1,77 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    bootstrap_catalog, create_database, create_table, current_generation, drop_database, load_catalog,
+    Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::error::RookError;
+use storage_manager::system_catalog::{read_system_tables, system_table_path};
+
+#[test]
+fn test_catalog_persistence() {
+    let db_name = "test_catalog_persistence_db";
+
+    // Step 1: Saves write a new generation of system tables and keep the previous one
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_catalog_persistence"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+    create_database(&mut catalog, db_name).unwrap();
+
+    let generation = current_generation(&config).unwrap();
+    assert!(!config.control_temp_file().exists());
+    let previous = read_system_tables(&config.generation_dir(generation - 1)).unwrap();
+    assert!(!previous.databases.contains_key(db_name), "backup holds the catalog before the save");
+    assert!(load_catalog(&config).unwrap().databases.contains_key(db_name));
+
+    // Step 2: A truncated system table is an error, not an empty catalog
+    let table_path = system_table_path(&config.generation_dir(generation), "rook_database");
+    let saved = fs::read(&table_path).unwrap();
+    fs::write(&table_path, &saved[..saved.len() / 2]).unwrap();
+    let err = load_catalog(&config).err().expect("corrupt catalog must not load");
+    assert!(matches!(&err, RookError::Corruption { file, page: Some(_), .. } if *file == table_path));
+    assert!(err.to_string().contains(&config.generation_dir(generation - 1).display().to_string()));
+    fs::write(&table_path, &saved).unwrap();
+
+    // Step 3: So is a corrupt or missing control file, which bootstrap will not paper over
+    let control = fs::read_to_string(config.control_file()).unwrap();
+    fs::write(config.control_file(), "generation ?").unwrap();
+    let err = load_catalog(&config).err().unwrap();
+    assert!(matches!(err, RookError::Corruption { file, page: None, .. } if file == config.control_file()));
+    fs::remove_file(config.control_file()).unwrap();
+    let err = load_catalog(&config).err().unwrap();
+    assert!(matches!(err, RookError::Io(e) if e.kind() == ErrorKind::NotFound));
+    let err = bootstrap_catalog(&config).expect_err("generations exist without a control file");
+    assert!(matches!(err, RookError::Corruption { file, page: None, .. } if file == config.control_file()));
+
+    // Step 4: Leftovers of an interrupted save are discarded on start
+    fs::write(config.control_file(), &control).unwrap();
+    fs::write(config.control_temp_file(), "genera").unwrap();
+    fs::create_dir_all(config.generation_dir(generation + 1)).unwrap();
+    bootstrap_catalog(&config).unwrap();
+    assert!(!config.control_temp_file().exists());
+    assert!(!config.generation_dir(generation + 1).exists());
+
+    // Step 5: A table file without the current format version is refused, not misread
+    let mut catalog = load_catalog(&config).unwrap();
+    create_table(&mut catalog, db_name, "items", vec![Column::new("id", DataType::Int)]).unwrap();
+    let table_path = catalog.table_file(db_name, "items").unwrap();
+    let saved = fs::read(&table_path).unwrap();
+    let mut old_format = saved.clone();
+    old_format[4..8].fill(0);
+    fs::write(&table_path, &old_format).unwrap();
+    let err = load_catalog(&config).err().expect("old format must not load");
+    assert!(matches!(&err, RookError::Corruption { file, page: Some(0), .. } if *file == table_path), "{}", err);
+    fs::write(&table_path, &saved).unwrap();
+
+    // Step 6: Clean up
+    let mut catalog = load_catalog(&config).unwrap();
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+    fs::remove_dir_all(&config.root).unwrap();
+}
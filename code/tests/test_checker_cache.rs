@@ -0,0 +1,82 @@
+use std::fs::{self, File, OpenOptions};
+use std::path::Path;
+
+use storage_manager::catalog::{
+    alter_table_add_constraint, bootstrap_catalog, create_database, create_table, load_catalog,
+    parse_constraint, Catalog, Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::page::{delete_row, insert_row, load_csv_and_insert, scan_rows, update_row};
+
+const DB_NAME: &str = "shop";
+
+fn open_table(catalog: &Catalog, table_name: &str) -> File {
+    let path = catalog.table_file(DB_NAME, table_name).unwrap();
+    OpenOptions::new().read(true).write(true).open(path).unwrap()
+}
+
+fn add(catalog: &mut Catalog, table_name: &str, text: &str) {
+    let constraint = parse_constraint(table_name, text).unwrap();
+    alter_table_add_constraint(catalog, DB_NAME, table_name, constraint).unwrap();
+}
+
+#[test]
+fn test_checker_cache() {
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_checker_cache"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    create_database(&mut catalog, DB_NAME).unwrap();
+    create_table(&mut catalog, DB_NAME, "users", vec![Column::new("id", DataType::Int)]).unwrap();
+    let columns = vec![Column::new("id", DataType::Int), Column::new("user_id", DataType::Int)];
+    create_table(&mut catalog, DB_NAME, "orders", columns).unwrap();
+    add(&mut catalog, "users", "PRIMARY KEY (id)");
+    add(&mut catalog, "orders", "FOREIGN KEY (user_id) REFERENCES users");
+    let mut users = open_table(&catalog, "users");
+    let mut orders = open_table(&catalog, "orders");
+
+    // Step 1: Single-row inserts keep the cached index up to date
+    for id in 1..=500 {
+        insert_row(&catalog, DB_NAME, "users", &mut users, &[&id.to_string()]).unwrap();
+    }
+    assert!(insert_row(&catalog, DB_NAME, "users", &mut users, &["250"]).is_err());
+
+    // Step 2: A child sees parent keys inserted after its checker was cached
+    insert_row(&catalog, DB_NAME, "orders", &mut orders, &["1", "1"]).unwrap();
+    insert_row(&catalog, DB_NAME, "users", &mut users, &["501"]).unwrap();
+    insert_row(&catalog, DB_NAME, "orders", &mut orders, &["2", "501"]).unwrap();
+
+    // Step 3: Rows loaded in bulk count for the next single-row insert
+    let csv_path = config.root.join("users.csv");
+    fs::write(&csv_path, "id\n600\n").unwrap();
+    load_csv_and_insert(&catalog, DB_NAME, "users", &mut users, csv_path.to_str().unwrap(), &CsvOptions::default()).unwrap();
+    assert!(insert_row(&catalog, DB_NAME, "users", &mut users, &["600"]).is_err());
+
+    // Step 4: A deleted row's key can be used again, and children no longer see it
+    let rows = scan_rows(&catalog, DB_NAME, "users", &mut users).unwrap();
+    let (tuple, _) = rows.iter().find(|(_, row)| row[0].to_string() == "600").unwrap();
+    delete_row(&catalog, DB_NAME, "users", &mut users, tuple.0, tuple.1).unwrap();
+    assert!(insert_row(&catalog, DB_NAME, "orders", &mut orders, &["3", "600"]).is_err());
+    insert_row(&catalog, DB_NAME, "users", &mut users, &["600"]).unwrap();
+
+    // Step 5: An update moves the key in the cached index
+    let rows = scan_rows(&catalog, DB_NAME, "users", &mut users).unwrap();
+    let (tuple, _) = rows.iter().find(|(_, row)| row[0].to_string() == "600").unwrap();
+    update_row(&catalog, DB_NAME, "users", &mut users, *tuple, &["700"]).unwrap();
+    insert_row(&catalog, DB_NAME, "users", &mut users, &["600"]).unwrap();
+    assert!(insert_row(&catalog, DB_NAME, "users", &mut users, &["700"]).is_err());
+
+    // Step 6: A failed update leaves the old key indexed
+    let rows = scan_rows(&catalog, DB_NAME, "users", &mut users).unwrap();
+    let (tuple, _) = rows.iter().find(|(_, row)| row[0].to_string() == "700").unwrap();
+    assert!(update_row(&catalog, DB_NAME, "users", &mut users, *tuple, &["1"]).is_err());
+    assert!(insert_row(&catalog, DB_NAME, "users", &mut users, &["700"]).is_err());
+
+    // Step 7: A constraint added later is enforced at once
+    add(&mut catalog, "orders", "UNIQUE (user_id)");
+    assert!(insert_row(&catalog, DB_NAME, "orders", &mut orders, &["4", "1"]).is_err());
+
+    // Step 8: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
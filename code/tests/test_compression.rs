@@ -0,0 +1,146 @@
+use std::fs;
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    alter_table_set_storage, create_database, create_table, drop_database, bootstrap_catalog,
+    load_catalog, Column, DataType, StorageStrategy, Table,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::toast::{detoast_tuple, toast_tuple, ToastFile, TOAST_TUPLE_THRESHOLD};
+use storage_manager::tuple::{decode_tuple, encode_row, tuple_layout};
+use storage_manager::value::{Value, VARLEN_COMPRESSED, VARLEN_EXTERNAL};
+
+/// Varlen header flags of each non-NULL attribute of a tuple (0 for the INT column).
+fn header_flags(table: &Table, tuple: &[u8]) -> Vec<u32> {
+    let layout = tuple_layout(table, tuple).unwrap();
+    layout
+        .attributes
+        .iter()
+        .filter_map(|span| {
+            let range = span.range.clone()?;
+            if span.column.data_type == DataType::Int {
+                return Some(0);
+            }
+            let header = u32::from_le_bytes(tuple[range.start..range.start + 4].try_into().unwrap());
+            Some(header & (VARLEN_EXTERNAL | VARLEN_COMPRESSED))
+        })
+        .collect()
+}
+
+/// Incompressible text: hex digits from a simple linear congruential generator.
+fn noise(len: usize) -> String {
+    let mut state: u64 = 42;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            char::from_digit((state >> 60) as u32, 16).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_compression() {
+    let db_name = "test_compression_db";
+    let table_name = "pages";
+
+    // Step 1: Strategies parse, default to EXTENDED and are stored in the catalog
+    assert_eq!(StorageStrategy::parse("external"), Ok(StorageStrategy::External));
+    assert!(StorageStrategy::parse("zip").is_err());
+    let column: Column = serde_json::from_str(r#"{ "name": "x", "data_type": "TEXT" }"#).unwrap();
+    assert_eq!(column.storage, StorageStrategy::Extended);
+
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_compression"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        table_name,
+        vec![
+            Column::new("id", DataType::Int),
+            Column::new("extended", DataType::Text),
+            Column::new("external", DataType::Text),
+            Column::new("main", DataType::Text),
+            Column::new("plain", DataType::Bytea),
+        ],
+    ).unwrap();
+    for (column, storage) in [
+        ("external", StorageStrategy::External),
+        ("main", StorageStrategy::Main),
+        ("plain", StorageStrategy::Plain),
+    ] {
+        alter_table_set_storage(&mut catalog, db_name, table_name, column, storage).unwrap();
+    }
+    assert!(alter_table_set_storage(
+        &mut catalog,
+        db_name,
+        table_name,
+        "id",
+        StorageStrategy::Main
+    ).is_err());
+    let reloaded = load_catalog(&config).unwrap();
+    assert_eq!(
+        reloaded.databases[db_name].tables[table_name].columns[2].storage,
+        StorageStrategy::External
+    );
+
+    let table = &catalog.databases[db_name].tables[table_name];
+    let mut toast = ToastFile::new(catalog.toast_file(db_name, table_name).unwrap());
+    let toast_path = catalog.toast_file(db_name, table_name).unwrap();
+
+    // Step 2: Compressible values shrink inline; nothing needs the TOAST file
+    let repetitive = "abc".repeat(2000);
+    let fields = ["1", repetitive.as_str(), "small", repetitive.as_str(), "\\x00"];
+    let tuple = toast_tuple(table, encode_row(table, &fields).unwrap(), &mut toast).unwrap();
+    assert!(tuple.len() < 1000, "Tuple should be compressed, is {} bytes", tuple.len());
+    assert_eq!(
+        header_flags(table, &tuple),
+        vec![0, VARLEN_COMPRESSED, 0, VARLEN_COMPRESSED, 0]
+    );
+    assert!(!Path::new(&toast_path).exists());
+
+    let values = decode_tuple(table, &detoast_tuple(table, &tuple, &mut toast).unwrap()).unwrap();
+    assert_eq!(values[1], Value::Text(repetitive.clone()));
+    assert_eq!(values[3], Value::Text(repetitive.clone()));
+
+    // Step 3: Incompressible values move out of line, except PLAIN (never) and MAIN (last resort)
+    let random = noise(2500);
+    let plain = format!("\\x{}", noise(1200)); // 600 bytes
+    let fields = ["2", random.as_str(), random.as_str(), random.as_str(), plain.as_str()];
+    let tuple = toast_tuple(table, encode_row(table, &fields).unwrap(), &mut toast).unwrap();
+    assert!(tuple.len() <= TOAST_TUPLE_THRESHOLD + 2600);
+    assert_eq!(
+        header_flags(table, &tuple),
+        vec![0, VARLEN_EXTERNAL, VARLEN_EXTERNAL, 0, 0]
+    );
+
+    let values = decode_tuple(table, &detoast_tuple(table, &tuple, &mut toast).unwrap()).unwrap();
+    assert_eq!(values[1], Value::Text(random.clone()));
+    assert_eq!(values[2], Value::Text(random.clone()));
+    assert_eq!(values[3], Value::Text(random.clone()));
+    assert_eq!(values[4].to_string(), plain);
+
+    // Step 4: MAIN values go out of line when the row cannot fit a page otherwise
+    let huge = noise(9000);
+    let fields = ["3", "", "", huge.as_str(), ""];
+    let tuple = toast_tuple(table, encode_row(table, &fields).unwrap(), &mut toast).unwrap();
+    assert_ne!(header_flags(table, &tuple)[1] & VARLEN_EXTERNAL, 0);
+    let values = decode_tuple(table, &detoast_tuple(table, &tuple, &mut toast).unwrap()).unwrap();
+    assert_eq!(values[3], Value::Text(huge));
+    assert_eq!(values[1], Value::Null);
+
+    // PLAIN values that cannot fit a page are rejected
+    let too_big = format!("\\x{}", noise(17000));
+    let fields = ["4", "", "", "", too_big.as_str()];
+    assert!(toast_tuple(table, encode_row(table, &fields).unwrap(), &mut toast).is_err());
+
+    // Step 5: Clean up
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+    let _ = fs::remove_file(&toast_path);
+}
@@ -0,0 +1,128 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    alter_table_add_constraint, alter_table_drop_column, alter_table_drop_constraint,
+    alter_table_rename_column, create_database, create_table, drop_database, bootstrap_catalog,
+    load_catalog, parse_constraint, Column, Constraint, DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::expr::Expr;
+use storage_manager::page::{insert_row, load_csv_and_insert, scan_tuples};
+use storage_manager::value::{parse_value, Value};
+
+#[test]
+fn test_constraints() {
+    // Step 1: CHECK expressions follow SQL three-valued logic
+    let columns = vec![Column::new("age", DataType::Int), Column::new("status", DataType::Text)];
+    let check = Expr::parse("age >= 18 AND status IN ('new', 'paid')").unwrap();
+    let row = |age: Value, status: &str| vec![age, Value::Text(status.to_string())];
+    assert_eq!(check.eval(&columns, &row(Value::Int(30), "paid")).unwrap(), Value::Boolean(true));
+    assert_eq!(check.eval(&columns, &row(Value::Int(9), "paid")).unwrap(), Value::Boolean(false));
+    assert_eq!(check.eval(&columns, &row(Value::Null, "new")).unwrap(), Value::Null);
+    assert!(Expr::parse("age >=").is_err());
+
+    // Step 2: DECIMALs are compared and computed exactly, beyond the precision of a double
+    let amount = vec![Column::new("amount", DataType::parse("DECIMAL(20,2)").unwrap())];
+    let decimal = |text: &str| vec![parse_value(amount[0].data_type, text).unwrap()];
+    let check = Expr::parse("amount > 12345678901234567.00").unwrap();
+    assert_eq!(check.eval(&amount, &decimal("12345678901234567.01")).unwrap(), Value::Boolean(true));
+    assert_eq!(check.eval(&amount, &decimal("12345678901234567.00")).unwrap(), Value::Boolean(false));
+    let eval = |expr: &str, text: &str| Expr::parse(expr).unwrap().eval(&amount, &decimal(text)).unwrap().to_string();
+    assert_eq!(eval("amount = 12345678901234567", "12345678901234567.00"), "true");
+    assert_eq!(eval("amount + 0.001", "12345678901234567.01"), "12345678901234567.011");
+    assert_eq!(eval("amount * 2 - 1", "12345678901234567.01"), "24691357802469133.02");
+    assert_eq!(eval("amount / 3", "1.00"), "0.333333");
+    assert_eq!(eval("-amount % 0.3", "1.00"), "-0.10");
+    assert_eq!(eval("ABS(amount - 5)", "1.25"), "3.75");
+    assert_eq!(eval("amount * 1.5e0", "2.00"), "3");
+    assert!(Expr::parse("amount / 0.0").unwrap().eval(&amount, &decimal("1.00")).is_err());
+
+    // Step 3: Constraint definitions parse with PostgreSQL-style default names
+    assert_eq!(
+        parse_constraint("users", "UNIQUE (email)").unwrap(),
+        Constraint::Unique { name: "users_email_key".to_string(), columns: vec!["email".to_string()] }
+    );
+    assert_eq!(parse_constraint("users", "CHECK (age > 0)").unwrap().name(), "users_age_check");
+
+    // Step 4: Create a table with DEFAULT, NOT NULL, PRIMARY KEY, UNIQUE and CHECK
+    let db_name = "test_constraints_db";
+    let table_name = "users";
+    let csv_path = "test_constraints.csv";
+
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_constraints"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    let mut age = Column::new("age", DataType::Int);
+    age.default = Some("18".to_string());
+    let mut email = Column::new("email", DataType::Text);
+    email.not_null = true;
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        table_name,
+        vec![Column::new("id", DataType::Int), email, age],
+    ).unwrap();
+    for text in ["PRIMARY KEY (id)", "UNIQUE (email)", "CONSTRAINT adult CHECK (age >= 18)"] {
+        let constraint = parse_constraint(table_name, text).unwrap();
+        alter_table_add_constraint(&mut catalog, db_name, table_name, constraint).unwrap();
+    }
+    let second_key = parse_constraint(table_name, "PRIMARY KEY (email)").unwrap();
+    assert!(alter_table_add_constraint(&mut catalog, db_name, table_name, second_key).is_err());
+
+    // Step 5: Violating rows are skipped one by one; missing fields take the default
+    fs::write(
+        csv_path,
+        "id,email,age\n\
+         1,a@x.org,30\n\
+         2,b@x.org\n\
+         1,c@x.org,40\n\
+         ,d@x.org,40\n\
+         3,a@x.org,40\n\
+         4,,40\n\
+         5,e@x.org,12\n",
+    )
+    .unwrap();
+    let table_path = catalog.table_file(db_name, table_name).unwrap();
+    let mut file = OpenOptions::new().read(true).write(true).open(&table_path).unwrap();
+    load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &CsvOptions::default()).unwrap();
+
+    let email = |s: &str| Value::Text(s.to_string());
+    let rows = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::Int(1), email("a@x.org"), Value::Int(30)],
+            vec![Value::Int(2), email("b@x.org"), Value::Int(18)],
+        ]
+    );
+
+    // Step 6: Keys already on disk are enforced by later loads and single-row inserts
+    buffer_manager.load_csv_to_buffer(&catalog, db_name, table_name, csv_path, &CsvOptions::default()).unwrap();
+    assert!(insert_row(&catalog, db_name, table_name, &mut file, &["2", "z@x.org"]).is_err());
+    insert_row(&catalog, db_name, table_name, &mut file, &["6", "f@x.org", "60"]).unwrap();
+    assert_eq!(scan_tuples(&catalog, db_name, table_name, &mut file).unwrap().len(), 3);
+
+    // Step 7: Adding a constraint that existing rows violate fails
+    let low_age = parse_constraint(table_name, "CHECK (age < 50)").unwrap();
+    assert!(alter_table_add_constraint(&mut catalog, db_name, table_name, low_age).is_err());
+
+    // Step 8: Renames follow into constraints; constrained columns cannot be dropped
+    alter_table_rename_column(&mut catalog, db_name, table_name, "age", "years").unwrap();
+    let table = &catalog.databases[db_name].tables[table_name];
+    assert!(table.constraints.iter().any(|c| c.columns() == ["years"]));
+    assert!(alter_table_drop_column(&mut catalog, db_name, table_name, "years").is_err());
+    alter_table_drop_constraint(&mut catalog, db_name, table_name, "adult").unwrap();
+    alter_table_drop_column(&mut catalog, db_name, table_name, "years").unwrap();
+
+    // Step 9: Clean up
+    let _ = fs::remove_file(csv_path);
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+}
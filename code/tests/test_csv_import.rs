@@ -0,0 +1,108 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    bootstrap_catalog, create_database, create_table, load_catalog, Catalog, Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::page::{load_csv_and_insert, scan_tuples};
+use storage_manager::value::Value;
+
+const DB_NAME: &str = "shop";
+
+fn text(value: &str) -> Value {
+    Value::Text(value.to_string())
+}
+
+/// Loads `csv` into a new table (id INT, name TEXT, note TEXT) and returns its rows.
+fn load(catalog: &mut Catalog, table_name: &str, csv: &[u8], options: &CsvOptions) -> Vec<Vec<Value>> {
+    let columns = vec![
+        Column::new("id", DataType::Int),
+        Column::new("name", DataType::Text),
+        Column::new("note", DataType::Text),
+    ];
+    create_table(catalog, DB_NAME, table_name, columns).unwrap();
+    let csv_path = catalog.config.root.join(format!("{}.csv", table_name));
+    fs::write(&csv_path, csv).unwrap();
+
+    let table_path = catalog.table_file(DB_NAME, table_name).unwrap();
+    let mut file = OpenOptions::new().read(true).write(true).open(&table_path).unwrap();
+    load_csv_and_insert(catalog, DB_NAME, table_name, &mut file, csv_path.to_str().unwrap(), options).unwrap();
+    scan_tuples(catalog, DB_NAME, table_name, &mut file).unwrap()
+}
+
+#[test]
+fn test_csv_import() {
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_csv_import"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    create_database(&mut catalog, DB_NAME).unwrap();
+    let defaults = CsvOptions::default();
+
+    // Step 1: Quoted fields hold delimiters, doubled quotes and line breaks
+    let csv = b"id,name,note\n1,\"Smith, Ann\",\"she said \"\"hi\"\"\"\n2,bob,\"two\nlines\"\n";
+    assert_eq!(
+        load(&mut catalog, "quoted", csv, &defaults),
+        vec![
+            vec![Value::Int(1), text("Smith, Ann"), text("she said \"hi\"")],
+            vec![Value::Int(2), text("bob"), text("two\nlines")],
+        ]
+    );
+
+    // Step 2: Header names map fields to columns in any order; unknown names are ignored
+    let csv = b"note,extra,id\nfirst,ignored,1\n";
+    assert_eq!(
+        load(&mut catalog, "by_name", csv, &defaults),
+        vec![vec![Value::Int(1), Value::Null, text("first")]]
+    );
+
+    // Step 3: Custom delimiter and NULL string; an empty field is then an empty value
+    let options = CsvOptions { delimiter: b';', null_strings: vec!["\\N".to_string()], ..CsvOptions::default() };
+    let csv = b"id;name;note\n1;;\\N\n";
+    assert_eq!(
+        load(&mut catalog, "semicolon", csv, &options),
+        vec![vec![Value::Int(1), text(""), Value::Null]]
+    );
+
+    // Step 4: Without a header, fields are matched by position
+    let options = CsvOptions { header: false, ..CsvOptions::default() };
+    assert_eq!(
+        load(&mut catalog, "positional", b"1,ann\n", &options),
+        vec![vec![Value::Int(1), text("ann"), Value::Null]]
+    );
+
+    // Step 5: Files are decoded from their encoding; a UTF-8 BOM is skipped
+    let options = CsvOptions { encoding: CsvOptions::encoding_for_label("latin1").unwrap(), ..CsvOptions::default() };
+    assert_eq!(
+        load(&mut catalog, "latin1", b"id,name\n1,Jos\xE9\n", &options),
+        vec![vec![Value::Int(1), text("José"), Value::Null]]
+    );
+    assert_eq!(
+        load(&mut catalog, "bom", b"\xEF\xBB\xBFid,name\n1,ann\n", &defaults),
+        vec![vec![Value::Int(1), text("ann"), Value::Null]]
+    );
+    assert!(CsvOptions::encoding_for_label("klingon").is_err());
+
+    // Step 6: A header naming none of the columns is rejected
+    create_table(&mut catalog, DB_NAME, "unknown", vec![Column::new("id", DataType::Int)]).unwrap();
+    let csv_path = config.root.join("unknown.csv");
+    fs::write(&csv_path, "a,b\n1,2\n").unwrap();
+    let mut buffer_manager = BufferManager::new();
+    assert!(buffer_manager
+        .load_csv_to_buffer(&catalog, DB_NAME, "unknown", csv_path.to_str().unwrap(), &defaults)
+        .is_err());
+
+    // Step 7: The buffer loader reads the same format
+    fs::write(&csv_path, "id\n\"7\"\n").unwrap();
+    buffer_manager
+        .load_csv_to_buffer(&catalog, DB_NAME, "unknown", csv_path.to_str().unwrap(), &defaults)
+        .unwrap();
+    let mut file = fs::File::open(catalog.table_file(DB_NAME, "unknown").unwrap()).unwrap();
+    assert_eq!(scan_tuples(&catalog, DB_NAME, "unknown", &mut file).unwrap(), vec![vec![Value::Int(7)]]);
+
+    // Step 8: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
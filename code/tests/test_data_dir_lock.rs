@@ -0,0 +1,40 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process;
+
+use storage_manager::config::StorageConfig;
+use storage_manager::lock::DataDirLock;
+
+#[test]
+fn test_data_dir_lock() {
+    // Step 1: The first lock creates the lock file and records the PID
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_data_dir_lock"));
+    let _ = fs::remove_dir_all(&config.root);
+    let lock = DataDirLock::acquire(&config).unwrap();
+    assert_eq!(lock.path(), config.lock_file());
+    assert_eq!(fs::read_to_string(config.lock_file()).unwrap().trim(), process::id().to_string());
+
+    // Step 2: A second lock on the same directory fails and names the holder
+    let err = DataDirLock::acquire(&config).err().expect("directory is already locked");
+    assert_eq!(err.kind(), ErrorKind::ResourceBusy);
+    assert!(err.to_string().contains(&format!("PID {}", process::id())));
+
+    // Other data directories are independent
+    let other = StorageConfig::new(config.root.join("other"));
+    drop(DataDirLock::acquire(&other).unwrap());
+
+    // Step 3: Releasing empties the file and lets the next process in
+    drop(lock);
+    assert_eq!(fs::read_to_string(config.lock_file()).unwrap(), "");
+    drop(DataDirLock::acquire(&config).unwrap());
+
+    // Step 4: A PID left by a process that died is a stale lock and is taken over
+    fs::write(config.lock_file(), "999999\n").unwrap();
+    let lock = DataDirLock::acquire(&config).unwrap();
+    assert_eq!(fs::read_to_string(config.lock_file()).unwrap().trim(), process::id().to_string());
+
+    // Step 5: Clean up
+    drop(lock);
+    fs::remove_dir_all(&config.root).unwrap();
+}
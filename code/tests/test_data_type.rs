@@ -0,0 +1,59 @@
+use storage_manager::catalog::{validate_columns, Column, DataType};
+
+#[test]
+fn test_data_type() {
+    // Step 1: Known SQL names parse case-insensitively (with aliases)
+    assert_eq!(DataType::parse("INT"), Ok(DataType::Int));
+    assert_eq!(DataType::parse("integer"), Ok(DataType::Int));
+    assert_eq!(DataType::parse(" text "), Ok(DataType::Text));
+    assert_eq!(DataType::parse("bigint"), Ok(DataType::BigInt));
+    assert_eq!(DataType::parse("SMALLINT"), Ok(DataType::SmallInt));
+    assert_eq!(DataType::parse("REAL"), Ok(DataType::Float));
+    assert_eq!(DataType::parse("double   precision"), Ok(DataType::Double));
+    assert_eq!(DataType::parse("bool"), Ok(DataType::Boolean));
+    assert_eq!(DataType::parse("varchar(20)"), Ok(DataType::Varchar(20)));
+    assert_eq!(DataType::parse("CHARACTER VARYING(20)"), Ok(DataType::Varchar(20)));
+    assert_eq!(DataType::parse("CHAR(5)"), Ok(DataType::Char(5)));
+    assert_eq!(DataType::parse("CHAR"), Ok(DataType::Char(1)));
+    assert!(DataType::parse("VARCHAR(0)").is_err());
+
+    // Every advertised name parses
+    for name in DataType::SUPPORTED {
+        let name = name.replace("(p,s)", "(10,2)").replace("(n)", "(20)");
+        assert!(DataType::parse(&name).is_ok(), "'{}' should be supported", name);
+    }
+
+    // Step 2: Unknown names are rejected with the list of supported types
+    let err = DataType::parse("GEOMETRY").unwrap_err();
+    assert!(err.contains("GEOMETRY"), "Error should name the bad type: {}", err);
+    for name in DataType::SUPPORTED {
+        assert!(err.contains(name), "Error should list '{}': {}", name, err);
+    }
+
+    // Step 3: Columns are stored in the catalog with the SQL type name
+    let json = serde_json::to_string(&Column::new("id", DataType::Int)).unwrap();
+    assert!(json.contains("\"data_type\":\"INT\""), "Unexpected JSON: {}", json);
+
+    let column: Column = serde_json::from_str(r#"{ "name": "id", "data_type": "INT" }"#).unwrap();
+    assert_eq!(column.data_type, DataType::Int);
+
+    let price = Column::new("price", DataType::Decimal { precision: 10, scale: 2 });
+    let json = serde_json::to_string(&price).unwrap();
+    let reloaded: Column = serde_json::from_str(&json).unwrap();
+    assert_eq!(reloaded.data_type, price.data_type);
+
+    assert!(
+        serde_json::from_str::<Column>(r#"{ "name": "x", "data_type": "GEOMETRY" }"#).is_err(),
+        "Unknown types in the catalog file must not deserialize"
+    );
+
+    // Step 4: Table definitions are validated
+    assert!(validate_columns(&[Column::new("id", DataType::Int)]).is_ok());
+    assert!(validate_columns(&[]).is_err());
+    assert!(validate_columns(&[Column::new("", DataType::Int)]).is_err());
+    assert!(validate_columns(&[
+        Column::new("id", DataType::Int),
+        Column::new("id", DataType::Text),
+    ])
+    .is_err());
+}
@@ -0,0 +1,77 @@
+use storage_manager::catalog::DataType;
+use storage_manager::datetime::{
+    civil_from_days, days_from_civil, parse_date, parse_time, parse_timestamp, Interval,
+    MICROS_PER_HOUR, MICROS_PER_SECOND,
+};
+use storage_manager::value::{parse_value, Value};
+
+/// Parses and prints a value of the given type.
+fn canonical(data_type: DataType, raw: &str) -> String {
+    parse_value(data_type, raw).expect("Failed to parse").to_string()
+}
+
+#[test]
+fn test_datetime() {
+    // Step 1: Calendar math agrees with known dates (epoch, leap days, pre-epoch)
+    assert_eq!(days_from_civil(1970, 1, 1), 0);
+    assert_eq!(days_from_civil(2000, 3, 1), 11017);
+    assert_eq!(days_from_civil(1969, 12, 31), -1);
+    for days in [-719_162, -1, 0, 59, 11016, 19_723, 2_932_896] {
+        let (y, m, d) = civil_from_days(days);
+        assert_eq!(days_from_civil(y, m, d), days, "Round trip failed for day {}", days);
+    }
+
+    // Step 2: ISO-8601 input parsing
+    assert_eq!(parse_date("2024-02-29"), Ok(19_782));
+    assert!(parse_date("2023-02-29").is_err(), "2023 is not a leap year");
+    assert!(parse_date("2024-13-01").is_err());
+    assert!(parse_date("24-01-01").is_err());
+    assert_eq!(parse_time("12:30"), Ok(12 * MICROS_PER_HOUR + 30 * 60 * MICROS_PER_SECOND));
+    assert!(parse_time("24:00:00").is_err());
+    assert!(parse_time("10:00:00.1234567").is_err(), "Only microsecond precision is stored");
+    assert_eq!(parse_timestamp("1970-01-01T01:00:00+01:00", true), Ok(0));
+    assert_eq!(parse_timestamp("1970-01-01 00:00:00Z", true), Ok(0));
+    assert!(parse_timestamp("1970-01-01T00:00:00+01:00", false).is_err());
+    assert!(parse_timestamp("2024-01-01 12:00:00+1é1", true).is_err(), "Non-ASCII offsets are rejected, not sliced");
+
+    // Step 3: Canonical output
+    assert_eq!(canonical(DataType::Date, "1999-12-31"), "1999-12-31");
+    assert_eq!(canonical(DataType::Time, "08:05:03.250"), "08:05:03.25");
+    assert_eq!(canonical(DataType::Timestamp, "2024-05-06T07:08:09"), "2024-05-06 07:08:09");
+    assert_eq!(canonical(DataType::Timestamp, "2024-05-06"), "2024-05-06 00:00:00");
+    assert_eq!(
+        canonical(DataType::TimestampTz, "2024-05-06T23:30:00.000001-02:00"),
+        "2024-05-07 01:30:00.000001+00:00"
+    );
+    assert_eq!(canonical(DataType::Timestamp, "1960-06-15 12:00:00"), "1960-06-15 12:00:00");
+    assert_eq!(canonical(DataType::Interval, "P1Y2M10DT2H30M15.5S"), "P1Y2M10DT2H30M15.5S");
+    assert_eq!(canonical(DataType::Interval, "P14M"), "P1Y2M");
+    assert_eq!(canonical(DataType::Interval, "P2W"), "P14D");
+    assert_eq!(canonical(DataType::Interval, "PT90M"), "PT1H30M");
+    assert_eq!(canonical(DataType::Interval, "-P1DT1S"), "P-1DT-1S");
+    assert_eq!(canonical(DataType::Interval, "PT0S"), "PT0S");
+    for bad in ["1D", "P", "PT", "P1H", "PT1D", "P1.5D"] {
+        assert!(Interval::parse(bad).is_err(), "'{}' should be rejected", bad);
+    }
+
+    // Step 4: Canonical output parses back to the same value
+    for raw in ["P-1DT-1S", "P1Y2M", "PT0.000001S"] {
+        let value = parse_value(DataType::Interval, raw).unwrap();
+        assert_eq!(parse_value(DataType::Interval, &value.to_string()), Ok(value));
+    }
+
+    // Step 5: Ordering follows time, not text
+    let ts = |raw: &str| parse_value(DataType::TimestampTz, raw).unwrap();
+    assert!(ts("2024-01-01T10:00:00+05:00") < ts("2024-01-01T06:00:00Z"));
+    let date = |raw: &str| parse_value(DataType::Date, raw).unwrap();
+    assert!(date("1969-12-31") < date("1970-01-01"));
+    let interval = |raw: &str| parse_value(DataType::Interval, raw).unwrap();
+    assert!(interval("P1M") > interval("P29D"));
+    assert!(interval("PT25H") > interval("P1D"));
+    assert!(interval("P1M") != interval("P30D"));
+    assert_eq!(
+        Value::Date(0).partial_cmp(&Value::Int(0)),
+        None,
+        "Different types are not comparable"
+    );
+}
@@ -0,0 +1,46 @@
+use storage_manager::catalog::DataType;
+use storage_manager::value::Decimal;
+
+#[test]
+fn test_decimal() {
+    // Step 1: Values are scaled exactly (no binary floating point involved)
+    let d = Decimal::parse("0.1", 10, 2).unwrap();
+    assert_eq!(d, Decimal { unscaled: 10, scale: 2 });
+    assert_eq!(d.to_string(), "0.10");
+
+    assert_eq!(Decimal::parse("42", 5, 0).unwrap().to_string(), "42");
+    assert_eq!(Decimal::parse(".25", 5, 2).unwrap().to_string(), "0.25");
+    assert_eq!(Decimal::parse("+7.", 5, 1).unwrap().to_string(), "7.0");
+
+    // Step 2: Extra fractional digits round half away from zero
+    assert_eq!(Decimal::parse("2.345", 10, 2).unwrap().to_string(), "2.35");
+    assert_eq!(Decimal::parse("-2.345", 10, 2).unwrap().to_string(), "-2.35");
+    assert_eq!(Decimal::parse("2.344", 10, 2).unwrap().to_string(), "2.34");
+
+    // Step 3: Precision is enforced
+    assert!(Decimal::parse("999.99", 5, 2).is_ok());
+    assert!(Decimal::parse("1000.00", 5, 2).is_err());
+    assert!(Decimal::parse("999.995", 5, 2).is_err(), "Rounding may not overflow the precision");
+
+    // Step 4: Malformed literals are rejected
+    for bad in ["", ".", "-", "1.2.3", "1e5", "12,5", "abc"] {
+        assert!(Decimal::parse(bad, 10, 2).is_err(), "'{}' should be rejected", bad);
+    }
+
+    // Step 5: Largest supported precision
+    let max = "9".repeat(38);
+    assert_eq!(Decimal::parse(&max, 38, 0).unwrap().to_string(), max);
+
+    // Step 6: Type names
+    assert_eq!(
+        DataType::parse("numeric(10, 2)"),
+        Ok(DataType::Decimal { precision: 10, scale: 2 })
+    );
+    assert_eq!(DataType::parse("DECIMAL(7)"), Ok(DataType::Decimal { precision: 7, scale: 0 }));
+    assert!(DataType::parse("DECIMAL(2,3)").is_err());
+    assert!(DataType::parse("DECIMAL(39,0)").is_err());
+    assert_eq!(
+        DataType::Decimal { precision: 10, scale: 2 }.to_string(),
+        "DECIMAL(10,2)"
+    );
+}
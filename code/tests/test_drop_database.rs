@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    create_database, create_table, drop_database, bootstrap_catalog, load_catalog, Column, DataType,
+};
+use storage_manager::config::{tombstone_path, StorageConfig};
+
+#[test]
+fn test_drop_database() {
+    let db_name = "test_drop_database_db";
+
+    // Step 1: Create a database with a table loaded into the buffer
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_drop_database"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        "events",
+        vec![Column::new("id", DataType::Int)],
+    ).unwrap();
+    buffer_manager
+        .load_table_on_create(&catalog, db_name, "events")
+        .expect("Failed to load table into buffer");
+
+    let db_path = catalog.database_dir(db_name).unwrap();
+    assert!(Path::new(&db_path).exists(), "Database directory was not created");
+
+    // Step 2: Drop the database
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+
+    // Step 3: Verify catalog (in memory and on disk), directory and buffer
+    assert!(!catalog.databases.contains_key(db_name));
+    assert!(!load_catalog(&config).unwrap().databases.contains_key(db_name));
+    assert!(!Path::new(&db_path).exists(), "Database directory still exists");
+    assert!(
+        !Path::new(&tombstone_path(&db_path)).exists(),
+        "Tombstone directory was left behind"
+    );
+    assert!(buffer_manager.loaded_table.is_none(), "Buffer still holds dropped table");
+
+    // Step 4: Dropping again fails
+    assert!(drop_database(&mut catalog, &mut buffer_manager, db_name).is_err());
+}
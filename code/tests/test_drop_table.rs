@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    create_database, create_table, drop_database, drop_table, bootstrap_catalog, load_catalog, Column, DataType,
+};
+use storage_manager::config::{tombstone_path, StorageConfig};
+
+#[test]
+fn test_drop_table() {
+    let db_name = "test_drop_table_db";
+    let table_name = "students";
+
+    // Step 1: Start from a fresh database containing one table
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_drop_table"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        table_name,
+        vec![
+            Column::new("id", DataType::Int),
+            Column::new("name", DataType::Text),
+        ],
+    ).unwrap();
+    buffer_manager
+        .load_table_on_create(&catalog, db_name, table_name)
+        .expect("Failed to load table into buffer");
+
+    let table_path = catalog.table_file(db_name, table_name).unwrap();
+    assert!(Path::new(&table_path).exists(), "Table file was not created");
+
+    // Step 2: Drop the table
+    drop_table(&mut catalog, &mut buffer_manager, db_name, table_name).unwrap();
+
+    // Step 3: Verify catalog (in memory and on disk), file and buffer
+    assert!(!catalog.databases[db_name].tables.contains_key(table_name));
+    assert!(!load_catalog(&config).unwrap().databases[db_name].tables.contains_key(table_name));
+    assert!(!Path::new(&table_path).exists(), "Table file still exists");
+    assert!(
+        !Path::new(&tombstone_path(&table_path)).exists(),
+        "Tombstone file was left behind"
+    );
+    assert!(buffer_manager.loaded_table.is_none(), "Buffer still holds dropped table");
+    assert_eq!(buffer_manager.pages.len(), 1, "Buffer should only hold the header page");
+
+    // Step 4: Dropping again fails
+    assert!(drop_table(&mut catalog, &mut buffer_manager, db_name, table_name).is_err());
+
+    // Step 5: Clean up
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+}
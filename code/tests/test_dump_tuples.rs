@@ -0,0 +1,61 @@
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+use storage_manager::catalog::{
+    bootstrap_catalog, create_database, create_table, list_databases, list_tables, load_catalog, Column,
+    DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::error::RookError;
+use storage_manager::page::{delete_row, dump_tuples, insert_row, PAGE_SIZE};
+use storage_manager::value::Value;
+
+#[test]
+fn test_dump_tuples() {
+    let db_name = "test_dump_db";
+
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_dump_tuples"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+
+    // Step 1: Databases and tables are listed by name, sorted
+    create_database(&mut catalog, db_name).unwrap();
+    create_database(&mut catalog, "archive_db").unwrap();
+    let columns = || vec![Column::new("id", DataType::Int), Column::new("name", DataType::Text)];
+    create_table(&mut catalog, db_name, "users", columns()).unwrap();
+    create_table(&mut catalog, db_name, "accounts", columns()).unwrap();
+    assert_eq!(list_databases(&catalog), vec!["archive_db", db_name]);
+    assert_eq!(list_tables(&catalog, db_name).unwrap(), vec!["accounts", "users"]);
+    assert!(list_tables(&catalog, "archive_db").unwrap().is_empty());
+    assert!(matches!(list_tables(&catalog, "missing_db"), Err(RookError::NotFound { kind: "Database", .. })));
+
+    // Step 2: Each data page comes back with its header and live tuples
+    let table_path = catalog.table_file(db_name, "users").unwrap();
+    let mut file = OpenOptions::new().read(true).write(true).open(&table_path).unwrap();
+    for (id, name) in [("1", "ada"), ("2", "grace"), ("3", "linus")] {
+        insert_row(&catalog, db_name, "users", &mut file, &[id, name]).unwrap();
+    }
+    delete_row(&catalog, db_name, "users", &mut file, 1, 1).unwrap();
+    let pages = dump_tuples(&catalog, db_name, "users", &mut file).unwrap();
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].page_num, 1);
+    assert!(pages[0].lower < pages[0].upper);
+    assert_eq!(
+        pages[0].tuples,
+        vec![
+            (0, vec![Value::Int(1), Value::Text("ada".to_string())]),
+            (2, vec![Value::Int(3), Value::Text("linus".to_string())]),
+        ]
+    );
+
+    // Step 3: A page whose lower offset is inside the page header is corruption, not a panic
+    file.seek(SeekFrom::Start(PAGE_SIZE as u64)).unwrap();
+    file.write_all(&2u32.to_le_bytes()).unwrap();
+    let err = dump_tuples(&catalog, db_name, "users", &mut file).unwrap_err();
+    assert!(matches!(&err, RookError::Corruption { file, page: Some(1), .. } if *file == table_path), "{}", err);
+
+    // Step 4: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
@@ -0,0 +1,110 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::catalog::{bootstrap_catalog, create_database, create_table, load_catalog, Column, DataType};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::error::RookError;
+use storage_manager::export::{export_csv, export_json};
+use storage_manager::page::{insert_row, load_csv_and_insert, scan_tuples};
+use storage_manager::value::Value;
+
+#[test]
+fn test_export_csv() {
+    let db_name = "test_export_db";
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_export_csv"));
+    let _ = fs::remove_dir_all(&config.root);
+    fs::create_dir_all(&config.root).unwrap();
+    let csv_path = config.root.join("blobs.csv");
+    let csv_path = csv_path.to_str().unwrap();
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    create_database(&mut catalog, db_name).unwrap();
+
+    // Step 1: A table with binary, text, decimal and date values, and NULLs
+    let columns = || {
+        vec![
+            Column::new("id", DataType::Int),
+            Column::new("digest", DataType::Bytea),
+            Column::new("note", DataType::Text),
+            Column::new("amount", DataType::parse("DECIMAL(10,2)").unwrap()),
+            Column::new("seen", DataType::Date),
+        ]
+    };
+    create_table(&mut catalog, db_name, "blobs", columns()).unwrap();
+    create_table(&mut catalog, db_name, "copy", columns()).unwrap();
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(catalog.table_file(db_name, "blobs").unwrap())
+        .unwrap();
+    insert_row(&catalog, db_name, "blobs", &mut file, &["1", "\\xdeadbeef", "plain", "12.50", "2024-02-29"]).unwrap();
+    insert_row(&catalog, db_name, "blobs", &mut file, &["2", "\\x00ff", "comma, \"quote\"\nand a line", "-0.01", ""])
+        .unwrap();
+    insert_row(&catalog, db_name, "blobs", &mut file, &["3", "", "", "", ""]).unwrap();
+
+    // Step 2: BYTEA is exported as hex, next to a header of column names
+    let rows = export_csv(&catalog, db_name, "blobs", &mut file, csv_path, &CsvOptions::default()).unwrap();
+    assert_eq!(rows, 3);
+    let exported = fs::read_to_string(csv_path).unwrap();
+    assert!(exported.starts_with("id,digest,note,amount,seen\n"), "{}", exported);
+    assert!(exported.contains("1,\\xdeadbeef,plain,12.50,2024-02-29\n"), "{}", exported);
+    assert!(exported.contains("\\x00ff"), "{}", exported);
+
+    // Step 3: Loading the export with the same options gives back the same rows
+    let mut copy = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(catalog.table_file(db_name, "copy").unwrap())
+        .unwrap();
+    load_csv_and_insert(&catalog, db_name, "copy", &mut copy, csv_path, &CsvOptions::default()).unwrap();
+    let original = scan_tuples(&catalog, db_name, "blobs", &mut file).unwrap();
+    assert_eq!(scan_tuples(&catalog, db_name, "copy", &mut copy).unwrap(), original);
+    assert_eq!(original[1][1], Value::Bytes(vec![0x00, 0xff]));
+    assert_eq!(original[2][1], Value::Null);
+
+    // Step 4: JSON Lines export keeps numbers, NULLs and JSON documents typed
+    let json_path = config.root.join("events.jsonl");
+    let json_path = json_path.to_str().unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        "events",
+        vec![
+            Column::new("id", DataType::Int),
+            Column::new("attrs", DataType::Json),
+            Column::new("digest", DataType::Bytea),
+            Column::new("amount", DataType::parse("DECIMAL(10,2)").unwrap()),
+        ],
+    )
+    .unwrap();
+    let mut events = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(catalog.table_file(db_name, "events").unwrap())
+        .unwrap();
+    insert_row(&catalog, db_name, "events", &mut events, &["7", r#"{"kind":"click","n":[1,2]}"#, "\\x01ab", "0.10"]).unwrap();
+    insert_row(&catalog, db_name, "events", &mut events, &["8", "", "", ""]).unwrap();
+    assert_eq!(export_json(&catalog, db_name, "events", &mut events, json_path).unwrap(), 2);
+    let lines: Vec<serde_json::Value> = fs::read_to_string(json_path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            serde_json::json!({"id": 7, "attrs": {"kind": "click", "n": [1, 2]}, "digest": "\\x01ab", "amount": "0.10"}),
+            serde_json::json!({"id": 8, "attrs": null, "digest": null, "amount": null}),
+        ]
+    );
+
+    // Step 5: Unknown tables cannot be exported
+    let missing = export_csv(&catalog, db_name, "missing", &mut file, csv_path, &CsvOptions::default());
+    assert!(matches!(missing, Err(RookError::NotFound { kind: "Table", .. })));
+    let missing = export_json(&catalog, db_name, "missing", &mut file, json_path);
+    assert!(matches!(missing, Err(RookError::NotFound { kind: "Table", .. })));
+
+    // Step 6: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
@@ -0,0 +1,119 @@
+use std::fs::{self, File, OpenOptions};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    alter_table_add_constraint, alter_table_drop_constraint, alter_table_rename_column,
+    create_database, create_table, drop_database, drop_table, bootstrap_catalog, load_catalog,
+    parse_constraint, Catalog, Column, Constraint, DataType, ReferentialAction,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::page::{delete_row, load_csv_and_insert, scan_rows, scan_tuples, update_row};
+use storage_manager::value::Value;
+
+const DB_NAME: &str = "test_foreign_keys_db";
+
+fn open_table(catalog: &Catalog, table_name: &str) -> File {
+    let path = catalog.table_file(DB_NAME, table_name).unwrap();
+    OpenOptions::new().read(true).write(true).open(path).unwrap()
+}
+
+fn load(catalog: &Catalog, table_name: &str, csv: &str) {
+    let csv_path = format!("test_foreign_keys_{}.csv", table_name);
+    fs::write(&csv_path, csv).unwrap();
+    load_csv_and_insert(catalog, DB_NAME, table_name, &mut open_table(catalog, table_name), &csv_path, &CsvOptions::default()).unwrap();
+    let _ = fs::remove_file(&csv_path);
+}
+
+fn add(catalog: &mut Catalog, table_name: &str, text: &str) -> bool {
+    let constraint = parse_constraint(table_name, text).unwrap();
+    alter_table_add_constraint(catalog, DB_NAME, table_name, constraint).is_ok()
+}
+
+fn rows(catalog: &Catalog, table_name: &str) -> Vec<Vec<Value>> {
+    scan_tuples(catalog, DB_NAME, table_name, &mut open_table(catalog, table_name)).unwrap()
+}
+
+#[test]
+fn test_foreign_keys() {
+    // Step 1: Foreign keys parse with an optional parent column list and ON DELETE action
+    assert_eq!(
+        parse_constraint("orders", "FOREIGN KEY (user_id) REFERENCES users ON DELETE SET NULL").unwrap(),
+        Constraint::ForeignKey {
+            name: "orders_user_id_fkey".to_string(),
+            columns: vec!["user_id".to_string()],
+            ref_table: "users".to_string(),
+            ref_columns: vec![],
+            on_delete: ReferentialAction::SetNull,
+        }
+    );
+    assert!(parse_constraint("orders", "FOREIGN KEY (user_id) users").is_err());
+
+    // Step 2: users <- orders (CASCADE) <- payments (RESTRICT), users <- reviews (SET NULL)
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_foreign_keys"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, DB_NAME);
+    create_database(&mut catalog, DB_NAME).unwrap();
+
+    let int = |name: &str| Column::new(name, DataType::Int);
+    create_table(&mut catalog, DB_NAME, "users", vec![int("id"), Column::new("name", DataType::Text)]).unwrap();
+    create_table(&mut catalog, DB_NAME, "orders", vec![int("id"), int("user_id")]).unwrap();
+    create_table(&mut catalog, DB_NAME, "reviews", vec![int("id"), int("user_id")]).unwrap();
+    create_table(&mut catalog, DB_NAME, "payments", vec![int("id"), int("order_id")]).unwrap();
+
+    assert!(!add(&mut catalog, "orders", "FOREIGN KEY (user_id) REFERENCES users"), "users has no key yet");
+    assert!(add(&mut catalog, "users", "PRIMARY KEY (id)"));
+    assert!(add(&mut catalog, "orders", "PRIMARY KEY (id)"));
+    assert!(add(&mut catalog, "orders", "FOREIGN KEY (user_id) REFERENCES users ON DELETE CASCADE"));
+    assert!(add(&mut catalog, "reviews", "FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE SET NULL"));
+    assert!(add(&mut catalog, "payments", "FOREIGN KEY (order_id) REFERENCES orders (id)"));
+
+    // Step 3: Loads skip child rows whose parent key does not exist; NULL keys are not checked
+    load(&catalog, "users", "id,name\n1,ann\n2,bob\n3,cid\n");
+    load(&catalog, "orders", "id,user_id\n10,1\n11,2\n12,9\n13,\n");
+    load(&catalog, "reviews", "id,user_id\n100,1\n101,3\n");
+    load(&catalog, "payments", "id,order_id\n1000,11\n1001,12\n");
+    assert_eq!(rows(&catalog, "orders").len(), 3);
+    assert_eq!(rows(&catalog, "payments"), vec![vec![Value::Int(1000), Value::Int(11)]]);
+
+    // Step 4: Deleting a parent cascades, sets NULL, or is restricted as a whole
+    let mut users = open_table(&catalog, "users");
+    let user_rows = scan_rows(&catalog, DB_NAME, "users", &mut users).unwrap();
+    let ((page, item), _) = user_rows[0];
+    delete_row(&catalog, DB_NAME, "users", &mut users, page, item).unwrap();
+    assert_eq!(rows(&catalog, "orders"), vec![
+        vec![Value::Int(11), Value::Int(2)],
+        vec![Value::Int(13), Value::Null],
+    ]);
+    assert!(rows(&catalog, "reviews").contains(&vec![Value::Int(100), Value::Null]));
+
+    let ((page, item), _) = user_rows[1];
+    assert!(delete_row(&catalog, DB_NAME, "users", &mut users, page, item).is_err(), "payment 1000 blocks it");
+    assert_eq!(rows(&catalog, "users").len(), 2);
+    assert_eq!(rows(&catalog, "orders").len(), 2);
+
+    // Step 5: Updates check the child's key and keep referenced parent keys
+    let (cid, _) = user_rows[2];
+    assert!(update_row(&catalog, DB_NAME, "users", &mut users, cid, &["4", "cid"]).is_err());
+    update_row(&catalog, DB_NAME, "users", &mut users, cid, &["3", "cyd"]).unwrap();
+    let mut orders = open_table(&catalog, "orders");
+    let (order, _) = scan_rows(&catalog, DB_NAME, "orders", &mut orders).unwrap()[1];
+    assert!(update_row(&catalog, DB_NAME, "orders", &mut orders, order, &["13", "7"]).is_err());
+    update_row(&catalog, DB_NAME, "orders", &mut orders, order, &["13", "3"]).unwrap();
+
+    // Step 6: Referenced tables, keys and columns stay consistent with their foreign keys
+    assert!(drop_table(&mut catalog, &mut buffer_manager, DB_NAME, "users").is_err());
+    assert!(alter_table_drop_constraint(&mut catalog, DB_NAME, "users", "users_pkey").is_err());
+    alter_table_rename_column(&mut catalog, DB_NAME, "users", "id", "uid").unwrap();
+    let orders_table = &catalog.databases[DB_NAME].tables["orders"];
+    assert!(orders_table.constraints.iter().any(|c| {
+        matches!(c, Constraint::ForeignKey { ref_columns, .. } if ref_columns == &["uid"])
+    }));
+
+    // Step 7: Clean up
+    drop_database(&mut catalog, &mut buffer_manager, DB_NAME).unwrap();
+}
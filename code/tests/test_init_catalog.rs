@@ -1,32 +1,41 @@
 use std::fs;
 use std::path::Path;
 
-use storage_manager::catalog::{init_catalog, CATALOG_FILE};
+use storage_manager::catalog::{bootstrap_catalog, create_database, load_catalog, save_catalog};
+use storage_manager::config::StorageConfig;
+use storage_manager::error::RookError;
 
 #[test]
 fn test_init_catalog() {
-    // Step 1: Ensure catalog.json doesn’t exist before test
-    if Path::new(CATALOG_FILE).exists() {
-        fs::remove_file(CATALOG_FILE).expect("Failed to remove existing catalog file");
-    }
-
-    // Step 2: Run init_catalog()
-    init_catalog();
-
-    // Step 3: Verify the file now exists
-    assert!(Path::new(CATALOG_FILE).exists(), "catalog.json was not created");
-
-    // Step 4: Read file content and check it’s valid JSON
-    let content = fs::read_to_string(CATALOG_FILE).expect("Failed to read catalog.json");
-    let parsed: serde_json::Value =
-        serde_json::from_str(&content).expect("catalog.json contains invalid JSON");
-
-    // Step 5: Verify structure is { "tables": {} }
-    assert!(
-        parsed.get("tables").is_some(),
-        "catalog.json does not contain 'tables' field"
-    );
-
-    // Step 6: Clean up (optional)
-    fs::remove_file(CATALOG_FILE).expect("Failed to clean up test catalog.json");
+    // Step 1: Ensure the data directory doesn't exist before test
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_init_catalog"));
+    let _ = fs::remove_dir_all(&config.root);
+
+    // Step 2: Run bootstrap_catalog()
+    bootstrap_catalog(&config).unwrap();
+
+    // Step 3: Verify the directories and the control file now exist, and no catalog.json was written
+    assert!(config.catalog_dir().is_dir(), "catalog directory was not created");
+    assert!(config.database_dir().is_dir(), "database directory was not created");
+    assert!(config.control_file().exists(), "control file was not created");
+    assert!(!config.catalog_file().exists(), "catalog.json should not be written any more");
+
+    // Step 4: The new catalog loads and has no databases
+    let mut catalog = load_catalog(&config).expect("new catalog does not load");
+    assert!(catalog.databases.is_empty(), "new catalog is not empty");
+
+    // Step 5: Running it again keeps the existing catalog
+    create_database(&mut catalog, "kept_db").unwrap();
+    save_catalog(&catalog).unwrap();
+    bootstrap_catalog(&config).unwrap();
+    assert!(load_catalog(&config).unwrap().databases.contains_key("kept_db"));
+
+    // Step 6: Without the control file, existing generations are not replaced by an empty catalog
+    fs::remove_file(config.control_file()).unwrap();
+    let err = bootstrap_catalog(&config).unwrap_err();
+    assert!(matches!(err, RookError::Corruption { file, page: None, .. } if file == config.control_file()));
+    assert!(!config.control_file().exists());
+
+    // Step 7: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
 }
@@ -1,6 +1,7 @@
-use std::fs::{File, remove_file};
+use std::fs::{File, OpenOptions, remove_file};
 use std::io::Read;
-use storage_manager::table::{init_table, TABLE_HEADER_SIZE};
+use storage_manager::page::PAGE_SIZE;
+use storage_manager::table::{init_table, FILE_FORMAT_VERSION, TABLE_HEADER_SIZE};
 
 const TEST_FILE: &str = "test_table_file.bin";
 
@@ -10,7 +11,13 @@ fn test_init_table() {
     let _ = remove_file(TEST_FILE);
 
     // Create a new test file
-    let mut file = File::create(TEST_FILE).expect("Failed to create test file");
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(TEST_FILE)
+        .expect("Failed to create test file");
 
     // Call API
     init_table(&mut file).expect("Failed to initialize table");
@@ -20,19 +27,30 @@ fn test_init_table() {
     let mut buffer = [1u8; TABLE_HEADER_SIZE as usize];
     file.read_exact(&mut buffer).expect("Failed to read header");
 
-    // Check header
+    // Check header: page count (header page + first data page) and format version, followed by zeros
     assert_eq!(
-        buffer,
-        [0u8; TABLE_HEADER_SIZE as usize],
-        "Table header should contain four 0 bytes"
+        u32::from_le_bytes(buffer[0..4].try_into().unwrap()),
+        2,
+        "Table header should start with a page count of 2"
+    );
+    assert_eq!(
+        u32::from_le_bytes(buffer[4..8].try_into().unwrap()),
+        FILE_FORMAT_VERSION,
+        "Table header should hold the file format version"
+    );
+    assert!(
+        buffer[8..].iter().all(|&b| b == 0),
+        "Rest of the table header should be zeroed"
     );
 
     // Check file size
     let metadata = file.metadata().expect("Failed to get file metadata");
     assert_eq!(
         metadata.len(),
-        TABLE_HEADER_SIZE as u64,
-        "File size should be exactly {} bytes (table header)",
-        TABLE_HEADER_SIZE
+        TABLE_HEADER_SIZE as u64 + PAGE_SIZE as u64,
+        "File size should be {} bytes (table header + first page)",
+        TABLE_HEADER_SIZE as usize + PAGE_SIZE
     );
+
+    let _ = remove_file(TEST_FILE);
 }
@@ -0,0 +1,64 @@
+use storage_manager::catalog::{Column, DataType, Table};
+use storage_manager::expr::Expr;
+use storage_manager::json::{json_extract_path, json_extract_path_text, json_typeof};
+use storage_manager::tuple::{decode_tuple, encode_row};
+use storage_manager::value::{decode_value, parse_value, Value};
+
+#[test]
+fn test_json() {
+    // Step 1: JSON and JSONB name the same type; invalid documents are rejected
+    assert_eq!(DataType::parse("jsonb"), Ok(DataType::Json));
+    assert!(parse_value(DataType::Json, "{\"a\": }").is_err());
+    assert!(parse_value(DataType::Json, "").is_err());
+
+    // Step 2: Documents round-trip through a tuple in binary form
+    let table = Table::new(vec![
+        Column::new("id", DataType::Int),
+        Column::new("attrs", DataType::Json),
+    ]);
+    let raw = r#"{"user": {"name": "ada", "tags": ["x", "y"]}, "n": -3, "big": 18446744073709551615,
+                  "pi": 3.25, "ok": true, "none": null, "emoji": "snow ☃"}"#;
+    let tuple = encode_row(&table, &["1", raw]).unwrap();
+    let values = decode_tuple(&table, &tuple).unwrap();
+    assert_eq!(values[1], parse_value(DataType::Json, raw).unwrap());
+    assert_eq!(
+        values[1].to_string(),
+        r#"{"big":18446744073709551615,"emoji":"snow ☃","n":-3,"none":null,"ok":true,"pi":3.25,"user":{"name":"ada","tags":["x","y"]}}"#
+    );
+
+    // Corrupt binary data is reported instead of panicking
+    let mut corrupt = tuple.clone();
+    let last = corrupt.len() - 1;
+    corrupt[last] = 0xFF;
+    corrupt.truncate(last - 3);
+    assert!(decode_value(DataType::Json, &corrupt, &mut 8).is_err());
+
+    // Step 3: Path extraction and type inspection
+    let doc = &values[1];
+    assert_eq!(json_extract_path(doc, &["user", "tags", "1"]).to_string(), "\"y\"");
+    assert_eq!(json_extract_path_text(doc, &["user", "name"]), Value::Text("ada".to_string()));
+    assert_eq!(json_extract_path_text(doc, &["n"]), Value::Text("-3".to_string()));
+    assert_eq!(json_extract_path(doc, &["user", "missing"]), Value::Null);
+    assert_eq!(json_extract_path(doc, &["n", "0"]), Value::Null);
+    assert_eq!(json_extract_path_text(doc, &["none"]), Value::Null);
+
+    assert_eq!(json_typeof(doc), Some("object"));
+    assert_eq!(json_typeof(&json_extract_path(doc, &["user", "tags"])), Some("array"));
+    assert_eq!(json_typeof(&json_extract_path(doc, &["pi"])), Some("number"));
+    assert_eq!(json_typeof(&json_extract_path(doc, &["none"])), Some("null"));
+    assert_eq!(json_typeof(&Value::Int(1)), None);
+
+    // Step 4: The accessors are callable from expressions, with keys and array indexes as path elements
+    let columns = vec![Column::new("attrs", DataType::Json)];
+    let row = std::slice::from_ref(doc);
+    let check = Expr::parse("json_typeof(attrs) = 'object' AND json_extract_path_text(attrs, 'user', 'name') = 'ada'")
+        .unwrap();
+    assert_eq!(check.eval(&columns, row).unwrap(), Value::Boolean(true));
+    let tag = Expr::parse("JSON_EXTRACT_PATH_TEXT(attrs, 'user', 'tags', 1)").unwrap();
+    assert_eq!(tag.eval(&columns, row).unwrap(), Value::Text("y".to_string()));
+    let tags = Expr::parse("JSON_EXTRACT_PATH(attrs, 'user', 'tags')").unwrap();
+    assert_eq!(json_typeof(&tags.eval(&columns, row).unwrap()), Some("array"));
+    assert_eq!(tag.eval(&columns, &[Value::Null]).unwrap(), Value::Null);
+    assert!(Expr::parse("JSON_TYPEOF(attrs, 'user')").unwrap().eval(&columns, row).is_err());
+    assert!(Expr::parse("JSON_EXTRACT_PATH('{}', 'user')").unwrap().eval(&columns, row).is_err());
+}
@@ -1,17 +1,19 @@
 use std::fs;
 use std::path::Path;
 
-use storage_manager::catalog::{init_catalog, load_catalog, CATALOG_FILE, Catalog};
+use storage_manager::catalog::{bootstrap_catalog, load_catalog, Catalog};
+use storage_manager::config::StorageConfig;
 
 #[test]
 fn test_load_catalog() {
-    // Step 1: Ensure a valid catalog file exists before loading
-    if !Path::new(CATALOG_FILE).exists() {
-        init_catalog(); // create catalog.json if missing
+    // Step 1: Ensure the catalog system tables exist before loading
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_load_catalog"));
+    if !config.control_file().exists() {
+        bootstrap_catalog(&config).unwrap(); // create the system tables if missing
     }
 
     // Step 2: Run load_catalog()
-    let catalog = load_catalog();
+    let catalog = load_catalog(&config).unwrap();
 
     // Step 3: Verify it returns a valid Catalog struct
     // (Just ensuring type correctness and successful deserialization)
@@ -21,5 +23,5 @@ fn test_load_catalog() {
     );
 
     // Step 4: Clean up (optional)
-    fs::remove_file(CATALOG_FILE).expect("Failed to clean up test catalog.json");
+    fs::remove_dir_all(&config.root).expect("Failed to clean up test catalog");
 }
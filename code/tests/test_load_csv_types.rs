@@ -0,0 +1,73 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    create_database, create_table, drop_database, bootstrap_catalog, load_catalog, Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::page::{load_csv_and_insert, scan_tuples};
+use storage_manager::value::Value;
+
+#[test]
+fn test_load_csv_types() {
+    let db_name = "test_load_csv_types_db";
+    let table_name = "measurements";
+    let csv_path = "test_load_csv_types.csv";
+
+    // Step 1: Create a table using every scalar type
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_load_csv_types"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        table_name,
+        vec![
+            Column::new("id", DataType::BigInt),
+            Column::new("sensor", DataType::SmallInt),
+            Column::new("reading", DataType::Float),
+            Column::new("precise", DataType::Double),
+            Column::new("ok", DataType::Boolean),
+            Column::new("cost", DataType::Decimal { precision: 10, scale: 2 }),
+        ],
+    ).unwrap();
+
+    // Step 2: Load a CSV; the row with an invalid BOOLEAN is skipped
+    fs::write(
+        csv_path,
+        "id,sensor,reading,precise,ok,cost\n\
+         5000000000,7,21.5,0.1,true,19.99\n\
+         -1,-2,-0.25,1e-10,f,0.005\n\
+         3,3,3,3,perhaps,3\n",
+    )
+    .unwrap();
+
+    let table_path = catalog.table_file(db_name, table_name).unwrap();
+    let mut file = OpenOptions::new().read(true).write(true).open(&table_path).unwrap();
+    load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &CsvOptions::default()).unwrap();
+
+    // Step 3: Scan and compare the decoded values and their printed form
+    let rows = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap();
+    assert_eq!(rows.len(), 2, "Invalid row should have been skipped");
+
+    assert_eq!(rows[0][0], Value::BigInt(5_000_000_000));
+    assert_eq!(rows[0][1], Value::SmallInt(7));
+    assert_eq!(rows[0][2], Value::Float(21.5));
+    assert_eq!(rows[0][3], Value::Double(0.1));
+    assert_eq!(rows[0][4], Value::Boolean(true));
+    assert_eq!(rows[0][5].to_string(), "19.99");
+
+    let printed: Vec<String> = rows[1].iter().map(|v| v.to_string()).collect();
+    assert_eq!(printed, vec!["-1", "-2", "-0.25", "1e-10", "false", "0.01"]);
+
+    // Step 4: Clean up
+    let _ = fs::remove_file(csv_path);
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+}
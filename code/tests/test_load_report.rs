@@ -0,0 +1,98 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    alter_table_add_constraint, bootstrap_catalog, create_database, create_table, load_catalog,
+    parse_constraint, Column, DataType, StorageStrategy,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::{CsvOptions, LoadMode, RejectedRow};
+use storage_manager::error::RookError;
+use storage_manager::page::{load_csv_and_insert, scan_tuples};
+use storage_manager::value::Value;
+
+#[test]
+fn test_load_report() {
+    let db_name = "shop";
+    let table_name = "items";
+
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_load_report"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    create_database(&mut catalog, db_name).unwrap();
+    let mut id = Column::new("id", DataType::Int);
+    id.not_null = true;
+    create_table(&mut catalog, db_name, table_name, vec![id, Column::new("name", DataType::Text)]).unwrap();
+
+    let csv_path = config.root.join("items.csv");
+    let csv_path = csv_path.to_str().unwrap();
+    fs::write(csv_path, "id,name\n1,pen\ntwo,\"cup, blue\"\n3,ink\n,lamp\n5,box\n").unwrap();
+    let table_path = catalog.table_file(db_name, table_name).unwrap();
+    let mut file = OpenOptions::new().read(true).write(true).open(&table_path).unwrap();
+
+    // Step 1: A permissive load reports every rejected row; unparsable INTs are rejected, not stored as 0
+    let reject_path = config.root.join("items.rejected.csv");
+    let options = CsvOptions { reject_file: Some(reject_path.clone()), ..CsvOptions::default() };
+    let report = load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &options).unwrap();
+    assert_eq!(report.rows_read, 5);
+    assert_eq!(report.rows_inserted, 3);
+    assert_eq!(report.rejected.iter().map(|r| r.row).collect::<Vec<_>>(), vec![2, 4]);
+    assert!(report.rejected[0].reason.contains("'two' is not a valid INT"), "{}", report.rejected[0].reason);
+    assert!(report.rejected[1].reason.contains("NOT NULL"), "{}", report.rejected[1].reason);
+    let rows = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap();
+    let ids: Vec<&Value> = rows.iter().map(|row| &row[0]).collect();
+    assert_eq!(ids, vec![&Value::Int(1), &Value::Int(3), &Value::Int(5)]);
+
+    // Step 2: The reject file holds the header and the rejected records, ready to be loaded again
+    assert_eq!(fs::read_to_string(&reject_path).unwrap(), "id,name\ntwo,\"cup, blue\"\n,lamp\n");
+
+    // Step 3: A strict load aborts on the first rejected row
+    let options = CsvOptions { mode: LoadMode::Strict, ..CsvOptions::default() };
+    let err = load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &options).unwrap_err();
+    let RookError::LoadAborted(report) = err else { panic!("expected LoadAborted, got {}", err) };
+    assert_eq!((report.rows_read, report.rejected.len()), (2, 1));
+
+    // Step 4: A permissive load aborts once more than max_errors rows are rejected
+    let options = CsvOptions { max_errors: Some(1), ..CsvOptions::default() };
+    let mut buffer_manager = BufferManager::new();
+    let err = buffer_manager.load_csv_to_buffer(&catalog, db_name, table_name, csv_path, &options).unwrap_err();
+    let RookError::LoadAborted(report) = err else { panic!("expected LoadAborted, got {}", err) };
+    assert_eq!(report.rejected.last(), Some(&RejectedRow { row: 4, reason: "column 'id' is NOT NULL".to_string() }));
+    assert_eq!(buffer_manager.loaded_table, None, "rows of the aborted load are dropped from the buffer");
+
+    // Step 5: A row rejected after its checks (too large for a page) leaves no key behind:
+    // the corrected row 2 loads, and row 4 cannot reference the rejected row 3
+    let mut note = Column::new("note", DataType::Text);
+    note.storage = StorageStrategy::Plain;
+    let columns = vec![Column::new("id", DataType::Int), Column::new("parent", DataType::Int), note];
+    for (tree, use_buffer) in [("tree", false), ("buffered_tree", true)] {
+        create_table(&mut catalog, db_name, tree, columns.clone()).unwrap();
+        for constraint in ["PRIMARY KEY (id)", "FOREIGN KEY (parent) REFERENCES {} (id)"] {
+            let constraint = parse_constraint(tree, &constraint.replace("{}", tree)).unwrap();
+            alter_table_add_constraint(&mut catalog, db_name, tree, constraint).unwrap();
+        }
+        let huge = "x".repeat(9000);
+        fs::write(csv_path, format!("id,parent,note
+1,,a
+2,,{huge}
+2,1,b
+3,,{huge}
+4,3,c
+")).unwrap();
+        let mut file = OpenOptions::new().read(true).write(true).open(catalog.table_file(db_name, tree).unwrap()).unwrap();
+        let report = if use_buffer {
+            BufferManager::new().load_csv_to_buffer(&catalog, db_name, tree, csv_path, &CsvOptions::default()).unwrap()
+        } else {
+            load_csv_and_insert(&catalog, db_name, tree, &mut file, csv_path, &CsvOptions::default()).unwrap()
+        };
+        assert_eq!(report.rejected.iter().map(|r| r.row).collect::<Vec<_>>(), vec![2, 4, 5], "{}", tree);
+        assert!(report.rejected[2].reason.contains("FOREIGN KEY"), "{}", report.rejected[2].reason);
+        let ids: Vec<Value> = scan_tuples(&catalog, db_name, tree, &mut file).unwrap().into_iter().map(|row| row[0].clone()).collect();
+        assert_eq!(ids, vec![Value::Int(1), Value::Int(2)]);
+    }
+
+    // Step 6: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    bootstrap_catalog, create_database, create_table, load_catalog, Column, DataType,
+};
+use storage_manager::config::{log_level_from_args, StorageConfig};
+use storage_manager::csv_import::CsvOptions;
+use tracing::level_filters::LevelFilter;
+
+/// Collects everything the subscriber writes.
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_log_level() {
+    // Step 1: The level comes from --log-level in either form, info by default
+    let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+    assert_eq!(log_level_from_args(&args(&[])).unwrap(), LevelFilter::INFO);
+    assert_eq!(log_level_from_args(&args(&["--log-level", "debug"])).unwrap(), LevelFilter::DEBUG);
+    assert_eq!(
+        log_level_from_args(&args(&["--data-dir", "/srv/rook", "--log-level=off"])).unwrap(),
+        LevelFilter::OFF
+    );
+    assert!(log_level_from_args(&args(&["--log-level", "loud"])).is_err());
+    assert!(log_level_from_args(&args(&["--log-level"])).is_err());
+
+    // Step 2: Set up a table and a CSV with one bad row
+    let tmp = Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_log_level");
+    let _ = fs::remove_dir_all(&tmp);
+    let config = StorageConfig::new(&tmp);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    create_database(&mut catalog, "shop").unwrap();
+    create_table(&mut catalog, "shop", "items", vec![Column::new("id", DataType::Int)]).unwrap();
+    let csv_path = tmp.join("items.csv");
+    fs::write(&csv_path, "id\n1\n2,extra\n3\n").unwrap();
+
+    // Step 3: The load reports through events inside a csv_load span, not on stdout
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(LevelFilter::INFO)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    tracing::subscriber::with_default(subscriber, || {
+        let mut buffer_manager = BufferManager::new();
+        buffer_manager
+            .load_csv_to_buffer(&catalog, "shop", "items", csv_path.to_str().unwrap(), &CsvOptions::default())
+            .unwrap();
+    });
+    let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+    assert!(output.contains("WARN"), "{}", output);
+    assert!(output.contains("csv_load"), "{}", output);
+    assert!(output.contains("row=2"), "{}", output);
+    assert!(output.contains("Loaded 2 rows"), "{}", output);
+    assert!(!output.contains("Flushed"), "debug events are below the level: {}", output);
+
+    // Step 4: Clean up
+    fs::remove_dir_all(&tmp).unwrap();
+}
@@ -0,0 +1,88 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    alter_table_add_column, create_database, create_table, drop_database, bootstrap_catalog,
+    load_catalog, Column, DataType, Table,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::page::{load_csv_and_insert, scan_tuples};
+use storage_manager::tuple::{decode_tuple, encode_tuple, TUPLE_HEADER_SIZE};
+use storage_manager::value::Value;
+
+#[test]
+fn test_null_bitmap() {
+    // Step 1: NULLs survive an encode/decode round trip and take no value bytes
+    let table = Table::new((0..10).map(|i| Column::new(&format!("c{}", i), DataType::Int)).collect());
+    let mut values: Vec<Value> = (0..10).map(Value::Int).collect();
+    values[0] = Value::Null;
+    values[9] = Value::Null;
+    let bytes = encode_tuple(&table, &values);
+    assert_eq!(bytes.len(), TUPLE_HEADER_SIZE + 2 + 8 * 4, "2 bitmap bytes + 8 INTs");
+    assert_eq!(decode_tuple(&table, &bytes).unwrap(), values);
+
+    // Tuples without NULLs carry no bitmap
+    let full: Vec<Value> = (0..10).map(Value::Int).collect();
+    assert_eq!(encode_tuple(&table, &full).len(), TUPLE_HEADER_SIZE + 10 * 4);
+    assert_eq!(Value::Null.to_string(), "NULL");
+
+    // Step 2: Older catalog entries default to nullable columns
+    let column: Column = serde_json::from_str(r#"{ "name": "id", "data_type": "INT" }"#).unwrap();
+    assert!(!column.not_null);
+
+    // Step 3: Empty CSV fields load as NULL, and rows leaving a NOT NULL column empty are skipped
+    let db_name = "test_null_bitmap_db";
+    let table_name = "people";
+    let csv_path = "test_null_bitmap.csv";
+
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_null_bitmap"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    let mut id = Column::new("id", DataType::Int);
+    id.not_null = true;
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        table_name,
+        vec![id, Column::new("age", DataType::Int), Column::new("name", DataType::Text)],
+    ).unwrap();
+
+    fs::write(csv_path, "id,age,name\n1,0,alice\n2,,\n,30,nobody\n3,40,\n").unwrap();
+    let table_path = catalog.table_file(db_name, table_name).unwrap();
+    let mut file = OpenOptions::new().read(true).write(true).open(&table_path).unwrap();
+    load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &CsvOptions::default()).unwrap();
+
+    let rows = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::Int(1), Value::Int(0), Value::Text("alice".to_string())],
+            vec![Value::Int(2), Value::Null, Value::Null],
+            vec![Value::Int(3), Value::Int(40), Value::Null],
+        ]
+    );
+
+    // Step 4: Added columns read as NULL for existing rows; NOT NULL needs a default
+    let mut score = Column::new("score", DataType::Int);
+    score.not_null = true;
+    assert!(alter_table_add_column(&mut catalog, db_name, table_name, score).is_err());
+    alter_table_add_column(
+        &mut catalog,
+        db_name,
+        table_name,
+        Column::new("email", DataType::Text)
+    ).unwrap();
+    let rows = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap();
+    assert!(rows.iter().all(|row| row[3] == Value::Null));
+
+    // Step 5: Clean up
+    let _ = fs::remove_file(csv_path);
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+}
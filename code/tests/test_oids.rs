@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    alter_table_add_column, alter_table_rename, bootstrap_catalog, create_database, create_table,
+    drop_table, load_catalog, parse_constraint, validate_identifier, Column, DataType, FIRST_OID,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::error::RookError;
+
+#[test]
+fn test_oids() {
+    let db_name = "test_oids_db";
+
+    // Step 1: Databases and tables get increasing OIDs and files named by them
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_oids"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(&mut catalog, db_name, "orders", vec![Column::new("id", DataType::Int)]).unwrap();
+    create_table(&mut catalog, db_name, "items", vec![Column::new("id", DataType::Int)]).unwrap();
+
+    let db_oid = catalog.databases[db_name].oid;
+    let orders_oid = catalog.databases[db_name].tables["orders"].oid;
+    let items_oid = catalog.databases[db_name].tables["items"].oid;
+    assert_eq!((db_oid, orders_oid, items_oid), (FIRST_OID, FIRST_OID + 1, FIRST_OID + 2));
+    let orders_path = config.root.join("base").join(db_oid.to_string()).join(orders_oid.to_string());
+    assert_eq!(catalog.table_file(db_name, "orders").unwrap(), orders_path);
+    assert!(orders_path.exists());
+
+    // Step 2: OIDs survive a reload and are never handed out twice, even after a drop
+    drop_table(&mut catalog, &mut buffer_manager, db_name, "items").unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    assert_eq!(catalog.databases[db_name].tables["orders"].oid, orders_oid);
+    create_table(&mut catalog, db_name, "returns", vec![Column::new("id", DataType::Int)]).unwrap();
+    assert_eq!(catalog.databases[db_name].tables["returns"].oid, items_oid + 1);
+
+    // Failed creates do not use up an OID
+    let duplicate = create_table(&mut catalog, db_name, "returns", vec![Column::new("id", DataType::Int)]);
+    assert!(matches!(duplicate, Err(RookError::AlreadyExists { .. })));
+    assert!(create_table(&mut catalog, "missing_db", "lost", vec![Column::new("id", DataType::Int)]).is_err());
+    assert_eq!(catalog.next_oid, items_oid + 2);
+
+    // Step 3: Renaming a table keeps its file
+    alter_table_rename(&mut catalog, &mut buffer_manager, db_name, "orders", "purchases").unwrap();
+    assert_eq!(catalog.table_file(db_name, "purchases").unwrap(), orders_path);
+
+    // Step 4: Names that are not plain identifiers are rejected everywhere
+    for name in ["", "a/b", "..", "1st", "has space", "ünïcode", &"x".repeat(64)] {
+        assert!(validate_identifier("Table", name).is_err(), "'{}' was accepted", name);
+    }
+    assert!(validate_identifier("Table", &"x".repeat(63)).is_ok());
+    assert!(validate_identifier("Table", "_order_items2").is_ok());
+
+    assert!(create_database(&mut catalog, "../escape").is_err());
+    let bad_table = create_table(&mut catalog, db_name, "a/b", vec![Column::new("id", DataType::Int)]);
+    assert!(matches!(bad_table, Err(RookError::InvalidName(_))));
+    let bad_column = create_table(&mut catalog, db_name, "bad_column", vec![Column::new("a b", DataType::Int)]);
+    assert!(matches!(bad_column, Err(RookError::InvalidName(_))));
+    assert!(alter_table_rename(&mut catalog, &mut buffer_manager, db_name, "purchases", "..").is_err());
+    assert!(alter_table_add_column(&mut catalog, db_name, "purchases", Column::new("x;y", DataType::Int)).is_err());
+    assert!(parse_constraint("purchases", "CONSTRAINT bad-name CHECK (id > 0)").is_err());
+
+    let tables = &load_catalog(&config).unwrap().databases[db_name].tables;
+    assert_eq!(tables.len(), 2, "only purchases and returns exist");
+    assert!(!config.database_dir().join("..").join("escape").exists());
+
+    // Step 5: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
@@ -0,0 +1,148 @@
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::Duration;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::bulk_load::{load_csv_parallel, CHUNK_ROWS};
+use storage_manager::catalog::{
+    alter_table_add_constraint, bootstrap_catalog, create_database, create_table, load_catalog,
+    parse_constraint, Catalog, Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::{CsvOptions, LoadMode};
+use storage_manager::error::RookError;
+use storage_manager::page::{page_count, scan_tuples, PAGE_SIZE};
+use storage_manager::value::Value;
+
+const DB_NAME: &str = "shop";
+
+fn create_items(catalog: &mut Catalog, table_name: &str) {
+    let columns = vec![Column::new("id", DataType::Int), Column::new("name", DataType::Text)];
+    create_table(catalog, DB_NAME, table_name, columns).unwrap();
+    let primary_key = parse_constraint(table_name, "PRIMARY KEY (id)").unwrap();
+    alter_table_add_constraint(catalog, DB_NAME, table_name, primary_key).unwrap();
+}
+
+fn rows(catalog: &Catalog, table_name: &str) -> Vec<Vec<Value>> {
+    let mut file = File::open(catalog.table_file(DB_NAME, table_name).unwrap()).unwrap();
+    scan_tuples(catalog, DB_NAME, table_name, &mut file).unwrap()
+}
+
+#[test]
+fn test_parallel_load() {
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_parallel_load"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    create_database(&mut catalog, DB_NAME).unwrap();
+    create_items(&mut catalog, "parallel");
+    create_items(&mut catalog, "serial");
+
+    // Step 1: A file of several chunks, with a bad INT in the second chunk and a duplicate key in the third
+    let total = CHUNK_ROWS * 2 + 500;
+    let mut csv = String::from("id,name\n");
+    for i in 1..=total {
+        match i {
+            12_345 => csv.push_str("twelve,bad\n"),
+            20_100 => csv.push_str("7,duplicate\n"),
+            _ => csv.push_str(&format!("{},item {}\n", i, i)),
+        }
+    }
+    let csv_path = config.root.join("items.csv");
+    fs::write(&csv_path, &csv).unwrap();
+    let csv_path = csv_path.to_str().unwrap();
+
+    // Step 2: The parallel load stores the rows in file order and reports like the serial one
+    let reject_path = config.root.join("items.rejected.csv");
+    let options = CsvOptions { reject_file: Some(reject_path.clone()), ..CsvOptions::default() };
+    let report = load_csv_parallel(&catalog, DB_NAME, "parallel", csv_path, &options, 4).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let serial = buffer_manager.load_csv_to_buffer(&catalog, DB_NAME, "serial", csv_path, &CsvOptions::default()).unwrap();
+    assert_eq!(report.to_string(), serial.to_string());
+    assert_eq!((report.rows_read, report.rows_inserted), (total, total - 2));
+    assert_eq!(report.rejected.iter().map(|r| r.row).collect::<Vec<_>>(), vec![12_345, 20_100]);
+    assert!(report.rejected[1].reason.contains("duplicate key"), "{}", report.rejected[1].reason);
+    assert_eq!((report.bytes_read, serial.bytes_read), (csv.len() as u64, csv.len() as u64));
+    let loaded = rows(&catalog, "parallel");
+    assert_eq!(loaded, rows(&catalog, "serial"));
+    assert_eq!(loaded[0], vec![Value::Int(1), Value::Text("item 1".to_string())]);
+    assert_eq!(fs::read_to_string(&reject_path).unwrap(), "id,name\ntwelve,bad\n7,duplicate\n");
+
+    // Step 3: Throughput is derived from the rows and bytes read
+    assert_eq!(report.rows_per_sec(Duration::from_secs(2)), total as f64 / 2.0);
+    assert!(report.mb_per_sec(Duration::from_millis(10)) > 0.0);
+
+    // Step 4: The table file holds exactly the pages counted in its header
+    let table_path = catalog.table_file(DB_NAME, "parallel").unwrap();
+    let committed = fs::read(&table_path).unwrap();
+    let pages = page_count(&mut File::open(&table_path).unwrap()).unwrap();
+    assert_eq!(committed.len(), pages as usize * PAGE_SIZE);
+
+    // Step 5: A strict load aborts on the first rejected row and leaves the table untouched
+    let strict = CsvOptions { mode: LoadMode::Strict, ..CsvOptions::default() };
+    fs::write(csv_path, format!("id,name\n{},new\n1,again\n", total + 1)).unwrap();
+    let err = load_csv_parallel(&catalog, DB_NAME, "parallel", csv_path, &strict, 2).unwrap_err();
+    let RookError::LoadAborted(aborted) = err else { panic!("expected LoadAborted, got {}", err) };
+    assert_eq!(aborted.rejected.len(), 1);
+    assert_eq!(fs::read(&table_path).unwrap(), committed);
+
+    // Step 6: Keys are checked in file order, so with duplicates across chunks the first row always
+    // wins and the same rows are rejected on every run, whatever the workers' scheduling
+    let distinct = CHUNK_ROWS + 7;
+    let total = CHUNK_ROWS * 3;
+    let mut csv = String::from("id,name\n");
+    for i in 0..total {
+        csv.push_str(&format!("{},row {}\n", i % distinct, i + 1));
+    }
+    fs::write(csv_path, &csv).unwrap();
+    let expected: Vec<Vec<Value>> =
+        (0..distinct).map(|i| vec![Value::Int(i as i32), Value::Text(format!("row {}", i + 1))]).collect();
+    for run in 0..3 {
+        let table_name = format!("duplicates_{}", run);
+        create_items(&mut catalog, &table_name);
+        let report = load_csv_parallel(&catalog, DB_NAME, &table_name, csv_path, &CsvOptions::default(), 8).unwrap();
+        assert_eq!(report.rejected.iter().map(|r| r.row).collect::<Vec<_>>(), (distinct + 1..=total).collect::<Vec<_>>());
+        assert_eq!(rows(&catalog, &table_name), expected, "run {}", run);
+    }
+
+    // Step 7: Workers compress and move large values out of line themselves; rows rejected after
+    // that leave no tuple behind
+    create_items(&mut catalog, "documents");
+    create_items(&mut catalog, "documents_serial");
+    let mut noise = 0x9e37_79b9_7f4a_7c15u64;
+    let mut incompressible = |len: usize| -> String {
+        (0..len)
+            .map(|_| {
+                noise ^= noise << 13;
+                noise ^= noise >> 7;
+                noise ^= noise << 17;
+                char::from(b'a' + (noise % 26) as u8)
+            })
+            .collect()
+    };
+    let mut csv = String::from("id,name\n");
+    for i in 0..200 {
+        let body = match i % 3 {
+            0 => incompressible(PAGE_SIZE * 2), // Out of line
+            1 => "z".repeat(PAGE_SIZE * 4),     // Compressed inline
+            _ => format!("short {}", i),
+        };
+        let id = if i == 150 { 3 } else { i }; // Duplicate of a row stored out of line
+        csv.push_str(&format!("{},{}\n", id, body));
+    }
+    fs::write(csv_path, &csv).unwrap();
+    let report = load_csv_parallel(&catalog, DB_NAME, "documents", csv_path, &CsvOptions::default(), 4).unwrap();
+    let serial = buffer_manager
+        .load_csv_to_buffer(&catalog, DB_NAME, "documents_serial", csv_path, &CsvOptions::default())
+        .unwrap();
+    assert_eq!(report.to_string(), serial.to_string());
+    assert_eq!(report.rejected.iter().map(|r| r.row).collect::<Vec<_>>(), vec![151]);
+    let documents = rows(&catalog, "documents");
+    assert_eq!(documents, rows(&catalog, "documents_serial"));
+    assert_eq!(documents.len(), 199);
+    assert_eq!(documents[1][1], Value::Text("z".repeat(PAGE_SIZE * 4)));
+    assert!(catalog.toast_file(DB_NAME, "documents").unwrap().exists());
+
+    // Step 8: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
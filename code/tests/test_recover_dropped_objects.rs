@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    create_database, create_table, drop_database, bootstrap_catalog, load_catalog,
+    recover_dropped_objects, Column, DataType,
+};
+use storage_manager::config::{tombstone_path, StorageConfig};
+
+#[test]
+fn test_recover_dropped_objects() {
+    let db_name = "test_recover_db";
+
+    // Step 1: Create a database with one table
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_recover_dropped_objects"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        "kept",
+        vec![Column::new("id", DataType::Int)],
+    ).unwrap();
+
+    // Step 2: Simulate a crash after the rename of a drop that never reached the catalog
+    let kept_path = catalog.table_file(db_name, "kept").unwrap();
+    let kept_tombstone = tombstone_path(&kept_path);
+    fs::rename(&kept_path, &kept_tombstone).unwrap();
+
+    // Step 3: Simulate a crash after the catalog commit of a drop (tombstones remain)
+    let db_oid = catalog.databases[db_name].oid;
+    let gone_tombstone = tombstone_path(&config.table_file(db_oid, catalog.next_oid));
+    fs::write(&gone_tombstone, b"orphan").unwrap();
+
+    let orphan_tombstone = tombstone_path(&config.table_dir(catalog.next_oid + 1));
+    fs::create_dir_all(&orphan_tombstone).unwrap();
+
+    // Step 4: Recover
+    recover_dropped_objects(&catalog);
+
+    // Step 5: Uncommitted drop is rolled back, committed drops are finished
+    assert!(Path::new(&kept_path).exists(), "Uncommitted drop was not rolled back");
+    assert!(!Path::new(&kept_tombstone).exists());
+    assert!(!Path::new(&gone_tombstone).exists(), "Orphaned table file was not removed");
+    assert!(!Path::new(&orphan_tombstone).exists(), "Orphaned database was not removed");
+
+    // Step 6: Clean up
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+}
@@ -0,0 +1,75 @@
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+use storage_manager::catalog::{
+    alter_table_add_constraint, bootstrap_catalog, create_database, create_table, load_catalog,
+    parse_constraint, Column, DataType, StorageStrategy,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::disk::read_page;
+use storage_manager::error::RookError;
+use storage_manager::page::{insert_row, scan_tuples, Page, PAGE_SIZE};
+use storage_manager::tuple::decode_tuple;
+
+#[test]
+fn test_rook_error() {
+    let db_name = "test_rook_error_db";
+    let table_name = "users";
+
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_rook_error"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+
+    // Step 1: Missing and duplicate objects
+    let missing = create_table(&mut catalog, db_name, table_name, vec![Column::new("id", DataType::Int)]);
+    assert!(matches!(missing, Err(RookError::NotFound { kind: "Database", .. })));
+    create_database(&mut catalog, db_name).unwrap();
+    let duplicate = create_database(&mut catalog, db_name);
+    assert!(matches!(duplicate, Err(RookError::AlreadyExists { kind: "Database", ref name }) if name == db_name));
+    assert!(matches!(create_database(&mut catalog, "no-dashes"), Err(RookError::InvalidName(_))));
+
+    let mut email = Column::new("email", DataType::Text);
+    email.not_null = true;
+    create_table(&mut catalog, db_name, table_name, vec![Column::new("id", DataType::Int), email]).unwrap();
+    let key = parse_constraint(table_name, "PRIMARY KEY (id)").unwrap();
+    alter_table_add_constraint(&mut catalog, db_name, table_name, key).unwrap();
+    assert!(matches!(catalog.table_file(db_name, "orders"), Err(RookError::NotFound { kind: "Table", .. })));
+
+    // Step 2: Rows that do not fit the schema or break a constraint
+    let table_path = catalog.table_file(db_name, table_name).unwrap();
+    let mut file = OpenOptions::new().read(true).write(true).open(&table_path).unwrap();
+    insert_row(&catalog, db_name, table_name, &mut file, &["1", "a@example.com"]).unwrap();
+    let too_many = insert_row(&catalog, db_name, table_name, &mut file, &["2", "b@example.com", "x"]);
+    assert!(matches!(too_many, Err(RookError::SchemaMismatch(_))));
+    let not_an_int = insert_row(&catalog, db_name, table_name, &mut file, &["two", "b@example.com"]);
+    assert!(matches!(not_an_int, Err(RookError::SchemaMismatch(_))));
+    let duplicate_key = insert_row(&catalog, db_name, table_name, &mut file, &["1", "c@example.com"]);
+    assert!(matches!(duplicate_key, Err(RookError::ConstraintViolation(_))));
+    let null_email = insert_row(&catalog, db_name, table_name, &mut file, &["3", ""]);
+    assert!(matches!(null_email, Err(RookError::ConstraintViolation(_))));
+    let mut plain = Column::new("body", DataType::Text);
+    plain.storage = StorageStrategy::Plain; // Never compressed or moved out of line
+    create_table(&mut catalog, db_name, "notes", vec![plain]).unwrap();
+    let notes_path = catalog.table_file(db_name, "notes").unwrap();
+    let mut notes = OpenOptions::new().read(true).write(true).open(notes_path).unwrap();
+    let too_large = insert_row(&catalog, db_name, "notes", &mut notes, &[&"x".repeat(PAGE_SIZE)]);
+    assert!(matches!(too_large, Err(RookError::TupleTooLarge(_))));
+
+    // Step 3: Pages past the end of the file
+    let mut page = Page::new();
+    assert!(matches!(read_page(&mut file, &mut page, 7), Err(RookError::NotFound { kind: "Page", .. })));
+
+    // Step 4: A tuple with an unknown schema version is corruption of its page
+    let tuple_len = 4 + 4 + 4 + "a@example.com".len() as u64; // header + INT + varlen TEXT
+    file.seek(SeekFrom::Start(2 * PAGE_SIZE as u64 - tuple_len)).unwrap();
+    file.write_all(&u16::MAX.to_le_bytes()).unwrap();
+    let err = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap_err();
+    assert!(matches!(&err, RookError::Corruption { file, page: Some(1), .. } if *file == table_path), "{}", err);
+    let table = &catalog.databases[db_name].tables[table_name];
+    assert!(matches!(decode_tuple(table, &[1, 0]), Err(RookError::MalformedTuple(_))));
+
+    // Step 5: Clean up
+    fs::remove_dir_all(&config.root).unwrap();
+}
@@ -1,43 +1,48 @@
 use std::fs;
+use std::os::unix::fs::MetadataExt;
 use std::path::Path;
 
 use storage_manager::catalog::{
-    init_catalog, load_catalog, save_catalog, CATALOG_FILE, Database, Table, Column,
+    alter_table_add_constraint, bootstrap_catalog, create_database, create_table, current_generation, load_catalog,
+    parse_constraint, save_catalog, Database, Table, Column, DataType,
 };
+use storage_manager::config::StorageConfig;
+use storage_manager::error::RookError;
+use storage_manager::system_catalog::system_table_path;
 
 #[test]
 fn test_save_catalog() {
-    // Step 1: Ensure the catalog file exists (create if missing)
-    if !Path::new(CATALOG_FILE).exists() {
-        init_catalog();
+    // Step 1: Ensure the catalog system tables exist (create if missing)
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_save_catalog"));
+    if !config.control_file().exists() {
+        bootstrap_catalog(&config).unwrap();
     }
 
     // Step 2: Load catalog into memory
-    let mut catalog = load_catalog();
+    let mut catalog = load_catalog(&config).unwrap();
 
     // Step 3: Ensure a test database exists
     let db_name = "test_db";
     if !catalog.databases.contains_key(db_name) {
-        catalog.databases.insert(db_name.to_string(), Database { tables: Default::default() });
+        let oid = catalog.allocate_oid();
+        catalog.databases.insert(db_name.to_string(), Database { oid, tables: Default::default() });
     }
 
     // Step 4: Add a new test table entry inside the test database
-    let test_table = Table {
-        columns: vec![
-            Column { name: "id".to_string(), data_type: "INT".to_string() },
-            Column { name: "name".to_string(), data_type: "TEXT".to_string() },
-            Column { name: "email".to_string(), data_type: "TEXT".to_string() },
-        ],
-    };
+    let test_table = Table::new(vec![
+        Column::new("id", DataType::Int),
+        Column::new("name", DataType::Text),
+        Column::new("email", DataType::Text),
+    ]);
 
     let db = catalog.databases.get_mut(db_name).unwrap();
     db.tables.insert("users".to_string(), test_table);
 
     // Step 5: Save catalog back to disk
-    save_catalog(&catalog);
+    save_catalog(&catalog).unwrap();
 
     // Step 6: Reload catalog from disk and verify it contains the database and table
-    let reloaded_catalog = load_catalog();
+    let reloaded_catalog = load_catalog(&config).unwrap();
 
     assert!(
         reloaded_catalog.databases.contains_key(db_name),
@@ -60,8 +65,26 @@ fn test_save_catalog() {
         "Expected 3 columns in 'users' table"
     );
 
-    // Step 7: Clean up (optional)
-    if Path::new(CATALOG_FILE).exists() {
-        fs::remove_file(CATALOG_FILE).expect("Failed to clean up test catalog.json");
+    // Step 7: A save only writes the system tables that changed; the others are linked to the previous generation
+    let mut catalog = reloaded_catalog;
+    let db_name = "notes_db";
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(&mut catalog, db_name, "notes", vec![Column::new("body", DataType::Text)]).unwrap();
+    let dir = config.generation_dir(current_generation(&config).unwrap());
+    assert_eq!(fs::metadata(system_table_path(&dir, "rook_database")).unwrap().nlink(), 2);
+    assert_eq!(fs::metadata(system_table_path(&dir, "rook_table")).unwrap().nlink(), 1);
+
+    // Step 8: A constraint too large for a catalog page is refused, and later saves still work
+    let check = format!("CHECK (body <> '{}')", "x".repeat(9000));
+    let err = alter_table_add_constraint(&mut catalog, db_name, "notes", parse_constraint("notes", &check).unwrap()).unwrap_err();
+    assert!(matches!(err, RookError::InvalidDefinition(_)), "{}", err);
+    assert!(catalog.databases[db_name].tables["notes"].constraints.is_empty());
+    let check = parse_constraint("notes", "CHECK (body <> '')").unwrap();
+    alter_table_add_constraint(&mut catalog, db_name, "notes", check).unwrap();
+    assert_eq!(load_catalog(&config).unwrap().databases[db_name].tables["notes"].constraints.len(), 1);
+
+    // Step 9: Clean up (optional)
+    if config.root.exists() {
+        fs::remove_dir_all(&config.root).expect("Failed to clean up test catalog");
     }
 }
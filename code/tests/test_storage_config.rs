@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    bootstrap_catalog, create_database, create_table, load_catalog, Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+
+#[test]
+fn test_storage_config() {
+    // Step 1: The data directory comes from --data-dir in either form
+    let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        StorageConfig::from_args(&args(&["--data-dir", "/srv/rook"])).unwrap(),
+        StorageConfig::new("/srv/rook")
+    );
+    assert_eq!(
+        StorageConfig::from_args(&args(&["--data-dir=/srv/rook"])).unwrap().root,
+        Path::new("/srv/rook")
+    );
+    assert!(StorageConfig::from_args(&args(&["--data-dir"])).is_err());
+
+    // Step 2: Two instances in one process keep separate catalogs and table files
+    let tmp = Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_storage_config");
+    let _ = fs::remove_dir_all(&tmp);
+    let first = StorageConfig::new(tmp.join("first"));
+    let second = StorageConfig::new(tmp.join("second"));
+    bootstrap_catalog(&first).unwrap();
+    bootstrap_catalog(&second).unwrap();
+
+    let mut first_catalog = load_catalog(&first).unwrap();
+    let mut second_catalog = load_catalog(&second).unwrap();
+    create_database(&mut first_catalog, "shop").unwrap();
+    create_database(&mut second_catalog, "shop").unwrap();
+    create_table(&mut first_catalog, "shop", "items", vec![Column::new("id", DataType::Int)]).unwrap();
+
+    assert!(first_catalog.table_file("shop", "items").unwrap().starts_with(&first.root));
+    assert!(first_catalog.table_file("shop", "items").unwrap().exists());
+    assert_eq!(fs::read_dir(second_catalog.database_dir("shop").unwrap()).unwrap().count(), 0);
+    assert!(load_catalog(&first).unwrap().databases["shop"].tables.contains_key("items"));
+    assert!(load_catalog(&second).unwrap().databases["shop"].tables.is_empty());
+
+    // Step 3: Buffer managers read table files from their own instance
+    let mut buffer_manager = BufferManager::new();
+    assert!(buffer_manager.load_table_on_create(&second_catalog, "shop", "items").is_err());
+    buffer_manager.load_table_on_create(&first_catalog, "shop", "items").unwrap();
+
+    // Step 4: Clean up
+    fs::remove_dir_all(&tmp).unwrap();
+}
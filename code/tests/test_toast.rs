@@ -0,0 +1,130 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    alter_table_rename, create_database, create_table, drop_database, drop_table, bootstrap_catalog,
+    load_catalog, Column, DataType, StorageStrategy,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::disk::read_page;
+use storage_manager::page::{
+    delete_row, load_csv_and_insert, page_count, scan_rows, scan_tuples, update_row, Page,
+    ITEM_ID_SIZE, PAGE_HEADER_SIZE,
+};
+use storage_manager::toast::TOAST_TUPLE_THRESHOLD;
+use storage_manager::value::Value;
+
+/// Lengths of the live (not deleted) tuples of a table or TOAST file.
+fn live_tuple_lengths(path: &Path) -> Vec<u32> {
+    let mut file = OpenOptions::new().read(true).open(path).unwrap();
+    let mut lengths = Vec::new();
+    for page_num in 1..page_count(&mut file).unwrap() {
+        let mut page = Page::new();
+        read_page(&mut file, &mut page, page_num).unwrap();
+        let lower = u32::from_le_bytes(page.data[0..4].try_into().unwrap());
+        for i in 0..(lower - PAGE_HEADER_SIZE) / ITEM_ID_SIZE {
+            let base = (PAGE_HEADER_SIZE + i * ITEM_ID_SIZE) as usize;
+            let length = u32::from_le_bytes(page.data[base + 4..base + 8].try_into().unwrap());
+            if length > 0 {
+                lengths.push(length);
+            }
+        }
+    }
+    lengths
+}
+
+#[test]
+fn test_toast() {
+    let db_name = "test_toast_db";
+    let table_name = "documents";
+    let csv_path = "test_toast.csv";
+
+    // Step 1: Create a table with variable-length columns stored out of line uncompressed
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_toast"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    let mut title = Column::new("title", DataType::Text);
+    title.storage = StorageStrategy::External;
+    let mut body = Column::new("body", DataType::Text);
+    body.storage = StorageStrategy::External;
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        table_name,
+        vec![Column::new("id", DataType::Int), title, body],
+    ).unwrap();
+
+    let table_path = catalog.table_file(db_name, table_name).unwrap();
+    let toast_path = catalog.toast_file(db_name, table_name).unwrap();
+
+    // Step 2: Load a small row, a row larger than a page and a row with two medium values
+    let huge = "lorem-ipsum.".repeat(3000); // 36 KB
+    let medium = "m".repeat(1500);
+    fs::write(
+        csv_path,
+        format!("id,title,body\n1,short,tiny\n2,huge,{}\n3,{},{}\n", huge, medium, medium),
+    )
+    .unwrap();
+
+    let mut file = OpenOptions::new().read(true).write(true).open(&table_path).unwrap();
+    assert!(!Path::new(&toast_path).exists(), "TOAST file is created on first use");
+    load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &CsvOptions::default()).unwrap();
+
+    // Main tuples stay small; the large values live in the TOAST file
+    let main_lengths = live_tuple_lengths(&table_path);
+    assert_eq!(main_lengths.len(), 3);
+    assert!(main_lengths.iter().all(|&len| len as usize <= TOAST_TUPLE_THRESHOLD));
+    assert!(live_tuple_lengths(&toast_path).len() >= 19, "36 KB + 1.5 KB in 2000-byte chunks");
+    load_catalog(&config).expect("the TOAST file keeps the file format version");
+
+    // Step 3: Values are detoasted transparently on read
+    let rows = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap();
+    assert_eq!(rows[0][2], Value::Text("tiny".to_string()));
+    assert_eq!(rows[1][2], Value::Text(huge.clone()));
+    assert_eq!(rows[2][1], Value::Text(medium.clone()));
+    assert_eq!(rows[2][2], Value::Text(medium.clone()));
+
+    // Step 4: Deleting rows removes their TOAST chunks
+    delete_row(&catalog, db_name, table_name, &mut file, 1, 1).unwrap();
+    assert!(delete_row(&catalog, db_name, table_name, &mut file, 1, 1).is_err());
+    let rows = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1][2], Value::Text(medium));
+
+    delete_row(&catalog, db_name, table_name, &mut file, 1, 2).unwrap();
+    assert!(live_tuple_lengths(&toast_path).is_empty());
+
+    // Step 5: An update whose new version cannot be written keeps the old row,
+    // and frees the TOAST chunks it had written for the new one
+    let mut read_only = OpenOptions::new().read(true).open(&table_path).unwrap();
+    let (tuple, _) = scan_rows(&catalog, db_name, table_name, &mut file).unwrap().remove(0);
+    assert!(update_row(&catalog, db_name, table_name, &mut read_only, tuple, &["1", "short", &huge]).is_err());
+    let rows = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap();
+    assert_eq!(rows, vec![vec![Value::Int(1), Value::Text("short".to_string()), Value::Text("tiny".to_string())]]);
+    assert!(live_tuple_lengths(&toast_path).is_empty());
+
+    // A successful update stores the new version, out of line
+    update_row(&catalog, db_name, table_name, &mut file, tuple, &["1", "short", &huge]).unwrap();
+    assert_eq!(scan_tuples(&catalog, db_name, table_name, &mut file).unwrap()[0][2], Value::Text(huge));
+    assert!(!live_tuple_lengths(&toast_path).is_empty());
+
+    // Step 6: The TOAST file stays with the table on rename and goes with it on drop
+    drop(file);
+    alter_table_rename(&mut catalog, &mut buffer_manager, db_name, table_name, "docs").unwrap();
+    assert_eq!(catalog.toast_file(db_name, "docs").unwrap(), toast_path);
+    assert!(Path::new(&toast_path).exists());
+    drop_table(&mut catalog, &mut buffer_manager, db_name, "docs").unwrap();
+    assert!(!Path::new(&toast_path).exists());
+
+    // Step 7: Clean up
+    let _ = fs::remove_file(csv_path);
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+}
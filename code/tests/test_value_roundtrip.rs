@@ -0,0 +1,66 @@
+use storage_manager::catalog::DataType;
+use storage_manager::value::{decode_value, encode_value, fixed_size, parse_value, Value};
+
+/// Parses `raw`, encodes it, decodes it back and returns the decoded value.
+fn roundtrip(data_type: DataType, raw: &str) -> Value {
+    let value = parse_value(data_type, raw).expect("Failed to parse value");
+
+    let mut bytes = Vec::new();
+    encode_value(&value, &mut bytes);
+    let expected_size = fixed_size(data_type).unwrap_or(4 + raw.len());
+    assert_eq!(bytes.len(), expected_size, "Encoded size mismatch for {}", data_type);
+
+    let mut cursor = 0;
+    let decoded = decode_value(data_type, &bytes, &mut cursor).expect("Failed to decode value");
+    assert_eq!(cursor, bytes.len(), "Decoder did not consume the whole value");
+    assert_eq!(decoded, value, "Round trip changed the value for {}", data_type);
+    decoded
+}
+
+#[test]
+fn test_value_roundtrip() {
+    // Integers, including the extremes of each width
+    assert_eq!(roundtrip(DataType::SmallInt, "-32768"), Value::SmallInt(i16::MIN));
+    assert_eq!(roundtrip(DataType::Int, "2147483647"), Value::Int(i32::MAX));
+    assert_eq!(roundtrip(DataType::BigInt, "9007199254740993"), Value::BigInt(9007199254740993));
+    assert_eq!(roundtrip(DataType::BigInt, "-9223372036854775808"), Value::BigInt(i64::MIN));
+
+    // Floating point
+    assert_eq!(roundtrip(DataType::Float, "3.5"), Value::Float(3.5));
+    assert_eq!(roundtrip(DataType::Float, "0.1").to_string(), "0.1");
+    assert_eq!(roundtrip(DataType::Double, "-0.000123"), Value::Double(-0.000123));
+    assert_eq!(roundtrip(DataType::Double, "1e300").to_string(), "1e300");
+
+    // Booleans in their common spellings
+    assert_eq!(roundtrip(DataType::Boolean, "TRUE"), Value::Boolean(true));
+    assert_eq!(roundtrip(DataType::Boolean, "f"), Value::Boolean(false));
+    assert_eq!(roundtrip(DataType::Boolean, "1").to_string(), "true");
+
+    // Decimals keep their exact digits
+    let money = DataType::Decimal { precision: 12, scale: 2 };
+    assert_eq!(roundtrip(money, "1234567.89").to_string(), "1234567.89");
+    assert_eq!(roundtrip(money, "-0.5").to_string(), "-0.50");
+
+    // Date and time types
+    assert_eq!(roundtrip(DataType::Date, "2024-02-29").to_string(), "2024-02-29");
+    assert_eq!(roundtrip(DataType::Time, "23:59:59.999999").to_string(), "23:59:59.999999");
+    assert_eq!(
+        roundtrip(DataType::Timestamp, "1900-01-01T00:00:00").to_string(),
+        "1900-01-01 00:00:00"
+    );
+    assert_eq!(
+        roundtrip(DataType::TimestampTz, "2024-01-01T00:00:00+05:30").to_string(),
+        "2023-12-31 18:30:00+00:00"
+    );
+    assert_eq!(roundtrip(DataType::Interval, "P1Y2M3DT4H").to_string(), "P1Y2M3DT4H");
+
+    // Text
+    assert_eq!(roundtrip(DataType::Text, "alice"), Value::Text("alice".to_string()));
+
+    // Invalid input is rejected instead of being stored as a default
+    assert!(parse_value(DataType::SmallInt, "40000").is_err());
+    assert!(parse_value(DataType::BigInt, "12abc").is_err());
+    assert!(parse_value(DataType::Double, "fast").is_err());
+    assert!(parse_value(DataType::Boolean, "maybe").is_err());
+    assert!(parse_value(DataType::Date, "2024/01/01").is_err());
+}
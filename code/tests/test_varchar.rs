@@ -0,0 +1,79 @@
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use storage_manager::buffer::BufferManager;
+use storage_manager::catalog::{
+    create_database, create_table, drop_database, bootstrap_catalog, load_catalog, Column, DataType,
+};
+use storage_manager::config::StorageConfig;
+use storage_manager::csv_import::CsvOptions;
+use storage_manager::page::{load_csv_and_insert, scan_tuples};
+use storage_manager::value::{decode_value, encode_value, parse_value, Value};
+
+#[test]
+fn test_varchar() {
+    // Step 1: Limits are counted in characters, not bytes
+    assert!(parse_value(DataType::Varchar(5), "héllo").is_ok());
+    assert!(parse_value(DataType::Varchar(5), "héllo!").is_err());
+    assert_eq!(
+        parse_value(DataType::Char(5), "ab   ").unwrap(),
+        Value::Text("ab   ".to_string())
+    );
+    assert_eq!(parse_value(DataType::Char(3), "x").unwrap().to_string(), "'x  '");
+    assert!(parse_value(DataType::Char(2), "abc").is_err());
+
+    // Step 2: Invalid UTF-8 is reported instead of being replaced
+    let mut bytes = Vec::new();
+    encode_value(&Value::Text("ok".to_string()), &mut bytes);
+    bytes[4] = 0xFF;
+    assert!(decode_value(DataType::Text, &bytes, &mut 0).is_err());
+
+    // Step 3: Load values far longer than the old fixed 10-byte slot
+    let db_name = "test_varchar_db";
+    let table_name = "users";
+    let csv_path = "test_varchar.csv";
+
+    let config = StorageConfig::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_varchar"));
+    let _ = fs::remove_dir_all(&config.root);
+    bootstrap_catalog(&config).unwrap();
+    let mut catalog = load_catalog(&config).unwrap();
+    let mut buffer_manager = BufferManager::new();
+    let _ = drop_database(&mut catalog, &mut buffer_manager, db_name);
+
+    create_database(&mut catalog, db_name).unwrap();
+    create_table(
+        &mut catalog,
+        db_name,
+        table_name,
+        vec![
+            Column::new("email", DataType::Varchar(40)),
+            Column::new("bio", DataType::Text),
+            Column::new("code", DataType::Char(4)),
+        ],
+    ).unwrap();
+
+    let long_bio = "ü".repeat(1000);
+    let mut csv = b"email,bio,code\n".to_vec();
+    csv.extend_from_slice(format!("alice.longname@example.com,{},ab\n", long_bio).as_bytes());
+    csv.extend_from_slice(b"bad\xFFrow@example.com,x,y\n");
+    csv.extend_from_slice(format!("{}@example.com,x,y\n", "b".repeat(40)).as_bytes());
+    csv.extend_from_slice(b"carol@example.com,,z\r\n");
+    fs::write(csv_path, csv).unwrap();
+
+    let table_path = catalog.table_file(db_name, table_name).unwrap();
+    let mut file = OpenOptions::new().read(true).write(true).open(&table_path).unwrap();
+    load_csv_and_insert(&catalog, db_name, table_name, &mut file, csv_path, &CsvOptions::default()).unwrap();
+
+    // Step 4: Invalid UTF-8 and overlong VARCHAR rows are skipped, the rest round-trip
+    let rows = scan_tuples(&catalog, db_name, table_name, &mut file).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0][0], Value::Text("alice.longname@example.com".to_string()));
+    assert_eq!(rows[0][1], Value::Text(long_bio));
+    assert_eq!(rows[0][2], Value::Text("ab  ".to_string()));
+    assert_eq!(rows[1][0], Value::Text("carol@example.com".to_string()));
+    assert_eq!(rows[1][2], Value::Text("z   ".to_string()));
+
+    // Step 5: Clean up
+    let _ = fs::remove_file(csv_path);
+    drop_database(&mut catalog, &mut buffer_manager, db_name).unwrap();
+}